@@ -0,0 +1,141 @@
+use crate::keybind::Keybind;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// On-disk JSON cache of discovered keybinds.
+///
+/// An entry is reused while it is younger than the TTL and the mtime of the
+/// config it was built from hasn't changed. Times are passed in by the caller
+/// so the validity rules can be tested without touching the clock.
+pub struct Cache {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    created: SystemTime,
+    config_mtime: Option<SystemTime>,
+    keybinds: Vec<Keybind>,
+}
+
+impl Cache {
+    pub fn new(path: PathBuf, ttl: Duration) -> Self {
+        Self { path, ttl }
+    }
+
+    /// Default cache directory: `$XDG_CACHE_HOME/fzf-keys`, else `$HOME/.cache/fzf-keys`.
+    pub fn default_dir() -> Option<PathBuf> {
+        if let Ok(cache_home) = std::env::var("XDG_CACHE_HOME")
+            && !cache_home.is_empty()
+        {
+            return Some(PathBuf::from(cache_home).join("fzf-keys"));
+        }
+
+        std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".cache/fzf-keys"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the cached keybinds if the entry is still valid at `now`.
+    pub fn load(&self, config_mtime: Option<SystemTime>, now: SystemTime) -> Option<Vec<Keybind>> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+
+        if entry.config_mtime != config_mtime {
+            return None;
+        }
+
+        // A creation time in the future means the clock moved; treat it as stale
+        let age = now.duration_since(entry.created).ok()?;
+        if age > self.ttl {
+            return None;
+        }
+
+        Some(entry.keybinds)
+    }
+
+    pub fn store(
+        &self,
+        keybinds: &[Keybind],
+        config_mtime: Option<SystemTime>,
+        now: SystemTime,
+    ) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let entry = CacheEntry {
+            created: now,
+            config_mtime,
+            keybinds: keybinds.to_vec(),
+        };
+        let content = serde_json::to_string(&entry).map_err(io::Error::other)?;
+
+        fs::write(&self.path, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keybind::Modifier;
+
+    fn temp_cache(name: &str, ttl: Duration) -> Cache {
+        let path = std::env::temp_dir()
+            .join(format!("fzf-keys-cache-test-{}", std::process::id()))
+            .join(format!("{}.json", name));
+        let _ = fs::remove_file(&path);
+        Cache::new(path, ttl)
+    }
+
+    fn sample_keybinds() -> Vec<Keybind> {
+        vec![
+            Keybind::new("kitty", "t")
+                .modifiers(vec![Modifier::Ctrl, Modifier::Shift])
+                .action("new_tab"),
+        ]
+    }
+
+    #[test]
+    fn test_cache_hit_within_ttl() {
+        let cache = temp_cache("hit", Duration::from_secs(60));
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(2_000);
+
+        cache.store(&sample_keybinds(), Some(mtime), now).unwrap();
+
+        let loaded = cache.load(Some(mtime), now + Duration::from_secs(30));
+        assert_eq!(loaded, Some(sample_keybinds()));
+    }
+
+    #[test]
+    fn test_cache_miss_on_changed_mtime() {
+        let cache = temp_cache("mtime", Duration::from_secs(60));
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(2_000);
+
+        cache.store(&sample_keybinds(), Some(mtime), now).unwrap();
+
+        let touched = mtime + Duration::from_secs(5);
+        assert_eq!(cache.load(Some(touched), now), None);
+    }
+
+    #[test]
+    fn test_cache_miss_on_expired_ttl() {
+        let cache = temp_cache("ttl", Duration::from_secs(60));
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(2_000);
+
+        cache.store(&sample_keybinds(), Some(mtime), now).unwrap();
+
+        assert_eq!(cache.load(Some(mtime), now + Duration::from_secs(61)), None);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::format::OutputFormat;
+use crate::keybind::{Program, SortKey};
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Persistent defaults read from `~/.config/fzf-keys/config.toml`.
+///
+/// Every setting is optional, and command-line flags take precedence over it:
+///
+/// ```toml
+/// format = "columns"
+/// sort = "action"
+///
+/// [sources]
+/// kitty = true
+/// emacs-config = "/home/me/.emacs.d/init.el"
+///
+/// [filters]
+/// exclude-programs = ["dunst"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    pub format: Option<OutputFormat>,
+    pub sort: Option<SortKey>,
+    pub sources: SourcesConfig,
+    pub filters: FiltersConfig,
+}
+
+/// Which sources to read when none are given on the command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SourcesConfig {
+    /// Query niri; by default it's queried only when no other source is requested.
+    pub niri: Option<bool>,
+    pub niri_config: Option<PathBuf>,
+    pub kitty: Option<bool>,
+    pub emacs_config: Option<PathBuf>,
+    pub qtile_config: Option<PathBuf>,
+    pub dunst_config: Option<PathBuf>,
+    pub windows_terminal_config: Option<PathBuf>,
+    pub dwm_config: Option<PathBuf>,
+    pub awesome_config: Option<PathBuf>,
+    pub kakoune_config: Option<PathBuf>,
+    pub keyd_config: Option<PathBuf>,
+}
+
+/// Filters applied to every listing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FiltersConfig {
+    /// Only list these programs; empty lists every program.
+    pub programs: Vec<Program>,
+    /// Never list these programs.
+    pub exclude_programs: Vec<Program>,
+    pub with_cooldown_only: Option<bool>,
+    pub respect_overlay_hidden: Option<bool>,
+    pub has_property: Vec<String>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/fzf-keys/config.toml`, or `~/.config/fzf-keys/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Ok(config_home) = std::env::var("XDG_CONFIG_HOME")
+            && !config_home.is_empty()
+        {
+            return Some(PathBuf::from(config_home).join("fzf-keys/config.toml"));
+        }
+
+        std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".config/fzf-keys/config.toml"))
+    }
+
+    /// Reads the config at `path`; a missing file gives the defaults.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Self::parse(&content)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e).into()),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            r#"
+format = "columns"
+sort = "action"
+
+[sources]
+niri = false
+kitty = true
+qtile-config = "/tmp/config.py"
+
+[filters]
+exclude-programs = ["dunst"]
+has-property = ["cooldown"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.format, Some(OutputFormat::Columns));
+        assert_eq!(config.sort, Some(SortKey::Action));
+        assert_eq!(config.sources.niri, Some(false));
+        assert_eq!(config.sources.kitty, Some(true));
+        assert_eq!(
+            config.sources.qtile_config,
+            Some(PathBuf::from("/tmp/config.py"))
+        );
+        assert_eq!(config.filters.exclude_programs, vec![Program::Dunst]);
+        assert_eq!(config.filters.has_property, vec!["cooldown"]);
+        assert!(config.filters.programs.is_empty());
+    }
+
+    #[test]
+    fn test_parse_empty_config() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_keys() {
+        assert!(Config::parse("fromat = \"columns\"").is_err());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let config = Config::load(Path::new("/nonexistent/fzf-keys/config.toml")).unwrap();
+        assert_eq!(config, Config::default());
+    }
+}
@@ -0,0 +1,274 @@
+use crate::error::SourceError;
+use crate::keybind::Keybind;
+use crate::source::Source;
+use crate::sources::awesome::AwesomeSource;
+use crate::sources::command::CommandSource;
+use crate::sources::dunst::DunstSource;
+use crate::sources::dwm::DwmSource;
+use crate::sources::emacs::EmacsSource;
+use crate::sources::kak::KakouneSource;
+use crate::sources::keyd::KeydSource;
+use crate::sources::kitty::KittySource;
+use crate::sources::niri::NiriSource;
+use crate::sources::qtile::QtileSource;
+use crate::sources::wt::WindowsTerminalSource;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long cached kitty keybinds are reused before Python is consulted again
+pub const KITTY_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Which sources [`discover_all`] should query, and where their configs live.
+///
+/// Sources are off by default; a config path both enables a source and says
+/// where to read it from.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoverOptions {
+    /// Query niri, reading `niri_configs` or the default config location.
+    pub niri: bool,
+    /// Niri configs to merge, in order; empty means the default config.
+    pub niri_configs: Vec<PathBuf>,
+    /// Describe niri binds without a `hotkey-overlay-title` from known action names.
+    pub describe_actions: bool,
+    /// Write numeric niri arguments like `"+10%"` without quotes.
+    pub bare_numeric_args: bool,
+    /// Keep niri binds without an action, labeled `unknown`.
+    pub include_empty_action: bool,
+    /// Write niri's `Mod` as `Super`, so it matches other programs' `Super`.
+    pub mod_is_super: bool,
+    /// Note on each niri bind when its config file was last modified.
+    pub show_mtime: bool,
+    pub kitty: bool,
+    /// Where to cache kitty keybinds; `None` always queries kitty.
+    pub kitty_cache: Option<PathBuf>,
+    /// Keep kitty mappings that unbind a key (`no_op`).
+    pub show_disabled: bool,
+    /// Describe kitty keybinds as `added`, `changed` or `default`.
+    pub kitty_diff: bool,
+    /// List each action of a kitty `combine` mapping separately.
+    pub split_combined: bool,
+    pub emacs_config: Option<PathBuf>,
+    pub qtile_config: Option<PathBuf>,
+    pub dunst_config: Option<PathBuf>,
+    pub windows_terminal_config: Option<PathBuf>,
+    pub dwm_config: Option<PathBuf>,
+    pub awesome_config: Option<PathBuf>,
+    pub kakoune_config: Option<PathBuf>,
+    pub keyd_config: Option<PathBuf>,
+    /// Commands printing keybinds as NDJSON, one source each.
+    pub source_commands: Vec<String>,
+}
+
+impl DiscoverOptions {
+    /// Number of sources these options ask for.
+    pub fn source_count(&self) -> usize {
+        [
+            self.niri,
+            self.kitty,
+            self.emacs_config.is_some(),
+            self.qtile_config.is_some(),
+            self.dunst_config.is_some(),
+            self.windows_terminal_config.is_some(),
+            self.dwm_config.is_some(),
+            self.awesome_config.is_some(),
+            self.kakoune_config.is_some(),
+            self.keyd_config.is_some(),
+        ]
+        .iter()
+        .filter(|&&requested| requested)
+        .count()
+            + self.source_commands.len()
+    }
+}
+
+/// How long one source took to discover its keybinds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceTiming {
+    pub name: String,
+    pub duration: Duration,
+    /// Number of keybinds the source yielded.
+    pub count: usize,
+}
+
+/// What happened while running the requested sources.
+#[derive(Debug, Default)]
+pub struct DiscoverReport {
+    /// Sources that failed, by name.
+    pub errors: Vec<(String, SourceError)>,
+    /// One entry per source that ran, in the order they ran.
+    pub timings: Vec<SourceTiming>,
+    /// Things worth knowing that aren't errors, such as a chord bound in two
+    /// merged niri configs.
+    pub notes: Vec<String>,
+}
+
+/// Discovers keybinds from every source requested in `opts`.
+///
+/// A failing source doesn't stop the others; its error is returned alongside
+/// the name of the source.
+pub fn discover_all(opts: &DiscoverOptions) -> (Vec<Keybind>, Vec<(String, SourceError)>) {
+    let mut keybinds = Vec::new();
+    let report = discover_all_into(opts, &mut |keybind| keybinds.push(keybind));
+    (keybinds, report.errors)
+}
+
+/// Runs `source`, passing its keybinds to `sink` and measuring how long it took.
+pub fn timed_discover(
+    source: &dyn Source<Item = Keybind>,
+    sink: &mut dyn FnMut(Keybind),
+) -> (SourceTiming, Result<(), SourceError>) {
+    let mut count = 0;
+    let start = Instant::now();
+    let result = source.discover_into(&mut |keybind| {
+        count += 1;
+        sink(keybind);
+    });
+
+    let timing = SourceTiming {
+        name: source.name().to_string(),
+        duration: start.elapsed(),
+        count,
+    };
+    (timing, result)
+}
+
+/// The niri source `opts` ask for, with its config paths resolved.
+///
+/// Explicit configs come first; otherwise the default config is looked up,
+/// as described on [`NiriSource::from_default_config`].
+pub fn niri_source(opts: &DiscoverOptions) -> Result<NiriSource, Box<dyn std::error::Error>> {
+    let niri_source = match opts.niri_configs.split_first() {
+        Some((config_path, extra)) => {
+            NiriSource::new(config_path.clone()).with_extra_configs(extra.to_vec())
+        }
+        None => NiriSource::from_default_config()?,
+    };
+
+    Ok(niri_source
+        .with_action_descriptions(opts.describe_actions)
+        .with_bare_numeric_args(opts.bare_numeric_args)
+        .with_include_empty_action(opts.include_empty_action)
+        .with_mtime(opts.show_mtime))
+}
+
+/// The sources requested in `opts` that read one config file, with that file.
+///
+/// These are every source but niri, kitty and external commands.
+pub fn config_file_sources(
+    opts: &DiscoverOptions,
+) -> Vec<(Box<dyn Source<Item = Keybind>>, &Path)> {
+    type NewSource = fn(PathBuf) -> Box<dyn Source<Item = Keybind>>;
+    let sources: [(&Option<PathBuf>, NewSource); 8] = [
+        (&opts.emacs_config, |path| Box::new(EmacsSource::new(path))),
+        (&opts.qtile_config, |path| Box::new(QtileSource::new(path))),
+        (&opts.dunst_config, |path| Box::new(DunstSource::new(path))),
+        (&opts.windows_terminal_config, |path| {
+            Box::new(WindowsTerminalSource::new(path))
+        }),
+        (&opts.dwm_config, |path| Box::new(DwmSource::new(path))),
+        (&opts.awesome_config, |path| {
+            Box::new(AwesomeSource::new(path))
+        }),
+        (&opts.kakoune_config, |path| {
+            Box::new(KakouneSource::new(path))
+        }),
+        (&opts.keyd_config, |path| Box::new(KeydSource::new(path))),
+    ];
+
+    sources
+        .into_iter()
+        .filter_map(|(path, new_source)| {
+            let path = path.as_deref()?;
+            Some((new_source(path.to_path_buf()), path))
+        })
+        .collect()
+}
+
+/// Like [`discover_all`], but passes each keybind to `sink` as its source yields it.
+pub fn discover_all_into(opts: &DiscoverOptions, sink: &mut dyn FnMut(Keybind)) -> DiscoverReport {
+    let mut report = DiscoverReport::default();
+    let mut canonicalize = |keybind: Keybind| {
+        sink(if opts.mod_is_super {
+            keybind.with_mod_as_super()
+        } else {
+            keybind
+        })
+    };
+    let sink: &mut dyn FnMut(Keybind) = &mut canonicalize;
+    let mut run = |source: &dyn Source<Item = Keybind>, report: &mut DiscoverReport| {
+        let (timing, result) = timed_discover(source, sink);
+        if let Err(e) = result {
+            report.errors.push((timing.name.clone(), e));
+        }
+        report.timings.push(timing);
+    };
+
+    if opts.niri {
+        match niri_source(opts) {
+            Ok(niri_source) => {
+                run(&niri_source, &mut report);
+                report.notes.extend(niri_source.shadowing_notes());
+            }
+            Err(e) => report
+                .errors
+                .push(("niri".to_string(), SourceError::Unavailable(e.to_string()))),
+        }
+    }
+
+    if opts.kitty {
+        let kitty_source = match &opts.kitty_cache {
+            Some(path) => KittySource::new_with_cache(path.clone(), KITTY_CACHE_TTL),
+            None => KittySource::new(),
+        };
+        run(
+            &kitty_source
+                .with_show_disabled(opts.show_disabled)
+                .with_diff(opts.kitty_diff)
+                .with_split_combined(opts.split_combined),
+            &mut report,
+        );
+    }
+
+    for (source, _) in config_file_sources(opts) {
+        run(source.as_ref(), &mut report);
+    }
+
+    for command in &opts.source_commands {
+        run(&CommandSource::new(command.clone()), &mut report);
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    struct SlowSource;
+
+    impl Source for SlowSource {
+        type Item = Keybind;
+
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+            thread::sleep(Duration::from_millis(20));
+            Ok(vec![Keybind::new("slow", "A"), Keybind::new("slow", "B")])
+        }
+    }
+
+    #[test]
+    fn test_timed_discover() {
+        let mut received = Vec::new();
+        let (timing, result) = timed_discover(&SlowSource, &mut |keybind| received.push(keybind));
+
+        assert!(result.is_ok());
+        assert_eq!(timing.name, "slow");
+        assert_eq!(timing.count, 2);
+        assert!(timing.duration >= Duration::from_millis(20));
+        assert_eq!(received.len(), 2);
+    }
+}
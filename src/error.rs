@@ -0,0 +1,80 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Errors a [`Source`](crate::source::Source) can report from discovery.
+#[derive(Debug)]
+pub enum SourceError {
+    /// The config file the source reads does not exist.
+    NotFound(PathBuf),
+    /// The config exists but could not be read.
+    Io(io::Error),
+    /// The config was read but its contents could not be parsed.
+    Parse(String),
+    /// The program (or its runtime, e.g. kitty's Python modules) isn't available.
+    Unavailable(String),
+}
+
+impl SourceError {
+    /// Wraps an I/O error from reading `path`, turning "not found" into [`SourceError::NotFound`].
+    pub fn from_io(path: &Path, error: io::Error) -> Self {
+        if error.kind() == io::ErrorKind::NotFound {
+            SourceError::NotFound(path.to_path_buf())
+        } else {
+            SourceError::Io(error)
+        }
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::NotFound(path) => write!(f, "config not found: {}", path.display()),
+            SourceError::Io(e) => write!(f, "I/O error: {}", e),
+            SourceError::Parse(msg) => write!(f, "parse error: {}", msg),
+            SourceError::Unavailable(msg) => write!(f, "source unavailable: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SourceError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SourceError {
+    fn from(error: io::Error) -> Self {
+        SourceError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_io_not_found() {
+        let error = io::Error::from(io::ErrorKind::NotFound);
+        let path = Path::new("/nonexistent/config.kdl");
+
+        assert!(matches!(
+            SourceError::from_io(path, error),
+            SourceError::NotFound(p) if p == path
+        ));
+    }
+
+    #[test]
+    fn test_from_io_other() {
+        let error = io::Error::from(io::ErrorKind::PermissionDenied);
+        let path = Path::new("/root/config.kdl");
+
+        assert!(matches!(
+            SourceError::from_io(path, error),
+            SourceError::Io(_)
+        ));
+    }
+}
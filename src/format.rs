@@ -0,0 +1,536 @@
+use crate::keybind::{Chord, Keybind, MergedKeybind, Modifier, Program};
+use crate::sources::niri::NiriSource;
+use serde::Deserialize;
+use unicode_width::UnicodeWidthStr;
+
+/// How keybinds are written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// One `Display` line per keybind, for piping into fzf
+    #[default]
+    Plain,
+    /// Keys, action and program in aligned columns, for reading in a terminal
+    Columns,
+    /// `program<TAB>index<TAB>line`, so fzf can hide the first two fields and pass them to `preview`
+    Tab,
+    /// One JSON-serialized keybind per line, written as sources yield them
+    Ndjson,
+    /// A single JSON array of every keybind, on one line unless `--pretty` is given
+    Json,
+    /// A `[Desktop Action ...]` block per keybind, for app launchers reading `.desktop` files
+    Desktop,
+}
+
+/// The keybind's `Display` line, optionally without the trailing `[program]` tag.
+///
+/// Dropping the tag is useful when listing a single source, where it's the same on every line.
+///
+/// Tabs, newlines and other control characters become spaces, so an action
+/// like `spawn "sh" "-c" "printf 'a\tb'"` can't split the line into extra fzf
+/// fields or lines.
+pub fn plain_line(keybind: &Keybind, program_tag: bool) -> String {
+    strip_program_tag(
+        sanitize_controls(keybind.to_string()),
+        keybind.program.as_str(),
+        program_tag,
+    )
+}
+
+/// Like [`plain_line`], for keybinds merged by [`merge_by_action`](crate::keybind::merge_by_action).
+pub fn merged_line(merged: &MergedKeybind, program_tag: bool) -> String {
+    strip_program_tag(
+        sanitize_controls(merged.to_string()),
+        merged.program.as_str(),
+        program_tag,
+    )
+}
+
+fn sanitize_controls(line: String) -> String {
+    if !line.contains(char::is_control) {
+        return line;
+    }
+    line.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+/// A tab-delimited line carrying the keybind's program and its index among
+/// that program's keybinds, as understood by `fzf-keys preview`.
+pub fn tab_line(keybind: &Keybind, index: usize, program_tag: bool) -> String {
+    format!(
+        "{}\t{}\t{}",
+        keybind.program,
+        index,
+        plain_line(keybind, program_tag)
+    )
+}
+
+/// The keybind serialized as a single line of JSON, for `--format ndjson`.
+pub fn ndjson_line(keybind: &Keybind) -> String {
+    serde_json::to_string(keybind).unwrap_or_default()
+}
+
+/// The keybind as a desktop entry action, for `--format desktop`:
+///
+/// ```text
+/// [Desktop Action niri-0]
+/// Name=Open a Terminal
+/// Exec=alacritty
+/// X-Keys=Mod+T
+/// ```
+///
+/// The action is named after the program and the keybind's `index` among its
+/// keybinds, and its `Name` is the description, or the action if there's none.
+/// Only niri's `spawn` and `spawn-sh` binds run a command, so only they get an
+/// `Exec` line. Blocks end with a blank line, to separate them.
+pub fn desktop_action(keybind: &Keybind, index: usize) -> String {
+    let id: String = keybind
+        .program
+        .as_str()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let mut lines = vec![
+        format!("[Desktop Action {}-{}]", id, index),
+        format!(
+            "Name={}",
+            desktop_value(keybind.description.as_deref().unwrap_or(&keybind.action))
+        ),
+    ];
+    if keybind.program == Program::Niri
+        && let Some(argv) = NiriSource::spawn_argv(keybind)
+    {
+        let args: Vec<String> = argv.iter().map(|arg| desktop_exec_arg(arg)).collect();
+        lines.push(format!("Exec={}", desktop_value(&args.join(" "))));
+    }
+    lines.push(format!("X-Keys={}", desktop_value(&keybind.keys_string())));
+
+    lines.join("\n") + "\n"
+}
+
+/// Escapes a desktop entry string value, which is one line with `\` escapes.
+fn desktop_value(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('\n', r"\n")
+        .replace('\t', r"\t")
+        .replace('\r', r"\r")
+}
+
+/// Quotes an `Exec` argument if it has characters the spec reserves, and
+/// doubles `%` so it isn't read as a field code like `%f`.
+fn desktop_exec_arg(arg: &str) -> String {
+    const RESERVED: &[char] = &[
+        ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(',
+        ')', '`',
+    ];
+    let arg = arg.replace('%', "%%");
+    if !arg.is_empty() && !arg.contains(RESERVED) {
+        return arg;
+    }
+    let mut quoted = String::from('"');
+    for c in arg.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The keybinds serialized as one JSON array, for `--format json`.
+///
+/// Compact output is a single line; `pretty` indents it across several lines for reading.
+pub fn json(keybinds: &[Keybind], pretty: bool) -> String {
+    let json = if pretty {
+        serde_json::to_string_pretty(keybinds)
+    } else {
+        serde_json::to_string(keybinds)
+    };
+    json.unwrap_or_default()
+}
+
+/// Reads keybinds written by `--format json` or `--format ndjson`, e.g. to
+/// restore a saved listing.
+///
+/// The input may hold any mix of JSON arrays of keybinds and single keybinds.
+pub fn load_json(reader: impl std::io::Read) -> Result<Vec<Keybind>, serde_json::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Many(Vec<Keybind>),
+        One(Box<Keybind>),
+    }
+
+    let mut keybinds = Vec::new();
+    for entry in serde_json::Deserializer::from_reader(reader).into_iter::<Entry>() {
+        match entry? {
+            Entry::Many(many) => keybinds.extend(many),
+            Entry::One(one) => keybinds.push(*one),
+        }
+    }
+    Ok(keybinds)
+}
+
+fn strip_program_tag(line: String, program: &str, program_tag: bool) -> String {
+    if program_tag {
+        return line;
+    }
+
+    let tag = format!(" [{}]", program);
+    match line.strip_suffix(&tag) {
+        Some(stripped) => stripped.to_string(),
+        None => line,
+    }
+}
+
+/// Keybinds listed under headings, e.g. `Mod+Shift:` followed by indented keybind lines.
+pub fn grouped_lines<'a>(
+    groups: impl IntoIterator<Item = (String, Vec<&'a Keybind>)>,
+    program_tag: bool,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (heading, keybinds) in groups {
+        lines.push(format!("{}:", heading));
+        for keybind in keybinds {
+            lines.push(format!("  {}", plain_line(keybind, program_tag)));
+        }
+    }
+    lines
+}
+
+/// Heading for a group of keybinds sharing `modifiers`, e.g. `Mod+Shift`.
+pub fn modifiers_heading(modifiers: &[Modifier]) -> String {
+    if modifiers.is_empty() {
+        return "No modifiers".to_string();
+    }
+    modifiers
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Every field of a keybind, one per line, for fzf's preview window.
+pub fn details(keybind: &Keybind) -> String {
+    let mut lines = vec![
+        format!("Keys:        {}", keybind.keys_string()),
+        format!("Action:      {}", keybind.action),
+    ];
+    if let Some(description) = &keybind.description {
+        lines.push(format!("Description: {}", description));
+    }
+    let props = keybind.property_annotations();
+    if !props.is_empty() {
+        lines.push(format!("Properties:  {}", props.join(", ")));
+    }
+    lines.push(format!("Program:     {}", keybind.program));
+
+    lines.join("\n")
+}
+
+/// Lays keybinds out as aligned `keys  action  program` rows.
+///
+/// Column widths are measured in terminal cells, so combos containing wide
+/// characters line up with plain ASCII ones.
+pub fn columns(keybinds: &[Keybind]) -> Vec<String> {
+    let rows: Vec<(String, &str, &str)> = keybinds
+        .iter()
+        .map(|keybind| {
+            (
+                keybind.keys_string(),
+                keybind.action.as_str(),
+                keybind.program.as_str(),
+            )
+        })
+        .collect();
+
+    let keys_width = rows
+        .iter()
+        .map(|(keys, _, _)| keys.width())
+        .max()
+        .unwrap_or(0);
+    let action_width = rows
+        .iter()
+        .map(|(_, action, _)| action.width())
+        .max()
+        .unwrap_or(0);
+
+    rows.iter()
+        .map(|(keys, action, program)| {
+            format!(
+                "{}  {}  {}",
+                pad(keys, keys_width),
+                pad(action, action_width),
+                program
+            )
+        })
+        .collect()
+}
+
+/// Mac-style modifier symbols for `--symbols`, in the order macOS lists them (`⌃⌥⇧⌘`).
+///
+/// Modifiers missing here, like niri's `Mod`, keep their name.
+pub const MODIFIER_SYMBOLS: &[(Modifier, &str)] = &[
+    (Modifier::Ctrl, "⌃"),
+    (Modifier::Alt, "⌥"),
+    (Modifier::Meta, "⌥"),
+    (Modifier::Shift, "⇧"),
+    (Modifier::Super, "⌘"),
+];
+
+/// Writes each step Mac-style, e.g. `⌃⇧T` for `Ctrl+Shift+T`, joining steps with `separator`.
+///
+/// Symbols take no `+` and follow [`MODIFIER_SYMBOLS`] order whatever order
+/// the source used; modifiers without a symbol come first as `Mod+`.
+pub fn symbol_keys(chord: &Chord, separator: &str) -> String {
+    chord
+        .steps()
+        .iter()
+        .map(|(modifiers, key)| {
+            let mut step = String::new();
+            for modifier in modifiers {
+                if !MODIFIER_SYMBOLS.iter().any(|(m, _)| m == modifier) {
+                    step.push_str(&format!("{}+", modifier));
+                }
+            }
+            for (modifier, symbol) in MODIFIER_SYMBOLS {
+                if modifiers.contains(modifier) {
+                    step.push_str(symbol);
+                }
+            }
+            step.push_str(key);
+            step
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// The keybind with its keys rewritten by [`symbol_keys`], for display only.
+///
+/// Like [`Keybind::with_chord_separator`], the result is a single step with no
+/// modifiers, so this is applied after anything that inspects the modifiers.
+pub fn with_symbols(mut keybind: Keybind, separator: &str) -> Keybind {
+    keybind.key = Chord::from(symbol_keys(&keybind.key, separator));
+    keybind
+}
+
+fn pad(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.width());
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keybind::Modifier;
+
+    #[test]
+    fn test_symbol_keys() {
+        let keybind = Keybind::new("kitty", "T")
+            .modifiers(vec![Modifier::Ctrl, Modifier::Shift])
+            .action("new_tab");
+        let reordered = keybind
+            .clone()
+            .modifiers(vec![Modifier::Shift, Modifier::Ctrl]);
+
+        assert_eq!(with_symbols(keybind, ">").keys_string(), "⌃⇧T");
+        assert_eq!(symbol_keys(&reordered.key, ">"), "⌃⇧T");
+
+        let niri = Chord::single(vec![Modifier::Shift, Modifier::Mod], "Q");
+        assert_eq!(symbol_keys(&niri, ">"), "Mod+⇧Q");
+
+        let sequence = Chord(vec![
+            (vec![Modifier::Ctrl], "x".to_string()),
+            (vec![Modifier::Ctrl], "f".to_string()),
+        ]);
+        assert_eq!(symbol_keys(&sequence, " "), "⌃x ⌃f");
+    }
+
+    #[test]
+    fn test_desktop_action_spawn() {
+        let terminal = Keybind::new(Program::Niri, "T")
+            .modifiers(vec![Modifier::Mod])
+            .action(r#"spawn "alacritty" "-e" "htop""#)
+            .action_name("spawn".to_string())
+            .action_args(vec!["alacritty".into(), "-e".into(), "htop".into()])
+            .description("Open a Terminal".to_string());
+        assert_eq!(
+            desktop_action(&terminal, 1),
+            "[Desktop Action niri-1]\n\
+             Name=Open a Terminal\n\
+             Exec=alacritty -e htop\n\
+             X-Keys=Mod+T\n"
+        );
+
+        let volume = Keybind::new(Program::Niri, "XF86AudioMute")
+            .action(r#"spawn-sh "wpctl set-mute @DEFAULT_SINK@ 100%""#)
+            .action_name("spawn-sh".to_string())
+            .action_args(vec!["wpctl set-mute @DEFAULT_SINK@ 100%".into()]);
+        assert!(
+            desktop_action(&volume, 0)
+                .contains(r#"Exec=sh -c "wpctl set-mute @DEFAULT_SINK@ 100%%""#)
+        );
+
+        // Only spawned commands can be run
+        let close = Keybind::new(Program::Niri, "Q")
+            .action("close-window")
+            .action_name("close-window".to_string());
+        assert!(!desktop_action(&close, 0).contains("Exec="));
+
+        // The action is read from its parts, not parsed back from the action text
+        let flattened = Keybind::new(Program::Niri, "F").action(r#"spawn "foot""#);
+        assert!(!desktop_action(&flattened, 0).contains("Exec="));
+    }
+
+    #[test]
+    fn test_columns_align() {
+        let keybinds = vec![
+            Keybind::new("niri", "T")
+                .modifiers(vec![Modifier::Mod, Modifier::Shift])
+                .action("spawn \"alacritty\""),
+            Keybind::new("kitty", "q").action("quit"),
+        ];
+
+        let lines = columns(&keybinds);
+
+        assert_eq!(
+            lines,
+            vec![
+                "Mod+Shift+T  spawn \"alacritty\"  niri",
+                "q            quit               kitty",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_columns_measure_wide_characters() {
+        let keybinds = vec![
+            Keybind::new("emacs", "あ").action("a"),
+            Keybind::new("emacs", "abc").action("b"),
+        ];
+
+        let lines = columns(&keybinds);
+
+        // "あ" is two cells wide, so it needs one space of padding to match "abc"
+        assert_eq!(lines, vec!["あ   a  emacs", "abc  b  emacs"]);
+    }
+
+    #[test]
+    fn test_details() {
+        let keybind = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("spawn \"alacritty\"")
+            .description("Open a Terminal".to_string())
+            .cooldown_ms(Some(150));
+
+        assert_eq!(
+            details(&keybind),
+            "Keys:        Mod+T\n\
+             Action:      spawn \"alacritty\"\n\
+             Description: Open a Terminal\n\
+             Properties:  cooldown=150ms\n\
+             Program:     niri"
+        );
+    }
+
+    #[test]
+    fn test_tab_line() {
+        let keybind = Keybind::new("kitty", "t")
+            .modifiers(vec![Modifier::Ctrl])
+            .action("new_tab");
+
+        assert_eq!(
+            tab_line(&keybind, 3, true),
+            "kitty\t3\tCtrl+t - new_tab [kitty]"
+        );
+        assert_eq!(tab_line(&keybind, 3, false), "kitty\t3\tCtrl+t - new_tab");
+    }
+
+    #[test]
+    fn test_tab_line_sanitizes_tabs() {
+        let keybind = Keybind::new("niri", "P")
+            .modifiers(vec![Modifier::Mod])
+            .action("spawn \"printf\" \"a\tb\nc\"");
+
+        let line = tab_line(&keybind, 0, true);
+
+        assert_eq!(line.split('\t').count(), 3);
+        assert_eq!(
+            line.split('\t').nth(2),
+            Some("Mod+P - spawn \"printf\" \"a b c\" [niri]")
+        );
+    }
+
+    #[test]
+    fn test_json_escapes_quotes_and_control_characters() {
+        let keybind = Keybind::new("niri", "P")
+            .action("spawn \"sh\" \"-c\" \"echo \\\"hi\\\"\tC:\\\\tmp\u{1b}\"")
+            .description("say \"hi\"\n".to_string());
+
+        let line = ndjson_line(&keybind);
+
+        assert!(!line.contains('\t') && !line.contains('\n') && !line.contains('\u{1b}'));
+        assert!(line.contains(r#"\t"#) && line.contains(r#"\u001b"#));
+        let parsed: Keybind = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed, keybind);
+        let parsed: Vec<Keybind> =
+            serde_json::from_str(&json(std::slice::from_ref(&keybind), true)).unwrap();
+        assert_eq!(parsed, vec![keybind]);
+    }
+
+    #[test]
+    fn test_load_json_round_trip() {
+        let keybinds = vec![
+            Keybind::new("niri", "T")
+                .modifiers(vec![Modifier::Mod, Modifier::IsoLevel3Shift])
+                .action("spawn \"alacritty\"")
+                .description("Open a Terminal: alacritty".to_string())
+                .repeat(false)
+                .cooldown_ms(150)
+                .allow_when_locked(true)
+                .allow_inhibiting(false)
+                .hidden_in_overlay(true),
+            Keybind::new(
+                "emacs",
+                Chord(vec![
+                    (vec![Modifier::Ctrl], "x".to_string()),
+                    (vec![Modifier::Ctrl], "f".to_string()),
+                ]),
+            )
+            .action("find-file"),
+        ];
+
+        let array = json(&keybinds, true);
+        assert_eq!(load_json(array.as_bytes()).unwrap(), keybinds);
+
+        let ndjson: String = keybinds
+            .iter()
+            .map(|keybind| ndjson_line(keybind) + "\n")
+            .collect();
+        assert_eq!(load_json(ndjson.as_bytes()).unwrap(), keybinds);
+    }
+
+    #[test]
+    fn test_load_json_accepts_display_modifier_names() {
+        let json = r#"{"modifiers": ["ISO_Level3_Shift"], "key": "a", "action": "x",
+            "description": null, "program": "niri", "repeat": null, "cooldown_ms": null,
+            "allow_when_locked": null, "allow_inhibiting": null}"#;
+
+        let keybinds = load_json(json.as_bytes()).unwrap();
+        assert_eq!(keybinds[0].key.modifiers(), [Modifier::IsoLevel3Shift]);
+        assert!(load_json("[{\"key\": 1}]".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_plain_line_program_tag() {
+        let keybind = Keybind::new("niri", "Q")
+            .modifiers(vec![Modifier::Mod])
+            .action("close-window");
+
+        assert_eq!(plain_line(&keybind, true), "Mod+Q - close-window [niri]");
+        assert_eq!(plain_line(&keybind, false), "Mod+Q - close-window");
+    }
+}
@@ -0,0 +1,61 @@
+/// Scores `needle` as a case-insensitive subsequence of `haystack`.
+///
+/// Returns `None` when some character of `needle` can't be found in order.
+/// Higher scores are better: every matched character scores a point, with
+/// bonuses for matches at the start of a word (after `+`, `-`, `_`, `>`, or a
+/// space) and for runs of consecutive matches. So `mst` scores highly against
+/// `Mod+Shift+T`, where each letter starts a word.
+pub fn score(haystack: &str, needle: &str) -> Option<u32> {
+    let needle: Vec<char> = needle.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous_matched = false;
+    let mut previous_char: Option<char> = None;
+
+    for c in haystack.chars() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        let word_start = previous_char.is_none_or(|p| matches!(p, '+' | '-' | '_' | '>' | ' '));
+
+        if next < needle.len() && lower == needle[next] {
+            score += 1;
+            if word_start {
+                score += 3;
+            }
+            if previous_matched {
+                score += 2;
+            }
+            next += 1;
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+
+        previous_char = Some(c);
+    }
+
+    (next == needle.len()).then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subsequence_match() {
+        assert!(score("Mod+Shift+T", "mst").is_some());
+        assert!(score("focus-column-left", "fcl").is_some());
+        assert_eq!(score("Mod+T", "tm"), None);
+        assert_eq!(score("Mod+T", "x"), None);
+    }
+
+    #[test]
+    fn test_word_starts_rank_higher() {
+        let word_starts = score("Mod+Shift+T", "mst").unwrap();
+        let scattered = score("mistletoe", "mst").unwrap();
+        assert!(word_starts > scattered);
+    }
+}
@@ -1,13 +1,29 @@
+use crate::fuzzy;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::Infallible;
 use std::fmt;
+use std::str::FromStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Serialized by variant name; the `Display` names `ISO_Level3_Shift` and
+/// `ISO_Level5_Shift` are accepted when deserializing too.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum Modifier {
     Mod,
     Super,
+    /// A separate modifier from Super on X11 and in Emacs (`H-`).
+    Hyper,
     Alt,
+    /// Emacs' `M-`; usually produced by Alt, but kept apart where configs distinguish them.
+    Meta,
     Ctrl,
     Shift,
+    #[serde(alias = "ISO_Level3_Shift")]
     IsoLevel3Shift,
+    #[serde(alias = "ISO_Level5_Shift")]
     IsoLevel5Shift,
 }
 
@@ -16,7 +32,9 @@ impl fmt::Display for Modifier {
         match self {
             Modifier::Mod => write!(f, "Mod"),
             Modifier::Super => write!(f, "Super"),
+            Modifier::Hyper => write!(f, "Hyper"),
             Modifier::Alt => write!(f, "Alt"),
+            Modifier::Meta => write!(f, "Meta"),
             Modifier::Ctrl => write!(f, "Ctrl"),
             Modifier::Shift => write!(f, "Shift"),
             Modifier::IsoLevel3Shift => write!(f, "ISO_Level3_Shift"),
@@ -25,156 +43,2842 @@ impl fmt::Display for Modifier {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl Modifier {
+    /// Every modifier, in declaration order.
+    pub const KNOWN: [Modifier; 9] = [
+        Modifier::Mod,
+        Modifier::Super,
+        Modifier::Hyper,
+        Modifier::Alt,
+        Modifier::Meta,
+        Modifier::Ctrl,
+        Modifier::Shift,
+        Modifier::IsoLevel3Shift,
+        Modifier::IsoLevel5Shift,
+    ];
+
+    /// The modifier named by its `Display` form, e.g. `ISO_Level3_Shift`.
+    fn from_display_name(name: &str) -> Option<Modifier> {
+        Modifier::KNOWN
+            .into_iter()
+            .find(|modifier| modifier.to_string() == name)
+    }
+
+    /// Canonical position of this modifier when modifiers are normalized or sorted.
+    fn rank(&self) -> u8 {
+        match self {
+            Modifier::Mod => 0,
+            Modifier::Super => 1,
+            Modifier::Hyper => 2,
+            Modifier::Ctrl => 3,
+            Modifier::Alt => 4,
+            Modifier::Meta => 5,
+            Modifier::Shift => 6,
+            Modifier::IsoLevel3Shift => 7,
+            Modifier::IsoLevel5Shift => 8,
+        }
+    }
+}
+
+impl Ord for Modifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for Modifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+bitflags::bitflags! {
+    /// A set of [`Modifier`]s, one bit each, so comparing modifiers needs no
+    /// sorting or allocation. Bits follow the canonical modifier order.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct ModifierMask: u16 {
+        const MOD = 1 << 0;
+        const SUPER = 1 << 1;
+        const HYPER = 1 << 2;
+        const CTRL = 1 << 3;
+        const ALT = 1 << 4;
+        const META = 1 << 5;
+        const SHIFT = 1 << 6;
+        const ISO_LEVEL3_SHIFT = 1 << 7;
+        const ISO_LEVEL5_SHIFT = 1 << 8;
+    }
+}
+
+impl From<&Modifier> for ModifierMask {
+    fn from(modifier: &Modifier) -> Self {
+        ModifierMask::from_bits_retain(1 << modifier.rank())
+    }
+}
+
+impl<'a> FromIterator<&'a Modifier> for ModifierMask {
+    fn from_iter<I: IntoIterator<Item = &'a Modifier>>(modifiers: I) -> Self {
+        modifiers.into_iter().map(ModifierMask::from).collect()
+    }
+}
+
+impl ModifierMask {
+    /// The modifiers in the mask, in canonical order.
+    pub fn modifiers(self) -> Vec<Modifier> {
+        let mut modifiers: Vec<Modifier> = Modifier::KNOWN
+            .into_iter()
+            .filter(|modifier| self.contains(modifier.into()))
+            .collect();
+        modifiers.sort();
+        modifiers
+    }
+}
+
+/// The program a keybind belongs to.
+///
+/// Programs with a source have their own variant, so filters and rules can't
+/// misspell them; anything else, e.g. keybinds from a custom [`Source`](crate::source::Source),
+/// is [`Program::Other`]. Programs order and print by name, e.g. `windows-terminal`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Program {
+    Niri,
+    Kitty,
+    Emacs,
+    Qtile,
+    Dunst,
+    WindowsTerminal,
+    Dwm,
+    Awesome,
+    Kakoune,
+    Keyd,
+    Other(String),
+}
+
+impl Program {
+    /// Every program with its own variant.
+    pub const KNOWN: [Program; 10] = [
+        Program::Niri,
+        Program::Kitty,
+        Program::Emacs,
+        Program::Qtile,
+        Program::Dunst,
+        Program::WindowsTerminal,
+        Program::Dwm,
+        Program::Awesome,
+        Program::Kakoune,
+        Program::Keyd,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Program::Niri => "niri",
+            Program::Kitty => "kitty",
+            Program::Emacs => "emacs",
+            Program::Qtile => "qtile",
+            Program::Dunst => "dunst",
+            Program::WindowsTerminal => "windows-terminal",
+            Program::Dwm => "dwm",
+            Program::Awesome => "awesome",
+            Program::Kakoune => "kakoune",
+            Program::Keyd => "keyd",
+            Program::Other(name) => name,
+        }
+    }
+
+    /// Whether this is one of the [`Program::KNOWN`] programs rather than [`Program::Other`].
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Program::Other(_))
+    }
+
+    /// Whether `name` names this program, ignoring case and surrounding whitespace.
+    pub fn is_named(&self, name: &str) -> bool {
+        self.as_str().trim().to_lowercase() == name.trim().to_lowercase()
+    }
+}
+
+impl AsRef<str> for Program {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Program {
+    type Err = Infallible;
+
+    /// Parses a program name, ignoring case and surrounding whitespace as in `" Niri "`.
+    ///
+    /// Names without a variant become [`Program::Other`], trimmed but otherwise as given.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(Program::KNOWN
+            .into_iter()
+            .find(|program| program.is_named(name))
+            .unwrap_or_else(|| Program::Other(name.trim().to_string())))
+    }
+}
+
+impl From<&str> for Program {
+    fn from(name: &str) -> Self {
+        let Ok(program) = name.parse();
+        program
+    }
+}
+
+impl From<String> for Program {
+    fn from(name: String) -> Self {
+        Program::from(name.as_str())
+    }
+}
+
+impl From<Program> for String {
+    fn from(program: Program) -> Self {
+        program.to_string()
+    }
+}
+
+impl Ord for Program {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for Program {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Rough classification of a key name, used to spot typos in configs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyKind {
+    /// A single character key like `T` or `5`.
+    Letter,
+    /// `F1` through `F35`.
+    Function,
+    /// XF86 media and hardware keys like `XF86AudioPlay`.
+    Media,
+    /// Mouse buttons and wheel/touchpad scroll events.
+    Mouse,
+    /// Named keysyms like `Return`, `Page_Up` or `comma`.
+    Named,
+    Unknown,
+}
+
+/// Logical group of a keybind, approximating the sections of niri's hotkey overlay.
+///
+/// Variants are declared in the order their groups are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    /// Launching programs.
+    Spawn,
+    /// Focusing, moving and sizing windows and columns.
+    Window,
+    /// Switching workspaces and moving things between them.
+    Workspace,
+    /// XF86 media and hardware keys.
+    Media,
+    Other,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Category::Spawn => write!(f, "Spawn"),
+            Category::Window => write!(f, "Window management"),
+            Category::Workspace => write!(f, "Workspaces"),
+            Category::Media => write!(f, "Media"),
+            Category::Other => write!(f, "Other"),
+        }
+    }
+}
+
+// Action prefixes recognized by `Keybind::category`, per program. The first
+// matching rule wins, so more specific prefixes come before general ones.
+const CATEGORY_RULES: &[(Program, &str, Category)] = &[
+    (Program::Niri, "spawn", Category::Spawn),
+    (Program::Niri, "focus-workspace", Category::Workspace),
+    (Program::Niri, "move-workspace", Category::Workspace),
+    (
+        Program::Niri,
+        "move-column-to-workspace",
+        Category::Workspace,
+    ),
+    (
+        Program::Niri,
+        "move-window-to-workspace",
+        Category::Workspace,
+    ),
+    (Program::Niri, "focus-", Category::Window),
+    (Program::Niri, "move-", Category::Window),
+    (Program::Niri, "close-window", Category::Window),
+    (Program::Niri, "consume-", Category::Window),
+    (Program::Niri, "expel-", Category::Window),
+    (Program::Niri, "maximize-", Category::Window),
+    (Program::Niri, "fullscreen-", Category::Window),
+    (Program::Niri, "center-", Category::Window),
+    (Program::Niri, "set-column-width", Category::Window),
+    (Program::Niri, "set-window-height", Category::Window),
+    (Program::Niri, "switch-preset-", Category::Window),
+    (Program::Niri, "toggle-window-", Category::Window),
+    (Program::Qtile, "lazy.spawn", Category::Spawn),
+    (Program::Qtile, "lazy.group", Category::Workspace),
+    (Program::Qtile, "lazy.window", Category::Window),
+    (Program::Qtile, "lazy.layout", Category::Window),
+    (Program::Awesome, "awful.spawn", Category::Spawn),
+    (Program::Awesome, "awful.tag", Category::Workspace),
+    (Program::Awesome, "awful.client", Category::Window),
+    (Program::Dwm, "spawn", Category::Spawn),
+    (Program::Dwm, "view", Category::Workspace),
+    (Program::Dwm, "tag", Category::Workspace),
+    (Program::Dwm, "focusstack", Category::Window),
+    (Program::Dwm, "killclient", Category::Window),
+    (Program::Dwm, "togglefloating", Category::Window),
+];
+
+// Named keysyms recognized by `Keybind::key_kind`, compared case-insensitively.
+const NAMED_KEYS: &[&str] = &[
+    "Return",
+    "Enter",
+    "Escape",
+    "Esc",
+    "Tab",
+    "ISO_Left_Tab",
+    "Space",
+    "BackSpace",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "Page_Up",
+    "Page_Down",
+    "PageUp",
+    "PageDown",
+    "Prior",
+    "Next",
+    "Left",
+    "Right",
+    "Up",
+    "Down",
+    "Print",
+    "Pause",
+    "Scroll_Lock",
+    "Caps_Lock",
+    "Num_Lock",
+    "Menu",
+    "comma",
+    "period",
+    "slash",
+    "backslash",
+    "minus",
+    "equal",
+    "plus",
+    "semicolon",
+    "apostrophe",
+    "grave",
+    "bracketleft",
+    "bracketright",
+    "less",
+    "greater",
+    "question",
+    "exclam",
+    "at",
+    "numbersign",
+    "dollar",
+    "percent",
+    "asciicircum",
+    "ampersand",
+    "asterisk",
+    "parenleft",
+    "parenright",
+    "underscore",
+    "colon",
+    "quotedbl",
+    "bar",
+    "asciitilde",
+    "braceleft",
+    "braceright",
+    "KP_Enter",
+    "KP_Add",
+    "KP_Subtract",
+    "KP_Multiply",
+    "KP_Divide",
+    // niri switch events, listed under a synthetic key name
+    "lid-open",
+    "lid-close",
+    "tablet-mode-on",
+    "tablet-mode-off",
+];
+
+/// A key combination, or a sequence of them pressed one after another.
+///
+/// Each step is its modifiers and key name: niri's `Mod+T` is one step, while
+/// kitty's `ctrl+x>ctrl+y` and Emacs' `C-x C-f` are two.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Chord(pub Vec<(Vec<Modifier>, String)>);
+
+impl Chord {
+    /// A chord of one step, e.g. `Mod+T`.
+    pub fn single(modifiers: Vec<Modifier>, key: impl Into<String>) -> Self {
+        Chord(vec![(modifiers, key.into())])
+    }
+
+    pub fn steps(&self) -> &[(Vec<Modifier>, String)] {
+        &self.0
+    }
+
+    /// Whether more than one key has to be pressed in turn.
+    pub fn is_sequence(&self) -> bool {
+        self.0.len() > 1
+    }
+
+    /// The modifiers of the first step.
+    pub fn modifiers(&self) -> &[Modifier] {
+        self.0
+            .first()
+            .map_or(&[], |(modifiers, _)| modifiers.as_slice())
+    }
+
+    /// The key name of the first step.
+    pub fn first_key(&self) -> &str {
+        self.0.first().map_or("", |(_, key)| key.as_str())
+    }
+
+    /// Whether both chords press the same keys, regardless of modifier order
+    /// and of the case of key names (niri's `E` is kitty's `e`).
+    pub fn is_same_as(&self, other: &Chord) -> bool {
+        self.0.len() == other.0.len()
+            && self.0.iter().zip(&other.0).all(|(a, b)| {
+                a.0.iter().collect::<ModifierMask>() == b.0.iter().collect::<ModifierMask>()
+                    && a.1.to_lowercase() == b.1.to_lowercase()
+            })
+    }
+
+    /// A copy with every step's modifiers in canonical order.
+    pub fn normalized(&self) -> Chord {
+        Chord(
+            self.0
+                .iter()
+                .map(|(modifiers, key)| {
+                    let mask: ModifierMask = modifiers.iter().collect();
+                    (mask.modifiers(), key.clone())
+                })
+                .collect(),
+        )
+    }
+
+    /// A copy with `Mod` replaced by `Super` in every step.
+    ///
+    /// niri's `Mod` is Super unless niri runs nested, so this lets `Mod+T`
+    /// compare equal to another program's `Super+T`.
+    pub fn with_mod_as_super(&self) -> Chord {
+        Chord(
+            self.0
+                .iter()
+                .map(|(modifiers, key)| {
+                    let mut canonical: Vec<Modifier> = Vec::new();
+                    for modifier in modifiers {
+                        let modifier = match modifier {
+                            Modifier::Mod => Modifier::Super,
+                            other => other.clone(),
+                        };
+                        if !canonical.contains(&modifier) {
+                            canonical.push(modifier);
+                        }
+                    }
+                    (canonical, key.clone())
+                })
+                .collect(),
+        )
+    }
+
+    /// The steps written as `Mod+Shift+T`, joined with `separator`.
+    pub fn join(&self, separator: &str) -> String {
+        self.0
+            .iter()
+            .map(|(modifiers, key)| join_keys(modifiers, key))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// Parses the serialized form: the first step's modifiers, and its key
+    /// followed by any further steps, e.g. `x>Ctrl+f`.
+    ///
+    /// A key that doesn't split into valid steps, like a literal `>`, is kept whole.
+    fn from_parts(modifiers: Vec<Modifier>, key: &str) -> Self {
+        let mut pieces = key.split(CHORD_SEPARATOR);
+        let first = pieces.next().unwrap_or_default();
+        let rest: Option<Vec<_>> = pieces.map(Self::parse_step).collect();
+
+        match rest {
+            Some(rest) if !first.is_empty() => {
+                let mut chord = Chord::single(modifiers, first);
+                chord.0.extend(rest);
+                chord
+            }
+            _ => Chord::single(modifiers, key),
+        }
+    }
+
+    fn parse_step(step: &str) -> Option<(Vec<Modifier>, String)> {
+        let (modifiers, key) = match step.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None => step.rsplit_once('+').unwrap_or(("", step)),
+        };
+        if key.is_empty() {
+            return None;
+        }
+
+        let modifiers = if modifiers.is_empty() {
+            Vec::new()
+        } else {
+            modifiers
+                .split('+')
+                .map(Modifier::from_display_name)
+                .collect::<Option<_>>()?
+        };
+        Some((modifiers, key.to_string()))
+    }
+}
+
+/// Parses a chord as it's displayed, e.g. `Mod+Shift+E` or `Ctrl+x>Ctrl+f`.
+///
+/// Modifier names are matched ignoring case, so `mod+shift+e` works too.
+impl FromStr for Chord {
+    type Err = String;
+
+    fn from_str(chord: &str) -> Result<Self, Self::Err> {
+        let chord = chord.trim();
+        if chord.is_empty() {
+            return Err("empty chord".to_string());
+        }
+
+        let parse_step = |step: &str| -> Result<(Vec<Modifier>, String), String> {
+            let (modifiers, key) = match step.strip_suffix("++") {
+                Some(modifiers) => (modifiers, "+"),
+                None => step.rsplit_once('+').unwrap_or(("", step)),
+            };
+            if key.is_empty() {
+                return Err(format!("missing key in '{}'", step));
+            }
+
+            let modifiers = modifiers
+                .split('+')
+                .filter(|name| !name.is_empty())
+                .map(|name| {
+                    Modifier::KNOWN
+                        .into_iter()
+                        .find(|modifier| modifier.to_string().eq_ignore_ascii_case(name))
+                        .ok_or_else(|| format!("unknown modifier '{}'", name))
+                })
+                .collect::<Result<_, _>>()?;
+            Ok((modifiers, key.to_string()))
+        };
+
+        // A piece left empty by the split means `>` is the key, as in `Ctrl+>`
+        let pieces: Vec<&str> = chord.split(CHORD_SEPARATOR).collect();
+        if pieces.iter().any(|piece| piece.is_empty()) {
+            return parse_step(chord).map(|(modifiers, key)| Chord::single(modifiers, key));
+        }
+        pieces
+            .into_iter()
+            .map(parse_step)
+            .collect::<Result<_, _>>()
+            .map(Chord)
+    }
+}
+
+impl From<&str> for Chord {
+    fn from(key: &str) -> Self {
+        Chord::single(Vec::new(), key)
+    }
+}
+
+impl From<String> for Chord {
+    fn from(key: String) -> Self {
+        Chord::single(Vec::new(), key)
+    }
+}
+
+/// Steps are joined with [`CHORD_SEPARATOR`], so a single step reads `Mod+Shift+T`.
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.join(CHORD_SEPARATOR))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(into = "KeybindRepr", from = "KeybindRepr")]
+#[schemars(with = "KeybindRepr")]
 pub struct Keybind {
-    pub modifiers: Vec<Modifier>,
-    pub key: String,
+    pub key: Chord,
     pub action: String,
+    /// The name of a single action, e.g. `spawn`, when the source reads it
+    /// apart from its arguments.
+    pub action_name: Option<String>,
+    /// The positional arguments of that action, unquoted, e.g. `["alacritty", "-e", "htop"]`.
+    pub action_args: Vec<String>,
     pub description: Option<String>,
-    pub program: String,
+    pub program: Program,
     pub repeat: Option<bool>,
     pub cooldown_ms: Option<u64>,
     pub allow_when_locked: Option<bool>,
     pub allow_inhibiting: Option<bool>,
+    /// Hidden from niri's hotkey overlay with `hotkey-overlay-title=false` or `=null`.
+    #[serde(default)]
+    pub hidden_in_overlay: bool,
+    /// When the config file the keybind was read from was last modified, in
+    /// seconds since the Unix epoch; only set when asked for, e.g. `--show-mtime`.
+    pub config_mtime: Option<u64>,
 }
 
-impl fmt::Display for Keybind {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if !self.modifiers.is_empty() {
-            for (i, modifier) in self.modifiers.iter().enumerate() {
-                if i > 0 {
-                    write!(f, "+")?;
-                }
-                write!(f, "{}", modifier)?;
-            }
-            write!(f, "+")?;
-        }
-        write!(f, "{}", self.key)?;
+/// How a [`Keybind`] is serialized: the first step's modifiers, and its key
+/// followed by any further steps joined with [`CHORD_SEPARATOR`], e.g. `x>Ctrl+f`.
+///
+/// This keeps the JSON output and the kitty cache in their flat shape.
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "Keybind")]
+struct KeybindRepr {
+    modifiers: Vec<Modifier>,
+    key: String,
+    action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    action_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    action_args: Vec<String>,
+    description: Option<String>,
+    program: String,
+    repeat: Option<bool>,
+    cooldown_ms: Option<u64>,
+    allow_when_locked: Option<bool>,
+    allow_inhibiting: Option<bool>,
+    /// Hidden from niri's hotkey overlay with `hotkey-overlay-title=false` or `=null`.
+    #[serde(default)]
+    hidden_in_overlay: bool,
+    /// Seconds since the Unix epoch; left out unless set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    config_mtime: Option<u64>,
+}
 
-        if let Some(desc) = &self.description {
-            write!(f, " - {}", desc)?;
-        } else {
-            write!(f, " - {}", self.action)?;
+impl From<Keybind> for KeybindRepr {
+    fn from(keybind: Keybind) -> Self {
+        let mut steps = keybind.key.0.into_iter();
+        let (modifiers, first) = steps.next().unwrap_or_default();
+        let mut key = first;
+        for (step_modifiers, step_key) in steps {
+            key.push_str(CHORD_SEPARATOR);
+            key.push_str(&join_keys(&step_modifiers, &step_key));
         }
 
-        // Add property annotations if present
-        let mut props = Vec::new();
-        if let Some(false) = self.repeat {
-            props.push("no-repeat".to_string());
-        }
-        if let Some(cooldown) = self.cooldown_ms {
-            props.push(format!("cooldown={}ms", cooldown));
-        }
-        if let Some(true) = self.allow_when_locked {
-            props.push("allow-locked".to_string());
-        }
-        if let Some(false) = self.allow_inhibiting {
-            props.push("no-inhibit".to_string());
+        KeybindRepr {
+            modifiers,
+            key,
+            action: keybind.action,
+            action_name: keybind.action_name,
+            action_args: keybind.action_args,
+            description: keybind.description,
+            program: keybind.program.into(),
+            repeat: keybind.repeat,
+            cooldown_ms: keybind.cooldown_ms,
+            allow_when_locked: keybind.allow_when_locked,
+            allow_inhibiting: keybind.allow_inhibiting,
+            hidden_in_overlay: keybind.hidden_in_overlay,
+            config_mtime: keybind.config_mtime,
         }
+    }
+}
 
-        if !props.is_empty() {
-            write!(f, " ({})", props.join(", "))?;
+impl From<KeybindRepr> for Keybind {
+    fn from(repr: KeybindRepr) -> Self {
+        Keybind {
+            key: Chord::from_parts(repr.modifiers, &repr.key),
+            action: repr.action,
+            action_name: repr.action_name,
+            action_args: repr.action_args,
+            description: repr.description,
+            program: repr.program.into(),
+            repeat: repr.repeat,
+            cooldown_ms: repr.cooldown_ms,
+            allow_when_locked: repr.allow_when_locked,
+            allow_inhibiting: repr.allow_inhibiting,
+            hidden_in_overlay: repr.hidden_in_overlay,
+            config_mtime: repr.config_mtime,
         }
-
-        write!(f, " [{}]", self.program)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Property annotations understood by [`Keybind::has_property`], as shown by `Display`.
+pub const PROPERTY_NAMES: &[&str] = &[
+    "no-repeat",
+    "cooldown",
+    "allow-locked",
+    "no-inhibit",
+    "overlay-hidden",
+];
 
-    #[test]
-    fn test_keybind_display() {
-        let keybind = Keybind {
-            modifiers: vec![Modifier::Mod, Modifier::Shift],
-            key: "T".to_string(),
-            action: "spawn-terminal".to_string(),
-            description: Some("Open Terminal".to_string()),
-            program: "niri".to_string(),
+impl Keybind {
+    /// Creates a keybind with no modifiers, an empty action and no optional properties.
+    ///
+    /// The remaining fields are filled in with the chainable setters, e.g.
+    /// `Keybind::new("niri", "T").modifiers(vec![Modifier::Mod]).action("close-window")`.
+    /// Sources that parse key sequences pass a whole [`Chord`] as the key.
+    pub fn new(program: impl Into<Program>, key: impl Into<Chord>) -> Self {
+        Self {
+            key: key.into(),
+            action: String::new(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: None,
+            program: program.into(),
             repeat: None,
             cooldown_ms: None,
             allow_when_locked: None,
             allow_inhibiting: None,
-        };
+            hidden_in_overlay: false,
+            config_mtime: None,
+        }
+    }
 
-        assert_eq!(keybind.to_string(), "Mod+Shift+T - Open Terminal [niri]");
+    /// Sets the modifiers of the chord's first step.
+    pub fn modifiers(mut self, modifiers: Vec<Modifier>) -> Self {
+        match self.key.0.first_mut() {
+            Some(step) => step.0 = modifiers,
+            None => self.key = Chord::single(modifiers, ""),
+        }
+        self
     }
 
-    #[test]
-    fn test_keybind_no_description() {
-        let keybind = Keybind {
-            modifiers: vec![Modifier::Mod],
-            key: "Q".to_string(),
-            action: "close-window".to_string(),
-            description: None,
-            program: "niri".to_string(),
-            repeat: None,
-            cooldown_ms: None,
-            allow_when_locked: None,
-            allow_inhibiting: None,
-        };
+    pub fn action(mut self, action: impl Into<String>) -> Self {
+        self.action = action.into();
+        self
+    }
 
-        assert_eq!(keybind.to_string(), "Mod+Q - close-window [niri]");
+    pub fn action_name(mut self, action_name: impl Into<Option<String>>) -> Self {
+        self.action_name = action_name.into();
+        self
     }
 
-    #[test]
-    fn test_keybind_no_modifiers() {
-        let keybind = Keybind {
-            modifiers: vec![],
-            key: "XF86AudioRaiseVolume".to_string(),
-            action: "volume-up".to_string(),
-            description: Some("Increase Volume".to_string()),
-            program: "niri".to_string(),
-            repeat: None,
-            cooldown_ms: None,
-            allow_when_locked: None,
-            allow_inhibiting: None,
-        };
+    pub fn action_args(mut self, action_args: Vec<String>) -> Self {
+        self.action_args = action_args;
+        self
+    }
 
-        assert_eq!(
-            keybind.to_string(),
-            "XF86AudioRaiseVolume - Increase Volume [niri]"
-        );
+    pub fn description(mut self, description: impl Into<Option<String>>) -> Self {
+        self.description = description.into();
+        self
     }
 
-    #[test]
-    fn test_keybind_with_properties() {
-        let keybind = Keybind {
-            modifiers: vec![Modifier::Mod],
-            key: "WheelScrollDown".to_string(),
-            action: "focus-workspace-down".to_string(),
-            description: None,
-            program: "niri".to_string(),
-            repeat: Some(false),
-            cooldown_ms: Some(150),
-            allow_when_locked: None,
-            allow_inhibiting: None,
-        };
+    pub fn repeat(mut self, repeat: impl Into<Option<bool>>) -> Self {
+        self.repeat = repeat.into();
+        self
+    }
 
-        assert_eq!(
-            keybind.to_string(),
-            "Mod+WheelScrollDown - focus-workspace-down (no-repeat, cooldown=150ms) [niri]"
-        );
+    pub fn cooldown_ms(mut self, cooldown_ms: impl Into<Option<u64>>) -> Self {
+        self.cooldown_ms = cooldown_ms.into();
+        self
     }
 
-    #[test]
-    fn test_keybind_allow_when_locked() {
-        let keybind = Keybind {
-            modifiers: vec![],
-            key: "XF86AudioRaiseVolume".to_string(),
-            action: "spawn-sh".to_string(),
-            description: Some("Volume Up".to_string()),
-            program: "niri".to_string(),
-            repeat: None,
-            cooldown_ms: None,
-            allow_when_locked: Some(true),
-            allow_inhibiting: None,
-        };
+    pub fn allow_when_locked(mut self, allow_when_locked: impl Into<Option<bool>>) -> Self {
+        self.allow_when_locked = allow_when_locked.into();
+        self
+    }
 
-        assert_eq!(
-            keybind.to_string(),
-            "XF86AudioRaiseVolume - Volume Up (allow-locked) [niri]"
+    pub fn allow_inhibiting(mut self, allow_inhibiting: impl Into<Option<bool>>) -> Self {
+        self.allow_inhibiting = allow_inhibiting.into();
+        self
+    }
+
+    pub fn hidden_in_overlay(mut self, hidden: bool) -> Self {
+        self.hidden_in_overlay = hidden;
+        self
+    }
+
+    pub fn config_mtime(mut self, config_mtime: impl Into<Option<u64>>) -> Self {
+        self.config_mtime = config_mtime.into();
+        self
+    }
+
+    pub fn has_cooldown(&self) -> bool {
+        self.cooldown_ms.is_some()
+    }
+
+    /// Looks up a boolean property by its niri name
+    /// (`repeat`, `allow-when-locked` or `allow-inhibiting`).
+    pub fn bool_property(&self, name: &str) -> Option<bool> {
+        match name {
+            "repeat" => self.repeat,
+            "allow-when-locked" => self.allow_when_locked,
+            "allow-inhibiting" => self.allow_inhibiting,
+            _ => None,
+        }
+    }
+
+    /// Looks up a numeric property by its niri name (`cooldown-ms`).
+    pub fn u64_property(&self, name: &str) -> Option<u64> {
+        match name {
+            "cooldown-ms" => self.cooldown_ms,
+            _ => None,
+        }
+    }
+
+    /// Whether the keybind carries one of the [`PROPERTY_NAMES`] annotations.
+    ///
+    /// Unknown names never match.
+    pub fn has_property(&self, name: &str) -> bool {
+        match name {
+            "no-repeat" => self.bool_property("repeat") == Some(false),
+            "cooldown" => self.has_cooldown(),
+            "allow-locked" => self.bool_property("allow-when-locked") == Some(true),
+            "no-inhibit" => self.bool_property("allow-inhibiting") == Some(false),
+            "overlay-hidden" => self.hidden_in_overlay,
+            _ => false,
+        }
+    }
+
+    /// Whether the keybind's program is one of `programs`, compared with [`Program::is_named`].
+    pub fn program_in<S: AsRef<str>>(&self, programs: &[S]) -> bool {
+        programs
+            .iter()
+            .any(|name| self.program.is_named(name.as_ref()))
+    }
+
+    /// Whether the key combination, action or description contains `query`, ignoring case.
+    ///
+    /// An empty query matches every keybind.
+    pub fn matches_query(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+
+        self.keys_string().to_lowercase().contains(&query)
+            || self.action.to_lowercase().contains(&query)
+            || self
+                .description
+                .as_ref()
+                .is_some_and(|d| d.to_lowercase().contains(&query))
+    }
+
+    /// Whether the action contains `action`, ignoring case, e.g. `alacritty` for
+    /// `spawn "alacritty"`.
+    ///
+    /// Unlike [`matches_query`](Self::matches_query), keys and descriptions aren't searched.
+    pub fn is_bound_to(&self, action: &str) -> bool {
+        self.action.to_lowercase().contains(&action.to_lowercase())
+    }
+
+    /// Best fuzzy score of `query` against the key combination, action or description.
+    ///
+    /// Returns `None` if `query` isn't a subsequence of any of them.
+    pub fn fuzzy_score(&self, query: &str) -> Option<u32> {
+        [
+            Some(self.keys_string().as_str()),
+            Some(self.action.as_str()),
+            self.description.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|text| fuzzy::score(text, query))
+        .max()
+    }
+
+    /// Groups the keybind by what its action does, e.g. `focus-column-left` is [`Category::Window`].
+    ///
+    /// Media keys are recognized by key name for every program; otherwise the
+    /// action is matched against per-program prefixes.
+    pub fn category(&self) -> Category {
+        if self.key_kind() == KeyKind::Media {
+            return Category::Media;
+        }
+
+        CATEGORY_RULES
+            .iter()
+            .find(|(program, prefix, _)| {
+                *program == self.program && self.action.starts_with(prefix)
+            })
+            .map_or(Category::Other, |(_, _, category)| *category)
+    }
+
+    /// Classifies the key name; for key sequences only the first key is considered.
+    pub fn key_kind(&self) -> KeyKind {
+        let key = self.key.first_key();
+
+        let mut chars = key.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return if c.is_whitespace() {
+                KeyKind::Unknown
+            } else {
+                KeyKind::Letter
+            };
+        }
+
+        if let Some(number) = key.strip_prefix('F').or_else(|| key.strip_prefix('f'))
+            && let Ok(n) = number.parse::<u8>()
+            && (1..=35).contains(&n)
+        {
+            return KeyKind::Function;
+        }
+
+        if key.starts_with("XF86") {
+            return KeyKind::Media;
+        }
+
+        if ["Mouse", "Wheel", "Touchpad"]
+            .iter()
+            .any(|prefix| key.starts_with(prefix))
+        {
+            return KeyKind::Mouse;
+        }
+
+        if NAMED_KEYS.iter().any(|name| name.eq_ignore_ascii_case(key)) {
+            return KeyKind::Named;
+        }
+
+        KeyKind::Unknown
+    }
+
+    /// The first step's modifiers sorted into their canonical order.
+    pub fn normalized_modifiers(&self) -> Vec<Modifier> {
+        self.modifier_mask().modifiers()
+    }
+
+    /// The first step's modifiers as a set, e.g. for comparing keybinds' modifiers.
+    pub fn modifier_mask(&self) -> ModifierMask {
+        self.key.modifiers().iter().collect()
+    }
+
+    /// Short labels for the properties that differ from the defaults, e.g. `cooldown=150ms`.
+    pub fn property_annotations(&self) -> Vec<String> {
+        let mut props = Vec::new();
+        if let Some(false) = self.repeat {
+            props.push("no-repeat".to_string());
+        }
+        if let Some(cooldown) = self.cooldown_ms {
+            props.push(format!("cooldown={}ms", cooldown));
+        }
+        if let Some(true) = self.allow_when_locked {
+            props.push("allow-locked".to_string());
+        }
+        if let Some(false) = self.allow_inhibiting {
+            props.push("no-inhibit".to_string());
+        }
+        if self.hidden_in_overlay {
+            props.push("overlay-hidden".to_string());
+        }
+        if let Some(mtime) = self.config_mtime {
+            props.push(format!("modified={}", utc_timestamp(mtime)));
+        }
+        props
+    }
+
+    /// A copy of this keybind with its modifiers in canonical order.
+    pub fn normalized(&self) -> Keybind {
+        Keybind {
+            key: self.key.normalized(),
+            ..self.clone()
+        }
+    }
+
+    /// Canonical identity of the key chord, e.g. `Mod+Shift+T`.
+    ///
+    /// Modifiers are sorted into a fixed order so that `Shift+Mod+T` and
+    /// `Mod+Shift+T` share a signature. Program, action and description are
+    /// ignored, which makes this suitable for grouping and conflict detection.
+    pub fn signature(&self) -> String {
+        self.key.normalized().to_string()
+    }
+
+    /// The key combination as written by the source, e.g. `Mod+Shift+T`.
+    ///
+    /// This is the leading part of the `Display` output.
+    pub fn keys_string(&self) -> String {
+        self.key.to_string()
+    }
+
+    /// Writes the keybind back out as a niri `binds` entry, the inverse of niri's parser.
+    ///
+    /// Produces e.g. `Mod+T hotkey-overlay-title="Open a Terminal" { spawn "alacritty"; }`.
+    /// The description is written as the title, and properties only when they are set.
+    pub fn to_niri_kdl(&self) -> String {
+        let mut kdl = self.keys_string();
+
+        if let Some(description) = &self.description {
+            kdl.push_str(&format!(
+                " hotkey-overlay-title={}",
+                kdl_string(description)
+            ));
+        } else if self.hidden_in_overlay {
+            kdl.push_str(" hotkey-overlay-title=null");
+        }
+        if let Some(repeat) = self.repeat {
+            kdl.push_str(&format!(" repeat={}", repeat));
+        }
+        if let Some(cooldown) = self.cooldown_ms {
+            kdl.push_str(&format!(" cooldown-ms={}", cooldown));
+        }
+        if let Some(allow) = self.allow_when_locked {
+            kdl.push_str(&format!(" allow-when-locked={}", allow));
+        }
+        if let Some(allow) = self.allow_inhibiting {
+            kdl.push_str(&format!(" allow-inhibiting={}", allow));
+        }
+
+        // Multiple actions are joined with ", "; split them outside of quoted arguments
+        kdl.push_str(" {");
+        let mut in_string = false;
+        let mut action = String::new();
+        let mut chars = self.action.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '"' {
+                in_string = !in_string;
+            }
+            if !in_string && c == ',' && chars.peek() == Some(&' ') {
+                chars.next();
+                kdl.push_str(&format!(" {};", action));
+                action.clear();
+            } else {
+                action.push(c);
+            }
+        }
+        kdl.push_str(&format!(" {}; }}", action));
+
+        kdl
+    }
+
+    /// Rejoins the chord steps with `separator`, e.g. `" "` for `ctrl+k ctrl+c` style.
+    ///
+    /// This is for display only: the steps after the first are folded into the
+    /// first step's key, so the result is no longer a sequence.
+    pub fn with_chord_separator(mut self, separator: &str) -> Self {
+        if separator != CHORD_SEPARATOR && self.key.is_sequence() {
+            let modifiers = self.key.modifiers().to_vec();
+            let mut key = self.key.first_key().to_string();
+            for (step_modifiers, step_key) in &self.key.0[1..] {
+                key.push_str(separator);
+                key.push_str(&join_keys(step_modifiers, step_key));
+            }
+            self.key = Chord::single(modifiers, key);
+        }
+        self
+    }
+
+    /// Writes `Mod` as `Super`, see [`Chord::with_mod_as_super`].
+    pub fn with_mod_as_super(mut self) -> Self {
+        self.key = self.key.with_mod_as_super();
+        self
+    }
+
+    /// Shortens the action to at most `width` terminal cells, ending it with `…` when cut.
+    ///
+    /// Like [`with_chord_separator`](Self::with_chord_separator) this is for
+    /// display only, so JSON output isn't shortened.
+    pub fn with_action_width(mut self, width: usize) -> Self {
+        truncate_to_width(&mut self.action, width);
+        self
+    }
+}
+
+/// Shortens `text` to at most `width` terminal cells, ending it with `…` when cut.
+fn truncate_to_width(text: &mut String, width: usize) {
+    if text.width() <= width {
+        return;
+    }
+
+    // Leave a cell for the ellipsis
+    let mut used = 0;
+    let end = text
+        .char_indices()
+        .find(|(_, c)| {
+            used += c.width().unwrap_or(0);
+            used > width.saturating_sub(1)
+        })
+        .map_or(text.len(), |(i, _)| i);
+    text.truncate(end);
+    text.push('…');
+}
+
+/// JSON Schema for a serialized [`Keybind`], generated from the type itself.
+pub fn json_schema() -> serde_json::Value {
+    schemars::schema_for!(Keybind).to_value()
+}
+
+/// How the steps of a multi-key [`Chord`] are joined when displayed, e.g. `Ctrl+x>Ctrl+f`.
+pub const CHORD_SEPARATOR: &str = ">";
+
+/// Field to order keybinds by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    /// The key name, then the modifiers
+    Key,
+    /// The action
+    Action,
+    /// The program, then the full keybind ordering
+    Program,
+}
+
+impl SortKey {
+    /// Orders `a` and `b` by this field, falling back to the `Ord` ordering for ties.
+    pub fn compare(self, a: &Keybind, b: &Keybind) -> std::cmp::Ordering {
+        match self {
+            SortKey::Key => {
+                let keys = |keybind: &Keybind| {
+                    keybind
+                        .key
+                        .steps()
+                        .iter()
+                        .map(|(_, key)| key.clone())
+                        .collect::<Vec<_>>()
+                };
+                keys(a).cmp(&keys(b)).then_with(|| a.cmp(b))
+            }
+            SortKey::Action => a.action.cmp(&b.action).then_with(|| a.cmp(b)),
+            SortKey::Program => a.cmp(b),
+        }
+    }
+}
+
+/// Sorts keybinds by `by`, falling back to the `Ord` ordering for ties.
+pub fn sort_keybinds(keybinds: &mut [Keybind], by: SortKey) {
+    keybinds.sort_by(|a, b| by.compare(a, b));
+}
+
+/// Gathers keybinds from the same program together, ordering programs by name.
+///
+/// The sort is stable, so keybinds keep their current order within each program.
+pub fn group_by_program(keybinds: &mut [Keybind]) {
+    group_by_program_with(keybinds, |_, _| std::cmp::Ordering::Equal);
+}
+
+/// Like [`group_by_program`], but orders the keybinds within each program with `within`.
+///
+/// Programs stay in name order whatever `within` does, e.g. for `--sort-within-group`.
+pub fn group_by_program_with(
+    keybinds: &mut [Keybind],
+    mut within: impl FnMut(&Keybind, &Keybind) -> std::cmp::Ordering,
+) {
+    keybinds.sort_by(|a, b| a.program.cmp(&b.program).then_with(|| within(a, b)));
+}
+
+/// Groups keybinds by their normalized modifiers, e.g. every `Mod+Shift+` bind together.
+///
+/// Groups are ordered by modifier rank, with unmodified keys first, and keybinds
+/// keep their current order within each group.
+pub fn group_by_modifiers(keybinds: &[Keybind]) -> Vec<(Vec<Modifier>, Vec<&Keybind>)> {
+    let mut groups: BTreeMap<Vec<Modifier>, Vec<&Keybind>> = BTreeMap::new();
+    for keybind in keybinds {
+        groups
+            .entry(keybind.normalized_modifiers())
+            .or_default()
+            .push(keybind);
+    }
+    groups.into_iter().collect()
+}
+
+/// Groups keybinds by [`Keybind::category`], in the order categories are declared.
+///
+/// Keybinds keep their current order within each group.
+pub fn group_by_category(keybinds: &[Keybind]) -> Vec<(Category, Vec<&Keybind>)> {
+    let mut groups: BTreeMap<Category, Vec<&Keybind>> = BTreeMap::new();
+    for keybind in keybinds {
+        groups.entry(keybind.category()).or_default().push(keybind);
+    }
+    groups.into_iter().collect()
+}
+
+/// Finds chords bound more than once, in one program or across programs.
+///
+/// Each group holds the keybinds sharing one [`Keybind::signature`], ordered by
+/// signature. A chord niri grabs never reaches kitty, so the same chord in two
+/// programs is a conflict too; with [`Keybind::with_mod_as_super`] applied,
+/// niri's `Mod+T` and kitty's `Super+T` are the same chord.
+pub fn find_conflicts(keybinds: &[Keybind]) -> Vec<Vec<&Keybind>> {
+    let mut by_chord: BTreeMap<String, Vec<&Keybind>> = BTreeMap::new();
+    for keybind in keybinds {
+        // Sources differ in letter case, e.g. niri's `T` and kitty's `t`
+        by_chord
+            .entry(keybind.signature().to_lowercase())
+            .or_default()
+            .push(keybind);
+    }
+
+    by_chord
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+/// Keeps only the keybinds in a [`find_conflicts`] group that `keep` accepts,
+/// in their original order, each described with what else its chord is bound to.
+///
+/// `Mod+T` bound to both `spawn "foot"` and `spawn "alacritty"` becomes two
+/// keybinds described as e.g. `spawn "foot" (conflicts with spawn "alacritty" [niri])`,
+/// so that a list of them says which bind to go fix, and in which program. Conflicts are found among
+/// all of `keybinds`, so a bind `keep` rejects still conflicts with the rest.
+pub fn conflicting(keybinds: &[Keybind], keep: impl Fn(&Keybind) -> bool) -> Vec<Keybind> {
+    let shown = |keybind: &Keybind| {
+        keybind
+            .description
+            .clone()
+            .unwrap_or_else(|| keybind.action.clone())
+    };
+
+    let mut conflicts: HashMap<&Keybind, String> = HashMap::new();
+    for group in find_conflicts(keybinds) {
+        for (i, keybind) in group.iter().enumerate() {
+            let others: Vec<String> = group
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, other)| format!("{} [{}]", shown(other), other.program))
+                .collect();
+            conflicts.insert(keybind, others.join(", "));
+        }
+    }
+
+    keybinds
+        .iter()
+        .filter(|keybind| keep(keybind))
+        .filter_map(|keybind| {
+            let others = conflicts.get(keybind)?;
+            let mut keybind = keybind.clone();
+            keybind.description = Some(format!("{} (conflicts with {})", shown(&keybind), others));
+            Some(keybind)
+        })
+        .collect()
+}
+
+/// Finds keybinds overridden by a later keybind of the same chord in the same program.
+///
+/// Sources yield keybinds in document order, and niri only keeps the last
+/// definition of a chord, so every earlier one is unreachable. The shadowed
+/// keybinds are returned in their original order.
+pub fn find_shadowed(keybinds: &[Keybind]) -> Vec<&Keybind> {
+    let mut last: HashMap<(&str, String), usize> = HashMap::new();
+    for (i, keybind) in keybinds.iter().enumerate() {
+        last.insert((keybind.program.as_str(), keybind.signature()), i);
+    }
+
+    keybinds
+        .iter()
+        .enumerate()
+        .filter(|(i, keybind)| last[&(keybind.program.as_str(), keybind.signature())] != *i)
+        .map(|(_, keybind)| keybind)
+        .collect()
+}
+
+/// Counts how many keybinds use each modifier, e.g. to see which one is overloaded.
+///
+/// A modifier counts once per keybind, however many steps of its chord use it.
+/// Bare keybinds without any modifier aren't counted; see [`count_bare`].
+pub fn modifier_histogram(keybinds: &[Keybind]) -> BTreeMap<Modifier, usize> {
+    let mut histogram = BTreeMap::new();
+    for keybind in keybinds {
+        let used: ModifierMask = keybind
+            .key
+            .steps()
+            .iter()
+            .flat_map(|(modifiers, _)| modifiers)
+            .collect();
+        for modifier in used.modifiers() {
+            *histogram.entry(modifier).or_default() += 1;
+        }
+    }
+    histogram
+}
+
+/// Number of keybinds whose chord has no modifier in any step.
+pub fn count_bare(keybinds: &[Keybind]) -> usize {
+    keybinds
+        .iter()
+        .filter(|keybind| {
+            keybind
+                .key
+                .steps()
+                .iter()
+                .all(|(modifiers, _)| modifiers.is_empty())
+        })
+        .count()
+}
+
+/// How the keybinds of one config differ from another's, as found by [`diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeybindDiff {
+    /// Chords only bound in the new keybinds.
+    pub added: Vec<Keybind>,
+    /// Chords only bound in the old keybinds.
+    pub removed: Vec<Keybind>,
+    /// Chords bound in both to different actions, as `(old, new)`.
+    pub changed: Vec<(Keybind, Keybind)>,
+}
+
+impl KeybindDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares two sets of keybinds, matching them by program and [`Keybind::signature`].
+///
+/// A chord bound more than once counts as its last binding, which is the one
+/// that takes effect. Added and changed keybinds are listed in the order of
+/// `new`, removed ones in the order of `old`.
+pub fn diff(old: &[Keybind], new: &[Keybind]) -> KeybindDiff {
+    fn last_by_chord(keybinds: &[Keybind]) -> HashMap<(&str, String), &Keybind> {
+        keybinds
+            .iter()
+            .map(|keybind| ((keybind.program.as_str(), keybind.signature()), keybind))
+            .collect()
+    }
+
+    let old_by_chord = last_by_chord(old);
+    let new_by_chord = last_by_chord(new);
+    let mut result = KeybindDiff::default();
+
+    for keybind in new {
+        let chord = (keybind.program.as_str(), keybind.signature());
+        // Skip bindings overridden later in the same config
+        if !std::ptr::eq(new_by_chord[&chord], keybind) {
+            continue;
+        }
+        match old_by_chord.get(&chord) {
+            None => result.added.push(keybind.clone()),
+            Some(previous) if previous.action != keybind.action => {
+                result.changed.push(((*previous).clone(), keybind.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    for keybind in old {
+        let chord = (keybind.program.as_str(), keybind.signature());
+        if std::ptr::eq(old_by_chord[&chord], keybind) && !new_by_chord.contains_key(&chord) {
+            result.removed.push(keybind.clone());
+        }
+    }
+
+    result
+}
+
+/// Several chords in one program that trigger the same action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedKeybind {
+    /// Key combinations in the order they were found, e.g. `["Mod+H", "Mod+Left"]`.
+    pub chords: Vec<String>,
+    pub action: String,
+    /// The first description among the merged keybinds.
+    pub description: Option<String>,
+    pub program: Program,
+}
+
+impl MergedKeybind {
+    /// Shortens the action to at most `width` terminal cells, see [`Keybind::with_action_width`].
+    pub fn with_action_width(mut self, width: usize) -> Self {
+        truncate_to_width(&mut self.action, width);
+        self
+    }
+}
+
+impl fmt::Display for MergedKeybind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} - {} [{}]",
+            self.chords.join(" / "),
+            self.description.as_deref().unwrap_or(&self.action),
+            self.program
+        )
+    }
+}
+
+/// Merges keybinds that share a program and action into one entry per action.
+///
+/// Entries are ordered by where their first chord appeared.
+pub fn merge_by_action(keybinds: &[Keybind]) -> Vec<MergedKeybind> {
+    let mut merged: Vec<MergedKeybind> = Vec::new();
+    let mut positions: HashMap<(&str, &str), usize> = HashMap::new();
+
+    for keybind in keybinds {
+        let key = (keybind.program.as_str(), keybind.action.as_str());
+        match positions.get(&key) {
+            Some(&position) => {
+                let entry = &mut merged[position];
+                entry.chords.push(keybind.keys_string());
+                if entry.description.is_none() {
+                    entry.description = keybind.description.clone();
+                }
+            }
+            None => {
+                positions.insert(key, merged.len());
+                merged.push(MergedKeybind {
+                    chords: vec![keybind.keys_string()],
+                    action: keybind.action.clone(),
+                    description: keybind.description.clone(),
+                    program: keybind.program.clone(),
+                });
+            }
+        }
+    }
+
+    merged
+}
+
+/// Removes keybinds that are identical apart from modifier order, keeping the first.
+pub fn dedupe(keybinds: &mut Vec<Keybind>) {
+    let mut seen = HashSet::new();
+    keybinds.retain(|keybind| seen.insert(keybind.normalized()));
+}
+
+/// Fewest keybinds in a row that [`collapse_numeric`] folds into one.
+const MIN_NUMERIC_RUN: usize = 3;
+
+/// Folds runs of digit keybinds sharing an action template into one keybind.
+///
+/// `Mod+1`..`Mod+9` bound to `focus-workspace 1`..`focus-workspace 9` become
+/// `Mod+[1-9] - focus-workspace N`. A run is at least [`MIN_NUMERIC_RUN`]
+/// keybinds of one program pressing consecutive digits with the same
+/// modifiers, whose actions differ only in a word that is the digit itself.
+/// The folded keybind takes the place of the run's first keybind; digits that
+/// skip a number are left alone.
+pub fn collapse_numeric(keybinds: Vec<Keybind>) -> Vec<Keybind> {
+    let templates: Vec<Option<(Keybind, u32)>> = keybinds.iter().map(numeric_template).collect();
+    let mut runs: HashMap<&Keybind, Vec<usize>> = HashMap::new();
+    for (i, template) in templates.iter().enumerate() {
+        if let Some((template, _)) = template {
+            runs.entry(template).or_default().push(i);
+        }
+    }
+
+    let mut collapsed: HashMap<usize, Keybind> = HashMap::new();
+    let mut folded = HashSet::new();
+    for (template, members) in runs {
+        let mut digits: Vec<u32> = members
+            .iter()
+            .filter_map(|&i| templates[i].as_ref().map(|(_, digit)| *digit))
+            .collect();
+        digits.sort_unstable();
+        if digits.len() < MIN_NUMERIC_RUN || digits.windows(2).any(|w| w[1] != w[0] + 1) {
+            continue;
+        }
+
+        let mut keybind = template.clone();
+        keybind.key = Chord::single(
+            template.key.modifiers().to_vec(),
+            format!("[{}-{}]", digits[0], digits[digits.len() - 1]),
+        );
+        collapsed.insert(members[0], keybind);
+        folded.extend(members[1..].iter().copied());
+    }
+
+    keybinds
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !folded.contains(i))
+        .map(|(i, keybind)| collapsed.remove(&i).unwrap_or(keybind))
+        .collect()
+}
+
+/// The keybind with its digit key and that digit's words in the action and
+/// description replaced by `N`, along with the digit.
+///
+/// Returns `None` unless the key is a single digit the action mentions.
+fn numeric_template(keybind: &Keybind) -> Option<(Keybind, u32)> {
+    if keybind.key.is_sequence() {
+        return None;
+    }
+    let key = keybind.key.first_key();
+    let mut chars = key.chars();
+    let digit = chars
+        .next()?
+        .to_digit(10)
+        .filter(|_| chars.next().is_none())?;
+
+    let template = |text: &str| {
+        let words: Vec<&str> = text
+            .split(' ')
+            .map(|word| if word == key { "N" } else { word })
+            .collect();
+        words.join(" ")
+    };
+    let action = template(&keybind.action);
+    if action == keybind.action {
+        return None;
+    }
+
+    let template = Keybind {
+        key: Chord::single(keybind.normalized_modifiers(), "N"),
+        action,
+        action_args: keybind
+            .action_args
+            .iter()
+            .map(|arg| template(arg))
+            .collect(),
+        description: keybind.description.as_deref().map(template),
+        ..keybind.clone()
+    };
+    Some((template, digit))
+}
+
+/// Scroll directions in the order [`collapse_directions`] lists them.
+const SCROLL_DIRECTIONS: [&str; 4] = ["Up", "Down", "Left", "Right"];
+
+/// Folds scroll keybinds that differ only in direction into one keybind.
+///
+/// `Mod+WheelScrollDown` and `Mod+WheelScrollUp` bound to `focus-workspace-down`
+/// and `focus-workspace-up` become `Mod+WheelScroll{Up,Down} - focus-workspace`.
+/// A family is two or more keybinds of one program with the same modifiers,
+/// scrolling the same way (e.g. `WheelScroll` or `TouchpadScroll`) in different
+/// directions, whose actions are the same once their direction is dropped. The
+/// folded keybind takes the place of the family's first keybind.
+pub fn collapse_directions(keybinds: Vec<Keybind>) -> Vec<Keybind> {
+    let templates: Vec<Option<(Keybind, usize)>> =
+        keybinds.iter().map(direction_template).collect();
+    let mut families: HashMap<&Keybind, Vec<usize>> = HashMap::new();
+    for (i, template) in templates.iter().enumerate() {
+        if let Some((template, _)) = template {
+            families.entry(template).or_default().push(i);
+        }
+    }
+
+    let mut collapsed: HashMap<usize, Keybind> = HashMap::new();
+    let mut folded = HashSet::new();
+    for (template, members) in families {
+        let mut directions: Vec<usize> = members
+            .iter()
+            .filter_map(|&i| templates[i].as_ref().map(|(_, direction)| *direction))
+            .collect();
+        directions.sort_unstable();
+        directions.dedup();
+        // A direction bound twice is a conflict, not a family
+        if directions.len() < 2 || directions.len() != members.len() {
+            continue;
+        }
+
+        let names: Vec<&str> = directions.iter().map(|&d| SCROLL_DIRECTIONS[d]).collect();
+        let mut keybind = template.clone();
+        keybind.key = Chord::single(
+            template.key.modifiers().to_vec(),
+            format!("{}{{{}}}", template.key.first_key(), names.join(",")),
+        );
+        collapsed.insert(members[0], keybind);
+        folded.extend(members[1..].iter().copied());
+    }
+
+    keybinds
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !folded.contains(i))
+        .map(|(i, keybind)| collapsed.remove(&i).unwrap_or(keybind))
+        .collect()
+}
+
+/// The keybind with its scroll key cut to the scroll kind, e.g. `WheelScroll`,
+/// and the direction dropped from the action and description, along with the
+/// direction's index in [`SCROLL_DIRECTIONS`].
+///
+/// Returns `None` unless the key is a scroll in a direction the action mentions,
+/// as a word or a `-`-separated part of one like `focus-workspace-down`.
+fn direction_template(keybind: &Keybind) -> Option<(Keybind, usize)> {
+    if keybind.key.is_sequence() {
+        return None;
+    }
+    let key = keybind.key.first_key();
+    let (direction, kind) = SCROLL_DIRECTIONS
+        .iter()
+        .enumerate()
+        .find_map(|(i, name)| key.strip_suffix(name).map(|kind| (i, kind)))?;
+    if !kind.ends_with("Scroll") {
+        return None;
+    }
+
+    let name = SCROLL_DIRECTIONS[direction].to_lowercase();
+    let template = |text: &str| {
+        let words: Vec<String> = text
+            .split(' ')
+            .map(|word| {
+                let parts: Vec<&str> = word.split('-').filter(|part| *part != name).collect();
+                parts.join("-")
+            })
+            .filter(|word| !word.is_empty())
+            .collect();
+        words.join(" ")
+    };
+    let action = template(&keybind.action);
+    if action == keybind.action {
+        return None;
+    }
+
+    let template = Keybind {
+        key: Chord::single(keybind.normalized_modifiers(), kind),
+        action,
+        action_args: keybind
+            .action_args
+            .iter()
+            .map(|arg| template(arg))
+            .collect(),
+        description: keybind.description.as_deref().map(template),
+        ..keybind.clone()
+    };
+    Some((template, direction))
+}
+
+/// Keeps the keybinds from one of `programs`, by name as in [`Keybind::program_in`].
+///
+/// An empty list keeps every keybind.
+pub fn filter_by_program<S: AsRef<str>>(keybinds: &mut Vec<Keybind>, programs: &[S]) {
+    if !programs.is_empty() {
+        keybinds.retain(|keybind| keybind.program_in(programs));
+    }
+}
+
+/// Keeps the keybinds fuzzy-matching `query`, best match first.
+///
+/// The sort is stable, so keybinds with equal scores keep their current order.
+pub fn rank_by_query(keybinds: &mut Vec<Keybind>, query: &str) {
+    let mut scored: Vec<(u32, Keybind)> = keybinds
+        .drain(..)
+        .filter_map(|keybind| keybind.fuzzy_score(query).map(|score| (score, keybind)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    keybinds.extend(scored.into_iter().map(|(_, keybind)| keybind));
+}
+
+/// Formats seconds since the Unix epoch as a UTC time, e.g. `2026-10-16T09:30:00Z`.
+fn utc_timestamp(secs: u64) -> String {
+    let (days, time) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01, after Howard Hinnant's `civil_from_days`
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+fn kdl_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn join_keys(modifiers: &[Modifier], key: &str) -> String {
+    let mut keys = String::new();
+    for modifier in modifiers {
+        keys.push_str(&format!("{}+", modifier));
+    }
+    keys.push_str(key);
+    keys
+}
+
+/// Keybinds sort by program, then normalized chord (modifiers before key, step by step), then action.
+///
+/// The remaining fields only break ties, keeping the ordering consistent with `Eq`.
+impl Ord for Keybind {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.program
+            .cmp(&other.program)
+            .then_with(|| self.key.normalized().cmp(&other.key.normalized()))
+            .then_with(|| self.action.cmp(&other.action))
+            .then_with(|| self.description.cmp(&other.description))
+            .then_with(|| self.action_name.cmp(&other.action_name))
+            .then_with(|| self.action_args.cmp(&other.action_args))
+            .then_with(|| self.key.cmp(&other.key))
+            .then_with(|| self.repeat.cmp(&other.repeat))
+            .then_with(|| self.cooldown_ms.cmp(&other.cooldown_ms))
+            .then_with(|| self.allow_when_locked.cmp(&other.allow_when_locked))
+            .then_with(|| self.allow_inhibiting.cmp(&other.allow_inhibiting))
+            .then_with(|| self.hidden_in_overlay.cmp(&other.hidden_in_overlay))
+            .then_with(|| self.config_mtime.cmp(&other.config_mtime))
+    }
+}
+
+impl PartialOrd for Keybind {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Keybind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.keys_string())?;
+
+        if let Some(desc) = &self.description {
+            write!(f, " - {}", desc)?;
+        } else {
+            write!(f, " - {}", self.action)?;
+        }
+
+        let props = self.property_annotations();
+        if !props.is_empty() {
+            write!(f, " ({})", props.join(", "))?;
+        }
+
+        write!(f, " [{}]", self.program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keybind_display() {
+        let keybind = Keybind {
+            key: Chord::single(vec![Modifier::Mod, Modifier::Shift], "T"),
+            action: "spawn-terminal".to_string(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: Some("Open Terminal".to_string()),
+            program: Program::Niri,
+            repeat: None,
+            cooldown_ms: None,
+            allow_when_locked: None,
+            allow_inhibiting: None,
+            hidden_in_overlay: false,
+            config_mtime: None,
+        };
+
+        assert_eq!(keybind.to_string(), "Mod+Shift+T - Open Terminal [niri]");
+    }
+
+    #[test]
+    fn test_keybind_no_description() {
+        let keybind = Keybind {
+            key: Chord::single(vec![Modifier::Mod], "Q"),
+            action: "close-window".to_string(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: None,
+            program: Program::Niri,
+            repeat: None,
+            cooldown_ms: None,
+            allow_when_locked: None,
+            allow_inhibiting: None,
+            hidden_in_overlay: false,
+            config_mtime: None,
+        };
+
+        assert_eq!(keybind.to_string(), "Mod+Q - close-window [niri]");
+    }
+
+    #[test]
+    fn test_keybind_no_modifiers() {
+        let keybind = Keybind {
+            key: Chord::single(vec![], "XF86AudioRaiseVolume"),
+            action: "volume-up".to_string(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: Some("Increase Volume".to_string()),
+            program: Program::Niri,
+            repeat: None,
+            cooldown_ms: None,
+            allow_when_locked: None,
+            allow_inhibiting: None,
+            hidden_in_overlay: false,
+            config_mtime: None,
+        };
+
+        assert_eq!(
+            keybind.to_string(),
+            "XF86AudioRaiseVolume - Increase Volume [niri]"
+        );
+    }
+
+    #[test]
+    fn test_keybind_with_properties() {
+        let keybind = Keybind {
+            key: Chord::single(vec![Modifier::Mod], "WheelScrollDown"),
+            action: "focus-workspace-down".to_string(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: None,
+            program: Program::Niri,
+            repeat: Some(false),
+            cooldown_ms: Some(150),
+            allow_when_locked: None,
+            allow_inhibiting: None,
+            hidden_in_overlay: false,
+            config_mtime: None,
+        };
+
+        assert_eq!(
+            keybind.to_string(),
+            "Mod+WheelScrollDown - focus-workspace-down (no-repeat, cooldown=150ms) [niri]"
+        );
+    }
+
+    #[test]
+    fn test_keybind_config_mtime() {
+        let keybind = Keybind::new("niri", "Q")
+            .modifiers(vec![Modifier::Mod])
+            .action("close-window");
+        let json = serde_json::to_string(&keybind).unwrap();
+        assert!(!json.contains("config_mtime"));
+
+        let keybind = keybind.config_mtime(1_792_143_000);
+        assert_eq!(
+            keybind.to_string(),
+            "Mod+Q - close-window (modified=2026-10-16T09:30:00Z) [niri]"
+        );
+        let json = serde_json::to_string(&keybind).unwrap();
+        assert!(json.contains(r#""config_mtime":1792143000"#));
+        assert_eq!(serde_json::from_str::<Keybind>(&json).unwrap(), keybind);
+
+        // Ordering agrees with equality when only the mtime differs
+        let older = keybind.clone().config_mtime(1_792_000_000);
+        assert_ne!(older, keybind);
+        assert_eq!(older.cmp(&keybind), Ordering::Less);
+    }
+
+    #[test]
+    fn test_utc_timestamp() {
+        assert_eq!(utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(utc_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(utc_timestamp(1_792_143_000), "2026-10-16T09:30:00Z");
+    }
+
+    #[test]
+    fn test_keybind_allow_when_locked() {
+        let keybind = Keybind {
+            key: Chord::single(vec![], "XF86AudioRaiseVolume"),
+            action: "spawn-sh".to_string(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: Some("Volume Up".to_string()),
+            program: Program::Niri,
+            repeat: None,
+            cooldown_ms: None,
+            allow_when_locked: Some(true),
+            allow_inhibiting: None,
+            hidden_in_overlay: false,
+            config_mtime: None,
+        };
+
+        assert_eq!(
+            keybind.to_string(),
+            "XF86AudioRaiseVolume - Volume Up (allow-locked) [niri]"
+        );
+    }
+
+    #[test]
+    fn test_signature_ignores_modifier_order() {
+        let keybind = Keybind {
+            key: Chord::single(vec![Modifier::Shift, Modifier::Mod], "T"),
+            action: "spawn-terminal".to_string(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: None,
+            program: Program::Niri,
+            repeat: None,
+            cooldown_ms: None,
+            allow_when_locked: None,
+            allow_inhibiting: None,
+            hidden_in_overlay: false,
+            config_mtime: None,
+        };
+        let reordered = Keybind {
+            key: Chord::single(vec![Modifier::Mod, Modifier::Shift], "T"),
+            action: "close-window".to_string(),
+            program: Program::Kitty,
+            ..keybind.clone()
+        };
+
+        assert_eq!(keybind.signature(), "Mod+Shift+T");
+        assert_eq!(keybind.signature(), reordered.signature());
+    }
+
+    #[test]
+    fn test_signature_differs_by_key() {
+        let keybind = Keybind {
+            key: Chord::single(vec![Modifier::Mod], "T"),
+            action: "spawn-terminal".to_string(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: None,
+            program: Program::Niri,
+            repeat: None,
+            cooldown_ms: None,
+            allow_when_locked: None,
+            allow_inhibiting: None,
+            hidden_in_overlay: false,
+            config_mtime: None,
+        };
+        let other = Keybind {
+            key: Chord::single(vec![Modifier::Mod], "Q"),
+            ..keybind.clone()
+        };
+
+        assert_ne!(keybind.signature(), other.signature());
+    }
+
+    #[test]
+    fn test_cooldown_predicate() {
+        let keybind = Keybind {
+            key: Chord::single(vec![Modifier::Mod], "WheelScrollDown"),
+            action: "focus-workspace-down".to_string(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: None,
+            program: Program::Niri,
+            repeat: None,
+            cooldown_ms: Some(150),
+            allow_when_locked: None,
+            allow_inhibiting: None,
+            hidden_in_overlay: false,
+            config_mtime: None,
+        };
+        let without = Keybind {
+            cooldown_ms: None,
+            ..keybind.clone()
+        };
+
+        assert!(keybind.has_cooldown());
+        assert!(keybind.has_property("cooldown"));
+        assert_eq!(keybind.u64_property("cooldown-ms"), Some(150));
+        assert!(!without.has_cooldown());
+        assert!(!without.has_property("cooldown"));
+    }
+
+    #[test]
+    fn test_allow_locked_predicate() {
+        let keybind = Keybind {
+            key: Chord::single(vec![], "XF86AudioRaiseVolume"),
+            action: "spawn-sh".to_string(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: None,
+            program: Program::Niri,
+            repeat: None,
+            cooldown_ms: None,
+            allow_when_locked: Some(true),
+            allow_inhibiting: None,
+            hidden_in_overlay: false,
+            config_mtime: None,
+        };
+        let disallowed = Keybind {
+            allow_when_locked: Some(false),
+            ..keybind.clone()
+        };
+
+        assert_eq!(keybind.bool_property("allow-when-locked"), Some(true));
+        assert!(keybind.has_property("allow-locked"));
+        assert!(!disallowed.has_property("allow-locked"));
+        assert!(!keybind.has_property("no-such-property"));
+    }
+
+    #[test]
+    fn test_builder_matches_struct_literal() {
+        let manual = Keybind {
+            key: Chord::single(vec![Modifier::Mod, Modifier::Shift], "WheelScrollDown"),
+            action: "focus-workspace-down".to_string(),
+            action_name: None,
+            action_args: Vec::new(),
+            description: Some("Next Workspace".to_string()),
+            program: Program::Niri,
+            repeat: Some(false),
+            cooldown_ms: Some(150),
+            allow_when_locked: Some(true),
+            allow_inhibiting: Some(false),
+            hidden_in_overlay: false,
+            config_mtime: None,
+        };
+
+        let built = Keybind::new("niri", "WheelScrollDown")
+            .modifiers(vec![Modifier::Mod, Modifier::Shift])
+            .action("focus-workspace-down")
+            .description("Next Workspace".to_string())
+            .repeat(false)
+            .cooldown_ms(150)
+            .allow_when_locked(true)
+            .allow_inhibiting(false);
+
+        assert_eq!(built, manual);
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let built = Keybind::new("kitty", "t");
+
+        assert_eq!(
+            built,
+            Keybind {
+                key: Chord::single(vec![], "t"),
+                action: String::new(),
+                action_name: None,
+                action_args: Vec::new(),
+                description: None,
+                program: Program::Kitty,
+                repeat: None,
+                cooldown_ms: None,
+                allow_when_locked: None,
+                allow_inhibiting: None,
+                hidden_in_overlay: false,
+                config_mtime: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_modifier_ordering() {
+        let mut modifiers = vec![
+            Modifier::Shift,
+            Modifier::IsoLevel5Shift,
+            Modifier::Alt,
+            Modifier::Mod,
+            Modifier::IsoLevel3Shift,
+            Modifier::Ctrl,
+            Modifier::Super,
+        ];
+        modifiers.sort();
+
+        assert_eq!(
+            modifiers,
+            vec![
+                Modifier::Mod,
+                Modifier::Super,
+                Modifier::Ctrl,
+                Modifier::Alt,
+                Modifier::Shift,
+                Modifier::IsoLevel3Shift,
+                Modifier::IsoLevel5Shift,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keybind_ordering() {
+        let kitty = Keybind::new("kitty", "t")
+            .modifiers(vec![Modifier::Ctrl])
+            .action("new_tab");
+        let niri_shift = Keybind::new("niri", "A")
+            .modifiers(vec![Modifier::Shift, Modifier::Mod])
+            .action("b-action");
+        let niri_mod_z = Keybind::new("niri", "Z")
+            .modifiers(vec![Modifier::Mod])
+            .action("a-action");
+        let niri_mod_a = Keybind::new("niri", "A")
+            .modifiers(vec![Modifier::Mod])
+            .action("z-action");
+        let niri_mod_a_first = Keybind::new("niri", "A")
+            .modifiers(vec![Modifier::Mod])
+            .action("a-action");
+
+        let mut keybinds = vec![
+            niri_shift.clone(),
+            niri_mod_z.clone(),
+            kitty.clone(),
+            niri_mod_a.clone(),
+            niri_mod_a_first.clone(),
+        ];
+        keybinds.sort();
+
+        assert_eq!(
+            keybinds,
+            vec![kitty, niri_mod_a_first, niri_mod_a, niri_mod_z, niri_shift]
+        );
+    }
+
+    #[test]
+    fn test_key_kind() {
+        let kind = |key: &str| Keybind::new("niri", key).key_kind();
+
+        assert_eq!(kind("T"), KeyKind::Letter);
+        assert_eq!(kind("F5"), KeyKind::Function);
+        assert_eq!(kind("XF86AudioPlay"), KeyKind::Media);
+        assert_eq!(kind("WheelUp"), KeyKind::Mouse);
+        assert_eq!(kind("Page_Up"), KeyKind::Named);
+        assert_eq!(kind("Frobnicate"), KeyKind::Unknown);
+    }
+
+    #[test]
+    fn test_keys_string() {
+        let bare = Keybind::new("niri", "XF86AudioMute").action("spawn");
+        let chord = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod, Modifier::Shift])
+            .action("spawn");
+
+        assert_eq!(bare.keys_string(), "XF86AudioMute");
+        assert_eq!(chord.keys_string(), "Mod+Shift+T");
+        assert!(chord.to_string().starts_with(&chord.keys_string()));
+    }
+
+    #[test]
+    fn test_chord_separator() {
+        let chord = Keybind::new(
+            "emacs",
+            Chord(vec![
+                (vec![Modifier::Ctrl], "x".to_string()),
+                (vec![Modifier::Ctrl], "f".to_string()),
+            ]),
+        )
+        .action("find-file");
+
+        assert_eq!(chord.keys_string(), "Ctrl+x>Ctrl+f");
+        assert_eq!(
+            chord.clone().with_chord_separator(" ").keys_string(),
+            "Ctrl+x Ctrl+f"
+        );
+        assert_eq!(
+            chord.with_chord_separator(", ").to_string(),
+            "Ctrl+x, Ctrl+f - find-file [emacs]"
+        );
+    }
+
+    #[test]
+    fn test_chord_literal_greater_than() {
+        let keybind = Keybind::new("kitty", ">");
+
+        assert!(!keybind.key.is_sequence());
+        assert_eq!(keybind.with_chord_separator(" ").keys_string(), ">");
+    }
+
+    #[test]
+    fn test_serialized_chord_round_trip() {
+        let sequence = Keybind::new(
+            "kitty",
+            Chord(vec![
+                (vec![Modifier::Ctrl], "x".to_string()),
+                (vec![Modifier::Ctrl, Modifier::Shift], "+".to_string()),
+            ]),
+        )
+        .action("new_window");
+        let greater_than = Keybind::new("kitty", ">").modifiers(vec![Modifier::Ctrl]);
+
+        let json = serde_json::to_value(&sequence).unwrap();
+        assert_eq!(json["modifiers"], serde_json::json!(["Ctrl"]));
+        assert_eq!(json["key"], "x>Ctrl+Shift++");
+
+        for keybind in [sequence, greater_than] {
+            let json = serde_json::to_string(&keybind).unwrap();
+            assert_eq!(serde_json::from_str::<Keybind>(&json).unwrap(), keybind);
+        }
+    }
+
+    #[test]
+    fn test_chord_from_str() {
+        assert_eq!(
+            "mod+Shift+E".parse::<Chord>(),
+            Ok(Chord::single(vec![Modifier::Mod, Modifier::Shift], "E"))
+        );
+        assert_eq!(
+            "Ctrl+x>Ctrl+f".parse::<Chord>(),
+            Ok(Chord(vec![
+                (vec![Modifier::Ctrl], "x".to_string()),
+                (vec![Modifier::Ctrl], "f".to_string()),
+            ]))
+        );
+        assert_eq!(
+            "Ctrl+>".parse::<Chord>(),
+            Ok(Chord::single(vec![Modifier::Ctrl], ">"))
+        );
+        assert!("Hyperr+T".parse::<Chord>().is_err());
+        assert!("".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    fn test_chord_is_same_as() {
+        let query: Chord = "Shift+Mod+e".parse().unwrap();
+
+        assert!(query.is_same_as(&Chord::single(vec![Modifier::Mod, Modifier::Shift], "E")));
+        assert!(!query.is_same_as(&Chord::single(vec![Modifier::Mod], "E")));
+        assert!(!query.is_same_as(&Chord::single(vec![Modifier::Mod, Modifier::Shift], "Q")));
+    }
+
+    #[test]
+    fn test_action_width() {
+        let keybind = Keybind::new("kitty", "F1").action("send_text all Hello, world!");
+
+        assert_eq!(keybind.clone().with_action_width(12).action, "send_text a…");
+        assert_eq!(
+            keybind.clone().with_action_width(26).action,
+            "send_text all Hello, worl…"
+        );
+        assert_eq!(
+            keybind.clone().with_action_width(27).action,
+            "send_text all Hello, world!"
+        );
+        assert_eq!(
+            Keybind::new("kitty", "F2")
+                .action("send_text 日本語")
+                .with_action_width(13)
+                .action,
+            "send_text 日…"
+        );
+    }
+
+    #[test]
+    fn test_matches_query() {
+        let keybind = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("spawn \"alacritty\"")
+            .description("Open a Terminal".to_string());
+
+        assert!(keybind.matches_query("ALACRITTY"));
+        assert!(keybind.matches_query("terminal"));
+        assert!(keybind.matches_query("mod+t"));
+        assert!(keybind.matches_query(""));
+        assert!(!keybind.matches_query("firefox"));
+    }
+
+    #[test]
+    fn test_is_bound_to() {
+        let keybind = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("spawn \"alacritty\"")
+            .description("Open a Terminal".to_string());
+
+        assert!(keybind.is_bound_to("Alacritty"));
+        assert!(keybind.is_bound_to("spawn"));
+        assert!(!keybind.is_bound_to("terminal"));
+        assert!(!keybind.is_bound_to("mod+t"));
+    }
+
+    #[test]
+    fn test_fuzzy_subsequence_match() {
+        let keybind = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod, Modifier::Shift])
+            .action("spawn \"alacritty\"");
+
+        assert!(keybind.fuzzy_score("mst").is_some());
+        assert!(keybind.fuzzy_score("spwn").is_some());
+        assert_eq!(keybind.fuzzy_score("xyz"), None);
+    }
+
+    #[test]
+    fn test_rank_by_query_orders_by_score() {
+        let weak = Keybind::new("niri", "O")
+            .modifiers(vec![Modifier::Mod])
+            .action("mistletoe");
+        let strong = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod, Modifier::Shift])
+            .action("close-window");
+        let unmatched = Keybind::new("niri", "Q").action("quit");
+
+        let mut keybinds = vec![weak.clone(), unmatched, strong.clone()];
+        rank_by_query(&mut keybinds, "mst");
+
+        assert_eq!(keybinds, vec![strong, weak]);
+    }
+
+    #[test]
+    fn test_sort_by_key() {
+        let b = Keybind::new("niri", "B").action("a");
+        let a = Keybind::new("kitty", "A").action("z");
+        let c = Keybind::new("emacs", "C").action("m");
+
+        let mut keybinds = vec![b.clone(), c.clone(), a.clone()];
+        sort_keybinds(&mut keybinds, SortKey::Key);
+        assert_eq!(keybinds, vec![a.clone(), b.clone(), c.clone()]);
+
+        sort_keybinds(&mut keybinds, SortKey::Action);
+        assert_eq!(keybinds, vec![b, c, a]);
+    }
+
+    #[test]
+    fn test_collapse_numeric_workspaces() {
+        let mut keybinds = vec![
+            Keybind::new("niri", "T")
+                .modifiers(vec![Modifier::Mod])
+                .action("spawn alacritty"),
+        ];
+        for n in 1..=9 {
+            keybinds.push(
+                Keybind::new("niri", n.to_string())
+                    .modifiers(vec![Modifier::Mod])
+                    .action(format!("focus-workspace {}", n)),
+            );
+        }
+
+        let collapsed = collapse_numeric(keybinds);
+
+        assert_eq!(collapsed.len(), 2);
+        assert_eq!(collapsed[0].action, "spawn alacritty");
+        assert_eq!(
+            collapsed[1].to_string(),
+            "Mod+[1-9] - focus-workspace N [niri]"
+        );
+    }
+
+    #[test]
+    fn test_collapse_numeric_skips_gaps() {
+        let keybinds: Vec<Keybind> = [1, 2, 4, 5]
+            .into_iter()
+            .map(|n| {
+                Keybind::new("niri", n.to_string())
+                    .modifiers(vec![Modifier::Mod])
+                    .action(format!("focus-workspace {}", n))
+            })
+            .collect();
+
+        assert_eq!(collapse_numeric(keybinds.clone()), keybinds);
+    }
+
+    #[test]
+    fn test_collapse_directions_scroll_pair() {
+        let scroll = |key: &str, action: &str| {
+            Keybind::new("niri", key)
+                .modifiers(vec![Modifier::Mod])
+                .action(action)
+                .cooldown_ms(Some(150))
+        };
+        let keybinds = vec![
+            scroll("WheelScrollDown", "focus-workspace-down"),
+            scroll("WheelScrollUp", "focus-workspace-up"),
+            // Scrolling down to move right isn't a direction family
+            scroll("WheelScrollDown", "focus-column-right")
+                .modifiers(vec![Modifier::Mod, Modifier::Shift]),
+            Keybind::new("niri", "Down")
+                .modifiers(vec![Modifier::Mod])
+                .action("focus-window-down"),
+        ];
+
+        let collapsed = collapse_directions(keybinds.clone());
+
+        assert_eq!(collapsed.len(), 3);
+        assert_eq!(
+            collapsed[0].to_string(),
+            "Mod+WheelScroll{Up,Down} - focus-workspace (cooldown=150ms) [niri]"
+        );
+        assert_eq!(collapsed[1..], keybinds[2..]);
+    }
+
+    #[test]
+    fn test_dedupe_ignores_modifier_order() {
+        let mut keybinds = vec![
+            Keybind::new("niri", "T")
+                .modifiers(vec![Modifier::Shift, Modifier::Mod])
+                .action("spawn"),
+            Keybind::new("niri", "T")
+                .modifiers(vec![Modifier::Mod, Modifier::Shift])
+                .action("spawn"),
+            Keybind::new("kitty", "T")
+                .modifiers(vec![Modifier::Mod, Modifier::Shift])
+                .action("spawn"),
+        ];
+
+        dedupe(&mut keybinds);
+
+        assert_eq!(keybinds.len(), 2);
+        assert_eq!(
+            keybinds[0].key.modifiers(),
+            [Modifier::Shift, Modifier::Mod]
+        );
+    }
+
+    #[test]
+    fn test_dedupe_many_duplicates() {
+        let modifier_orders = [
+            vec![Modifier::Mod, Modifier::Shift],
+            vec![Modifier::Shift, Modifier::Mod],
+        ];
+        let mut keybinds: Vec<Keybind> = (0..10_000)
+            .map(|i| {
+                Keybind::new("niri", format!("F{}", i % 100))
+                    .modifiers(modifier_orders[i % 2].clone())
+                    .action(format!("action-{}", i % 300))
+            })
+            .collect();
+
+        dedupe(&mut keybinds);
+
+        // Keys repeat every 100 and actions every 300, so pairs repeat every 300
+        assert_eq!(keybinds.len(), 300);
+    }
+
+    #[test]
+    fn test_group_by_program_keeps_order_within_groups() {
+        let niri_b = Keybind::new("niri", "B");
+        let kitty_z = Keybind::new("kitty", "Z");
+        let niri_a = Keybind::new("niri", "A");
+        let kitty_y = Keybind::new("kitty", "Y");
+
+        let mut keybinds = vec![
+            niri_b.clone(),
+            kitty_z.clone(),
+            niri_a.clone(),
+            kitty_y.clone(),
+        ];
+        group_by_program(&mut keybinds);
+
+        assert_eq!(keybinds, vec![kitty_z, kitty_y, niri_b, niri_a]);
+    }
+
+    #[test]
+    fn test_group_by_program_with_sorts_within_groups() {
+        let kitty_z = Keybind::new("kitty", "Z").action("a");
+        let kitty_y = Keybind::new("kitty", "Y").action("b");
+        let niri_b = Keybind::new("niri", "B").action("c");
+        let niri_a = Keybind::new("niri", "A").action("d");
+
+        let mut keybinds = vec![
+            niri_b.clone(),
+            kitty_z.clone(),
+            niri_a.clone(),
+            kitty_y.clone(),
+        ];
+        group_by_program_with(&mut keybinds, |a, b| SortKey::Key.compare(a, b));
+        assert_eq!(
+            keybinds,
+            vec![
+                kitty_y.clone(),
+                kitty_z.clone(),
+                niri_a.clone(),
+                niri_b.clone()
+            ]
+        );
+
+        // Reversing the order within groups leaves the groups themselves in place
+        let mut keybinds = vec![
+            niri_a.clone(),
+            kitty_y.clone(),
+            niri_b.clone(),
+            kitty_z.clone(),
+        ];
+        group_by_program_with(&mut keybinds, |a, b| SortKey::Action.compare(b, a));
+        assert_eq!(keybinds, vec![kitty_y, kitty_z, niri_a, niri_b]);
+    }
+
+    #[test]
+    fn test_find_conflicts_within_program() {
+        let spawn = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("spawn");
+        let close = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("close-window");
+        let other_modifiers = Keybind::new("kitty", "t")
+            .modifiers(vec![Modifier::Ctrl])
+            .action("new_tab");
+        let other_key = Keybind::new("niri", "Q")
+            .modifiers(vec![Modifier::Mod])
+            .action("quit");
+        let keybinds = vec![spawn.clone(), other_modifiers, close.clone(), other_key];
+
+        let conflicts = find_conflicts(&keybinds);
+
+        assert_eq!(conflicts, vec![vec![&spawn, &close]]);
+    }
+
+    #[test]
+    fn test_find_conflicts_across_programs() {
+        let niri = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Super])
+            .action("spawn");
+        let kitty = Keybind::new("kitty", "t")
+            .modifiers(vec![Modifier::Super])
+            .action("new_tab");
+        let keybinds = vec![niri.clone(), kitty.clone()];
+
+        assert_eq!(find_conflicts(&keybinds), vec![vec![&niri, &kitty]]);
+    }
+
+    #[test]
+    fn test_modifier_mask_round_trip() {
+        for modifier in Modifier::KNOWN {
+            let mask = ModifierMask::from(&modifier);
+            assert_eq!(mask.bits().count_ones(), 1);
+            assert_eq!(mask.modifiers(), [modifier]);
+        }
+
+        let modifiers = vec![Modifier::Mod, Modifier::Ctrl, Modifier::Shift];
+        let mask: ModifierMask = modifiers.iter().collect();
+        assert_eq!(
+            mask,
+            ModifierMask::MOD | ModifierMask::CTRL | ModifierMask::SHIFT
+        );
+        assert_eq!(mask.modifiers(), modifiers);
+        assert!(ModifierMask::default().modifiers().is_empty());
+    }
+
+    #[test]
+    fn test_modifier_mask_ignores_order() {
+        let a = Keybind::new("niri", "T").modifiers(vec![
+            Modifier::Shift,
+            Modifier::Alt,
+            Modifier::Mod,
+        ]);
+        let b = Keybind::new("kitty", "t").modifiers(vec![
+            Modifier::Mod,
+            Modifier::Shift,
+            Modifier::Alt,
+        ]);
+
+        assert_eq!(a.modifier_mask(), b.modifier_mask());
+        assert_ne!(
+            a.modifier_mask(),
+            Keybind::new("niri", "T")
+                .modifiers(vec![Modifier::Mod, Modifier::Shift])
+                .modifier_mask()
+        );
+        assert_eq!(a.signature(), "Mod+Alt+Shift+T");
+    }
+
+    #[test]
+    fn test_conflicting_keeps_only_collisions() {
+        let foot = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("spawn \"foot\"");
+        let quit = Keybind::new("niri", "Q")
+            .modifiers(vec![Modifier::Mod])
+            .action("close-window");
+        let alacritty = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("spawn \"alacritty\"")
+            .description("Terminal".to_string());
+        let kitty = Keybind::new("kitty", "t")
+            .modifiers(vec![Modifier::Ctrl])
+            .action("new_tab");
+
+        let keybinds = [foot, quit, alacritty, kitty];
+        let conflicts = conflicting(&keybinds, |_| true);
+
+        let lines: Vec<String> = conflicts.iter().map(Keybind::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "Mod+T - spawn \"foot\" (conflicts with Terminal [niri]) [niri]",
+                "Mod+T - Terminal (conflicts with spawn \"foot\" [niri]) [niri]",
+            ]
+        );
+
+        let conflicts = conflicting(&keybinds, |keybind| keybind.action.contains("foot"));
+        let lines: Vec<String> = conflicts.iter().map(Keybind::to_string).collect();
+        assert_eq!(
+            lines,
+            ["Mod+T - spawn \"foot\" (conflicts with Terminal [niri]) [niri]"]
+        );
+    }
+
+    #[test]
+    fn test_conflicting_names_other_programs() {
+        let niri = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Super])
+            .action("spawn \"foot\"");
+        let kitty = Keybind::new("kitty", "t")
+            .modifiers(vec![Modifier::Super])
+            .action("new_tab");
+
+        let conflicts = conflicting(&[niri, kitty], |_| true);
+
+        let lines: Vec<String> = conflicts.iter().map(Keybind::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "Super+T - spawn \"foot\" (conflicts with new_tab [kitty]) [niri]",
+                "Super+t - new_tab (conflicts with spawn \"foot\" [niri]) [kitty]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mod_as_super_conflicts() {
+        let niri = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("spawn");
+        let kitty = Keybind::new("kitty", "t")
+            .modifiers(vec![Modifier::Super])
+            .action("new_tab");
+        let keybinds = vec![niri, kitty];
+
+        assert!(find_conflicts(&keybinds).is_empty());
+
+        let canonical: Vec<Keybind> = keybinds
+            .into_iter()
+            .map(Keybind::with_mod_as_super)
+            .collect();
+        assert_eq!(canonical[0].signature(), "Super+T");
+        assert_eq!(
+            find_conflicts(&canonical),
+            vec![vec![&canonical[0], &canonical[1]]]
+        );
+    }
+
+    #[test]
+    fn test_chord_with_mod_as_super() {
+        let niri = Chord::single(vec![Modifier::Mod, Modifier::Shift], "T");
+        let kitty = Chord::single(vec![Modifier::Shift, Modifier::Super], "t");
+
+        assert!(!niri.is_same_as(&kitty));
+        assert!(niri.with_mod_as_super().is_same_as(&kitty));
+        assert_eq!(
+            Chord::single(vec![Modifier::Mod, Modifier::Super], "T")
+                .with_mod_as_super()
+                .modifiers(),
+            [Modifier::Super]
+        );
+    }
+
+    #[test]
+    fn test_program_from_str_round_trip() {
+        for program in Program::KNOWN {
+            assert!(program.is_known());
+            assert_eq!(program.to_string().parse::<Program>(), Ok(program));
+        }
+        assert_eq!(
+            "windows-terminal".parse::<Program>(),
+            Ok(Program::WindowsTerminal)
+        );
+    }
+
+    #[test]
+    fn test_program_from_str_unknown() {
+        let program: Program = "sway".parse().unwrap();
+
+        assert_eq!(program, Program::Other("sway".to_string()));
+        assert!(!program.is_known());
+        assert_eq!(program.to_string(), "sway");
+        assert_eq!(Program::from(" Sway "), Program::Other("Sway".to_string()));
+        // Only case and whitespace are forgiven, so a typo isn't mistaken for a known program
+        assert_eq!(Program::from(" NIRI "), Program::Niri);
+        assert_eq!(Program::from("nirii"), Program::Other("nirii".to_string()));
+    }
+
+    #[test]
+    fn test_filter_by_program() {
+        let niri = Keybind::new(Program::Niri, "T");
+        let kitty = Keybind::new(Program::Kitty, "t");
+        let custom = Keybind::new(Program::Other("Sway".to_string()), "Return");
+        let all = vec![niri.clone(), kitty.clone(), custom.clone()];
+
+        let mut keybinds = all.clone();
+        filter_by_program(&mut keybinds, &[" NIRI "]);
+        assert_eq!(keybinds, vec![niri.clone()]);
+
+        let mut keybinds = all.clone();
+        filter_by_program(&mut keybinds, &[Program::Kitty, "sway".into()]);
+        assert_eq!(keybinds, vec![kitty, custom]);
+
+        let mut keybinds = all.clone();
+        filter_by_program::<&str>(&mut keybinds, &[]);
+        assert_eq!(keybinds, all);
+    }
+
+    #[test]
+    fn test_program_orders_by_name() {
+        let mut programs = vec![
+            Program::WindowsTerminal,
+            Program::Other("alacritty".to_string()),
+            Program::Niri,
+            Program::Kitty,
+        ];
+        programs.sort();
+
+        assert_eq!(
+            programs,
+            vec![
+                Program::Other("alacritty".to_string()),
+                Program::Kitty,
+                Program::Niri,
+                Program::WindowsTerminal,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_modifier_histogram() {
+        let keybinds = vec![
+            Keybind::new("niri", "T").modifiers(vec![Modifier::Mod]),
+            Keybind::new("niri", "Q").modifiers(vec![Modifier::Mod, Modifier::Shift]),
+            Keybind::new("niri", "XF86AudioMute"),
+            Keybind::new(
+                "emacs",
+                Chord(vec![
+                    (vec![Modifier::Ctrl], "x".to_string()),
+                    (vec![Modifier::Ctrl], "f".to_string()),
+                ]),
+            ),
+            Keybind::new(
+                "qtile",
+                Chord(vec![
+                    (vec![Modifier::Mod], "z".to_string()),
+                    (vec![], "x".to_string()),
+                ]),
+            ),
+            Keybind::new("kitty", "T").modifiers(vec![Modifier::Shift, Modifier::Ctrl]),
+        ];
+
+        let histogram = modifier_histogram(&keybinds);
+
+        assert_eq!(
+            histogram.into_iter().collect::<Vec<_>>(),
+            vec![
+                (Modifier::Mod, 3),
+                (Modifier::Ctrl, 2),
+                (Modifier::Shift, 2),
+            ]
+        );
+        assert_eq!(count_bare(&keybinds), 1);
+    }
+
+    #[test]
+    fn test_modifier_histogram_empty() {
+        assert!(modifier_histogram(&[]).is_empty());
+        assert_eq!(count_bare(&[Keybind::new("niri", "Escape")]), 1);
+    }
+
+    #[test]
+    fn test_group_by_modifiers() {
+        let mod_t = Keybind::new("niri", "T").modifiers(vec![Modifier::Mod]);
+        let shift_mod_q = Keybind::new("niri", "Q").modifiers(vec![Modifier::Shift, Modifier::Mod]);
+        let mod_h = Keybind::new("niri", "H").modifiers(vec![Modifier::Mod]);
+        let mod_shift_e = Keybind::new("niri", "E").modifiers(vec![Modifier::Mod, Modifier::Shift]);
+        let keybinds = vec![
+            shift_mod_q.clone(),
+            mod_t.clone(),
+            mod_shift_e.clone(),
+            mod_h.clone(),
+        ];
+
+        let groups = group_by_modifiers(&keybinds);
+
+        assert_eq!(
+            groups,
+            vec![
+                (vec![Modifier::Mod], vec![&mod_t, &mod_h]),
+                (
+                    vec![Modifier::Mod, Modifier::Shift],
+                    vec![&shift_mod_q, &mod_shift_e]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_category() {
+        let focus = Keybind::new("niri", "H").action("focus-column-left");
+        let spawn = Keybind::new("niri", "T").action("spawn \"alacritty\"");
+        let volume = Keybind::new("niri", "XF86AudioRaiseVolume")
+            .action("spawn \"wpctl\" \"set-volume\" \"@DEFAULT_AUDIO_SINK@\" \"0.1+\"");
+        let workspace = Keybind::new("niri", "1").action("focus-workspace 1");
+        let quit = Keybind::new("niri", "E").action("quit");
+        let other_program = Keybind::new("kitty", "t").action("focus-column-left");
+
+        assert_eq!(focus.category(), Category::Window);
+        assert_eq!(spawn.category(), Category::Spawn);
+        assert_eq!(volume.category(), Category::Media);
+        assert_eq!(workspace.category(), Category::Workspace);
+        assert_eq!(quit.category(), Category::Other);
+        assert_eq!(other_program.category(), Category::Other);
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = vec![
+            Keybind::new("niri", "T")
+                .modifiers(vec![Modifier::Mod])
+                .action("spawn \"alacritty\""),
+            Keybind::new("niri", "Q")
+                .modifiers(vec![Modifier::Mod])
+                .action("close-window"),
+            Keybind::new("niri", "H")
+                .modifiers(vec![Modifier::Mod])
+                .action("focus-column-left"),
+        ];
+        let new = vec![
+            Keybind::new("niri", "T")
+                .modifiers(vec![Modifier::Mod])
+                .action("spawn \"foot\""),
+            Keybind::new("niri", "H")
+                .modifiers(vec![Modifier::Mod])
+                .action("focus-column-left"),
+            Keybind::new("niri", "L")
+                .modifiers(vec![Modifier::Mod])
+                .action("focus-column-right"),
+        ];
+
+        let diff = diff(&old, &new);
+
+        assert_eq!(diff.added, vec![new[2].clone()]);
+        assert_eq!(diff.removed, vec![old[1].clone()]);
+        assert_eq!(diff.changed, vec![(old[0].clone(), new[0].clone())]);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_find_shadowed() {
+        let first = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("spawn \"alacritty\"");
+        let other_program = Keybind::new("kitty", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("new_tab");
+        let last = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod])
+            .action("spawn \"foot\"");
+        let keybinds = vec![first.clone(), other_program, last];
+
+        assert_eq!(find_shadowed(&keybinds), vec![&first]);
+    }
+
+    #[test]
+    fn test_hyper_and_meta_display() {
+        let keybind = Keybind::new("emacs", "x").modifiers(vec![Modifier::Hyper, Modifier::Meta]);
+
+        assert_eq!(Modifier::Hyper.to_string(), "Hyper");
+        assert_eq!(Modifier::Meta.to_string(), "Meta");
+        assert_eq!(keybind.keys_string(), "Hyper+Meta+x");
+        assert_eq!(
+            keybind.normalized_modifiers(),
+            vec![Modifier::Hyper, Modifier::Meta]
+        );
+    }
+
+    #[test]
+    fn test_merge_by_action() {
+        let keybinds = vec![
+            Keybind::new("niri", "H")
+                .modifiers(vec![Modifier::Mod])
+                .action("focus-column-left"),
+            Keybind::new("niri", "L")
+                .modifiers(vec![Modifier::Mod])
+                .action("focus-column-right"),
+            Keybind::new("niri", "Left")
+                .modifiers(vec![Modifier::Mod])
+                .action("focus-column-left"),
+            Keybind::new("kitty", "Left")
+                .modifiers(vec![Modifier::Ctrl])
+                .action("focus-column-left"),
+        ];
+
+        let merged = merge_by_action(&keybinds);
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].chords, vec!["Mod+H", "Mod+Left"]);
+        assert_eq!(
+            merged[0].to_string(),
+            "Mod+H / Mod+Left - focus-column-left [niri]"
+        );
+        assert_eq!(merged[1].chords, vec!["Mod+L"]);
+        assert_eq!(merged[2].program, Program::Kitty);
+    }
+
+    #[test]
+    fn test_to_niri_kdl() {
+        let keybind = Keybind::new("niri", "T")
+            .modifiers(vec![Modifier::Mod, Modifier::Shift])
+            .action("spawn \"sh\" \"-c\" \"a, b\", close-window")
+            .description("Say \"hi\"".to_string())
+            .cooldown_ms(Some(150));
+
+        assert_eq!(
+            keybind.to_niri_kdl(),
+            r#"Mod+Shift+T hotkey-overlay-title="Say \"hi\"" cooldown-ms=150 { spawn "sh" "-c" "a, b"; close-window; }"#
         );
     }
 }
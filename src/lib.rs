@@ -1,3 +1,15 @@
+pub mod cache;
+pub mod config;
+pub mod discover;
+pub mod error;
+pub mod format;
+pub mod fuzzy;
 pub mod keybind;
+pub mod pretty_keys;
+pub mod sample;
 pub mod source;
 pub mod sources;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub use discover::{DiscoverOptions, discover_all};
+pub use format::load_json;
@@ -1,59 +1,1163 @@
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand};
+use fzf_keys::cache::Cache;
+use fzf_keys::config::{Config, SourcesConfig};
+use fzf_keys::discover::{self, DiscoverOptions};
+use fzf_keys::format::{self, OutputFormat};
+use fzf_keys::keybind::{self, Chord, KeyKind, Keybind, Program, SortKey};
+use fzf_keys::pretty_keys;
+use fzf_keys::sample::{self, Rng};
 use fzf_keys::source::Source;
 use fzf_keys::sources::kitty::KittySource;
-use fzf_keys::sources::niri::NiriSource;
-use std::path::PathBuf;
+use fzf_keys::sources::niri::{NiriSource, Severity};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Exit code when every requested source failed
+const EXIT_ALL_SOURCES_FAILED: u8 = 1;
+
+/// Exit code when discovery succeeded but no keybinds were printed
+const EXIT_NO_KEYBINDS: u8 = 2;
+
+/// Exit code when `check` found errors in the config
+const EXIT_CHECK_ERRORS: u8 = 3;
 
 #[derive(Parser)]
 #[command(name = "fzf-keys")]
 #[command(about = "Search through keybinds from various programs", long_about = None)]
-struct Args {
-    /// Path to niri config file
-    #[arg(short, long)]
-    niri_config: Option<PathBuf>,
+struct Cli {
+    #[command(flatten)]
+    sources: SourceArgs,
+
+    /// Options for listing keybinds when no subcommand is given
+    #[command(flatten)]
+    list: ListArgs,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Which sources to read keybinds from; accepted before or after any subcommand.
+#[derive(clap::Args)]
+struct SourceArgs {
+    /// Read defaults from this file instead of ~/.config/fzf-keys/config.toml
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Path to niri config file; repeat to merge several, later files shadowing earlier ones
+    #[arg(short, long, global = true)]
+    niri_config: Vec<PathBuf>,
+
+    /// Print the niri config path that would be read, one per line when merging, and exit
+    #[arg(long, global = true)]
+    print_config_path: bool,
+
+    /// Describe niri binds without a hotkey-overlay-title using known action names
+    #[arg(long, global = true)]
+    describe_actions: bool,
+
+    /// Show numeric niri arguments without quotes, e.g. `set-column-width +10%`
+    #[arg(long, global = true)]
+    bare_numeric_args: bool,
+
+    /// Keep niri binds without an action, e.g. `Mod+T { }`, labeled `unknown`
+    #[arg(long, global = true)]
+    include_empty_action: bool,
+
+    /// Treat niri's `Mod` as `Super`, so `Mod+T` and `Super+T` are the same chord
+    #[arg(long, global = true)]
+    mod_is_super: bool,
+
+    /// Note when each niri bind's config file was last modified, e.g. `(modified=2026-10-16T09:30:00Z)`
+    #[arg(long, global = true)]
+    show_mtime: bool,
+
+    /// Include kitty keybinds (requires kitty terminal); `--kitty=false` overrides the config file
+    #[arg(short, long, global = true, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    kitty: Option<bool>,
+
+    /// Always query kitty instead of using cached keybinds
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Include kitty mappings that unbind a key (`no_op`, `discard_event`)
+    #[arg(long, global = true)]
+    show_disabled: bool,
+
+    /// Describe kitty keybinds as added, changed or default relative to kitty's defaults
+    #[arg(long, global = true)]
+    kitty_diff: bool,
+
+    /// List each action of a kitty `combine` mapping as its own keybind
+    #[arg(long, global = true)]
+    split_combined: bool,
+
+    /// Path to an Emacs init file to scan for keybinds
+    #[arg(long, global = true)]
+    emacs_config: Option<PathBuf>,
+
+    /// Path to a Qtile config.py to scan for keybinds
+    #[arg(long, global = true)]
+    qtile_config: Option<PathBuf>,
+
+    /// Path to a dunstrc to read notification shortcuts from
+    #[arg(long, global = true)]
+    dunst_config: Option<PathBuf>,
+
+    /// Path to a Windows Terminal settings.json to read actions from
+    #[arg(long, global = true)]
+    windows_terminal_config: Option<PathBuf>,
+
+    /// Path to dwm's config.h to scan for keybinds
+    #[arg(long, global = true)]
+    dwm_config: Option<PathBuf>,
+
+    /// Path to an awesome rc.lua to scan for keybinds
+    #[arg(long, global = true)]
+    awesome_config: Option<PathBuf>,
+
+    /// Path to a Kakoune kakrc to scan for `map` commands
+    #[arg(long, global = true)]
+    kakoune_config: Option<PathBuf>,
+
+    /// Path to a keyd config (e.g. /etc/keyd/default.conf) to list key remaps from
+    #[arg(long, global = true)]
+    keyd_config: Option<PathBuf>,
+
+    /// Run this shell command and read keybinds from its NDJSON output (repeatable)
+    #[arg(long, global = true, value_name = "COMMAND")]
+    source_cmd: Vec<String>,
+}
+
+#[derive(clap::Args)]
+struct ListArgs {
+    /// Only show keybinds that have a cooldown set; `=false` overrides the config file
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    with_cooldown_only: Option<bool>,
 
-    /// Include kitty keybinds (requires kitty terminal)
+    /// Only show keybinds carrying this property (repeatable)
+    #[arg(long, value_name = "NAME", value_parser = clap::builder::PossibleValuesParser::new(keybind::PROPERTY_NAMES))]
+    has_property: Vec<String>,
+
+    /// Only show keybinds overridden by a later bind of the same chord in the same program
+    #[arg(long)]
+    shadowed: bool,
+
+    /// Only show keybinds from these programs, from the config file
+    #[arg(skip)]
+    program: Vec<Program>,
+
+    /// Leave out keybinds from these programs, from the config file
+    #[arg(skip)]
+    exclude_program: Vec<Program>,
+
+    /// Leave out niri binds hidden from the hotkey overlay (`hotkey-overlay-title=false` or `=null`); `=false` overrides the config file
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    respect_overlay_hidden: Option<bool>,
+
+    /// Print a warning to stderr for each keybind whose key name isn't recognized
+    #[arg(long)]
+    warn_unknown_keys: bool,
+
+    /// Print only the key combination of each keybind, e.g. `Mod+Shift+T`
+    #[arg(long)]
+    keys_only: bool,
+
+    /// Only show keybinds whose keys, action or description contain this text (case-insensitive)
     #[arg(short, long)]
-    kitty: bool,
+    query: Option<String>,
+
+    /// Only show keybinds whose action contains this text (case-insensitive), e.g. `alacritty`
+    #[arg(long, value_name = "TEXT")]
+    action_query: Option<String>,
+
+    /// Match --query as a fuzzy subsequence and rank results by match quality
+    #[arg(long)]
+    fuzzy: bool,
+
+    /// Print how long each source took, and how many keybinds it found, to stderr
+    #[arg(long)]
+    timings: bool,
+
+    /// Output format: `plain` lines for fzf, aligned `columns` for reading, or `ndjson`/`json` for tools
+    #[arg(long, value_enum, conflicts_with = "keys_only")]
+    format: Option<OutputFormat>,
+
+    /// Cut actions longer than this many columns short with `…`; JSON output keeps them whole
+    #[arg(long, value_name = "WIDTH")]
+    max_action_width: Option<usize>,
+
+    /// Indent `--format json` output across several lines instead of printing it on one
+    #[arg(long)]
+    pretty: bool,
+
+    /// Separator between the steps of a multi-key chord, e.g. `" "` for `Ctrl+x Ctrl+f`
+    #[arg(long, value_name = "SEP", default_value = keybind::CHORD_SEPARATOR)]
+    chord_separator: String,
+
+    /// Show modifiers as Mac-style symbols, e.g. `⌃⇧T` for `Ctrl+Shift+T`
+    #[arg(long, conflicts_with = "group_by_modifiers")]
+    symbols: bool,
+
+    /// Show punctuation keys as the glyphs they type, e.g. `Mod+,` for `Mod+Comma`
+    #[arg(long)]
+    pretty_keys: bool,
+
+    /// Show keybinds sharing a program and action on one line, e.g. `Mod+H / Mod+Left - focus-column-left`
+    #[arg(long, conflicts_with_all = ["keys_only", "format"])]
+    merge_aliases: bool,
+
+    /// List keybinds under a heading for each modifier combination, e.g. `Mod+Shift:`
+    #[arg(long, conflicts_with_all = ["keys_only", "format", "merge_aliases"])]
+    group_by_modifiers: bool,
+
+    /// List keybinds under a heading for what they do, e.g. `Workspaces:`, like niri's hotkey overlay
+    #[arg(long, conflicts_with_all = ["keys_only", "format", "merge_aliases", "group_by_modifiers"])]
+    group_by_category: bool,
+
+    /// Leave out the trailing `[program]` tag, e.g. when listing a single source
+    #[arg(long)]
+    no_program_tag: bool,
+
+    /// Drop keybinds that are listed more than once
+    #[arg(long)]
+    dedupe: bool,
+
+    /// Only list chords bound more than once, even across programs, each noting what else it's bound to
+    #[arg(long)]
+    conflicts_only: bool,
+
+    /// Fold runs like `Mod+1`..`Mod+9` with the same action into one `Mod+[1-9] - focus-workspace N` line
+    #[arg(long)]
+    collapse_numeric: bool,
+
+    /// Fold scroll binds differing only in direction into one `Mod+WheelScroll{Up,Down} - focus-workspace` line
+    #[arg(long)]
+    collapse_directions: bool,
+
+    /// Sort keybinds by this field (fuzzy ranking takes precedence)
+    #[arg(long, value_enum)]
+    sort: Option<SortKey>,
+
+    /// List each program's keybinds together, programs in name order
+    #[arg(long)]
+    group_by_program: bool,
+
+    /// Sort keybinds within each --group-by-program group by this field, keeping the groups in place
+    #[arg(long, value_enum, value_name = "FIELD", requires = "group_by_program")]
+    sort_within_group: Option<SortKey>,
+
+    /// Print this line between --group-by-program groups, an empty one if no value is given; plain, tab and NDJSON output only
+    #[arg(long, value_name = "SEP", num_args = 0..=1, default_missing_value = "", requires = "group_by_program")]
+    group_separator: Option<String>,
+
+    /// Reverse the final output order, e.g. for fzf's bottom-up layout
+    #[arg(long)]
+    reverse: bool,
+
+    /// Print at most this many keybinds, after filtering and ordering
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+
+    /// Print N keybinds picked at random, e.g. for flashcard-style practice
+    #[arg(long, value_name = "N")]
+    sample: Option<usize>,
+
+    /// Seed for --sample, so the same keybinds are picked every run
+    #[arg(long, requires = "sample")]
+    seed: Option<u64>,
+
+    /// Pick a keybind in a built-in search box instead of listing them all (needs the `tui` feature)
+    #[arg(long)]
+    tui: bool,
+
+    /// Reprint the list whenever the niri config changes, clearing the terminal first (needs the `watch` feature)
+    #[arg(long, conflicts_with = "tui")]
+    watch: bool,
+}
+
+impl ListArgs {
+    /// Fills in whatever wasn't given on the command line from the config file.
+    fn with_config(mut self, config: &Config) -> Self {
+        // A configured format doesn't apply to output shapes that can't be formatted
+        if !self.keys_only
+            && !self.merge_aliases
+            && !self.group_by_modifiers
+            && !self.group_by_category
+        {
+            self.format = self.format.or(config.format);
+        }
+        self.sort = self.sort.or(config.sort);
+        self.with_cooldown_only = self
+            .with_cooldown_only
+            .or(config.filters.with_cooldown_only);
+        self.respect_overlay_hidden = self
+            .respect_overlay_hidden
+            .or(config.filters.respect_overlay_hidden);
+        if self.has_property.is_empty() {
+            self.has_property = config.filters.has_property.clone();
+        }
+        self.program = config.filters.programs.clone();
+        self.exclude_program = config.filters.exclude_programs.clone();
+        self
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List keybinds, one per line (the default)
+    List(Box<ListArgs>),
+    /// Show chords bound more than once, in one program or across programs
+    Conflicts,
+    /// Print how many keybinds each program has
+    Count,
+    /// Report how often each modifier is used, the busiest modifier
+    /// combinations and which letters and digits are still free
+    Stats,
+    /// Show niri binds added, removed or changed between two config files
+    Diff {
+        /// The config before editing
+        old: PathBuf,
+        /// The config after editing
+        new: PathBuf,
+    },
+    /// Validate the niri config's binds, reporting problems with their line numbers
+    ///
+    /// Unparseable binds and binds without an action are errors; a chord bound
+    /// twice is a warning. Exits with 3 if there are any errors, e.g. for a pre-commit hook.
+    Check,
+    /// Check that the requested sources can be read, with a hint for each that can't
+    ///
+    /// Exits with 3 if any check fails.
+    Doctor,
+    /// Print every keybind bound to a chord, e.g. `fzf-keys explain Mod+Shift+E`
+    ///
+    /// Modifiers may be given in any order. Exits with 2 if the chord is unbound.
+    Explain {
+        /// The chord as it's listed, e.g. `Mod+Shift+E` or `Ctrl+x>Ctrl+f`
+        // `From<String>` would take the whole text as the key, so parse it explicitly
+        #[arg(value_parser = str::parse::<Chord>)]
+        chord: Chord,
+    },
+    /// Print the JSON Schema of a serialized keybind, for tools consuming the JSON output
+    Schema,
+    /// Print a shell completion script, e.g. `fzf-keys completions bash > /etc/bash_completion.d/fzf-keys`
+    Completions {
+        /// Shell to complete in: bash, zsh, fish, elvish or powershell
+        shell: clap_complete::Shell,
+    },
+    /// Print every detail of one keybind, e.g. for fzf's preview window
+    ///
+    /// The program and index come from the first two fields of `--format tab`.
+    Preview {
+        /// Program the keybind belongs to, e.g. `niri`
+        program: Program,
+        /// Position of the keybind among that program's discovered keybinds
+        index: usize,
+    },
+}
+
+/// Which sources to query, from the command line and then the config file.
+fn discover_options(args: &SourceArgs, config: &SourcesConfig) -> DiscoverOptions {
+    let kitty = args.kitty.or(config.kitty).unwrap_or(false);
+    let emacs_config = args.emacs_config.clone().or(config.emacs_config.clone());
+    let qtile_config = args.qtile_config.clone().or(config.qtile_config.clone());
+    let dunst_config = args.dunst_config.clone().or(config.dunst_config.clone());
+    let windows_terminal_config = args
+        .windows_terminal_config
+        .clone()
+        .or(config.windows_terminal_config.clone());
+    let dwm_config = args.dwm_config.clone().or(config.dwm_config.clone());
+    let awesome_config = args
+        .awesome_config
+        .clone()
+        .or(config.awesome_config.clone());
+    let kakoune_config = args
+        .kakoune_config
+        .clone()
+        .or(config.kakoune_config.clone());
+    let keyd_config = args.keyd_config.clone().or(config.keyd_config.clone());
+
+    let other_source_requested = kitty
+        || emacs_config.is_some()
+        || qtile_config.is_some()
+        || dunst_config.is_some()
+        || windows_terminal_config.is_some()
+        || dwm_config.is_some()
+        || awesome_config.is_some()
+        || kakoune_config.is_some()
+        || keyd_config.is_some()
+        || !args.source_cmd.is_empty();
+
+    DiscoverOptions {
+        // Niri is the default when no other source was requested
+        niri: !args.niri_config.is_empty() || config.niri.unwrap_or(!other_source_requested),
+        niri_configs: if args.niri_config.is_empty() {
+            config.niri_config.clone().into_iter().collect()
+        } else {
+            args.niri_config.clone()
+        },
+        describe_actions: args.describe_actions,
+        bare_numeric_args: args.bare_numeric_args,
+        include_empty_action: args.include_empty_action,
+        mod_is_super: args.mod_is_super,
+        show_mtime: args.show_mtime,
+        kitty,
+        kitty_cache: Cache::default_dir()
+            .filter(|_| !args.no_cache)
+            .map(|dir| dir.join("kitty.json")),
+        show_disabled: args.show_disabled,
+        kitty_diff: args.kitty_diff,
+        split_combined: args.split_combined,
+        emacs_config,
+        qtile_config,
+        dunst_config,
+        windows_terminal_config,
+        dwm_config,
+        awesome_config,
+        kakoune_config,
+        keyd_config,
+        source_commands: args.source_cmd.clone(),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let config = match cli.sources.config.clone().or_else(Config::default_path) {
+        Some(path) => Config::load(&path).unwrap_or_else(|e| {
+            eprintln!("Error reading config, ignoring it: {}", e);
+            Config::default()
+        }),
+        None => Config::default(),
+    };
+    let opts = discover_options(&cli.sources, &config.sources);
+    if cli.sources.print_config_path {
+        return print_config_path(&opts);
+    }
+
+    match cli.command {
+        None => list_or_watch(&cli.list.with_config(&config), &opts),
+        Some(Command::List(args)) => list_or_watch(&args.with_config(&config), &opts),
+        Some(Command::Conflicts) => conflicts(&opts),
+        Some(Command::Count) => count(&opts),
+        Some(Command::Stats) => stats(&opts),
+        Some(Command::Diff { old, new }) => diff(&old, &new),
+        Some(Command::Check) => check(&opts),
+        Some(Command::Doctor) => doctor(&opts),
+        Some(Command::Explain { chord }) if opts.mod_is_super => {
+            explain(&opts, &chord.with_mod_as_super())
+        }
+        Some(Command::Explain { chord }) => explain(&opts, &chord),
+        Some(Command::Preview { program, index }) => preview(&opts, &program, index),
+        Some(Command::Schema) => {
+            let schema = keybind::json_schema();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&schema).unwrap_or_default()
+            );
+            ExitCode::SUCCESS
+        }
+        Some(Command::Completions { shell }) => {
+            clap_complete::generate(shell, &mut Cli::command(), "fzf-keys", &mut io::stdout());
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn print_config_path(opts: &DiscoverOptions) -> ExitCode {
+    match discover::niri_source(opts) {
+        Ok(source) => {
+            for path in source.config_paths() {
+                println!("{}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error finding niri config: {}", e);
+            ExitCode::from(EXIT_ALL_SOURCES_FAILED)
+        }
+    }
+}
+
+/// Discovers every keybind up front, reporting source errors on stderr.
+///
+/// Fails with the exit code to use when every requested source failed.
+fn discover_all(opts: &DiscoverOptions) -> Result<Vec<Keybind>, ExitCode> {
+    let (keybinds, errors) = discover::discover_all(opts);
+    for (source, e) in &errors {
+        eprintln!("Error discovering {} keybinds: {}", source, e);
+    }
+
+    if !errors.is_empty() && errors.len() == opts.source_count() {
+        Err(ExitCode::from(EXIT_ALL_SOURCES_FAILED))
+    } else {
+        Ok(keybinds)
+    }
+}
+
+fn list_or_watch(args: &ListArgs, opts: &DiscoverOptions) -> ExitCode {
+    if args.watch {
+        watch(args, opts)
+    } else {
+        list(args, opts)
+    }
+}
+
+/// Lists keybinds, then again after every change to the niri config, until interrupted.
+#[cfg(feature = "watch")]
+fn watch(args: &ListArgs, opts: &DiscoverOptions) -> ExitCode {
+    use std::io::IsTerminal;
+
+    let source = match discover::niri_source(opts) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Error watching niri config: {}", e);
+            return ExitCode::from(EXIT_ALL_SOURCES_FAILED);
+        }
+    };
+
+    list(args, opts);
+    let result = source.watch(|_| {
+        // A pipe gets each list in turn; a terminal only shows the latest
+        if io::stdout().is_terminal() {
+            print!("\x1b[2J\x1b[H");
+        }
+        list(args, opts);
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error watching niri config: {}", e);
+            ExitCode::from(EXIT_ALL_SOURCES_FAILED)
+        }
+    }
+}
+
+#[cfg(not(feature = "watch"))]
+fn watch(args: &ListArgs, opts: &DiscoverOptions) -> ExitCode {
+    eprintln!("--watch needs fzf-keys built with `--features watch`; listing once");
+    list(args, opts)
 }
 
-fn main() {
-    let args = Args::parse();
+/// Lets the user pick one of `keybinds` with the built-in picker.
+#[cfg(feature = "tui")]
+fn pick(keybinds: Vec<Keybind>) -> Option<Keybind> {
+    fzf_keys::tui::pick(keybinds).unwrap_or_else(|e| {
+        eprintln!("Error running the picker: {}", e);
+        None
+    })
+}
 
-    // Collect keybinds from all requested sources
-    let mut all_keybinds = Vec::new();
+#[cfg(not(feature = "tui"))]
+fn pick(_keybinds: Vec<Keybind>) -> Option<Keybind> {
+    eprintln!("--tui needs fzf-keys built with `--features tui`");
+    None
+}
 
-    // Try niri if specified or as default
-    if !args.kitty {
-        let niri_source = if let Some(config_path) = args.niri_config {
-            NiriSource::new(config_path)
+fn doctor(opts: &DiscoverOptions) -> ExitCode {
+    // Prints one checklist line, and the hint if the check failed
+    fn checked(ok: bool, what: &str, hint: &str) -> bool {
+        if ok {
+            println!("[ok]   {}", what);
         } else {
-            match NiriSource::from_default_config() {
-                Ok(source) => source,
-                Err(e) => {
-                    eprintln!("Error initializing niri source: {}", e);
-                    return;
+            println!("[fail] {}", what);
+            println!("       hint: {}", hint);
+        }
+        ok
+    }
+
+    let mut all_ok = true;
+    if opts.niri {
+        match discover::niri_source(opts) {
+            Ok(source) => {
+                for path in source.config_paths() {
+                    all_ok &= checked(
+                        path.is_file(),
+                        &format!("niri config: {}", path.display()),
+                        "pass --niri-config, or set FZF_KEYS_NIRI_CONFIG, to your config.kdl",
+                    );
                 }
             }
+            Err(e) => {
+                all_ok &= checked(
+                    false,
+                    &format!("niri config: {}", e),
+                    "pass --niri-config with the path to your config.kdl",
+                );
+            }
+        }
+    }
+
+    if opts.kitty {
+        let python = KittySource::check_python();
+        all_ok &= checked(
+            python.is_ok(),
+            &match &python {
+                Ok(()) => "kitty's Python modules import".to_string(),
+                Err(e) => format!("kitty's Python modules import: {}", e),
+            },
+            "install kitty, or add its Python modules to PYTHONPATH; until then only kitty.conf is read",
+        );
+        let conf = KittySource::config_path();
+        all_ok &= checked(
+            python.is_ok() || conf.as_ref().is_some_and(|path| path.is_file()),
+            &match &conf {
+                Some(path) => format!("kitty.conf: {}", path.display()),
+                None => "kitty.conf: can't locate it".to_string(),
+            },
+            "create kitty.conf, or set KITTY_CONFIG_DIRECTORY to where it is",
+        );
+    }
+
+    for (source, path) in discover::config_file_sources(opts) {
+        all_ok &= checked(
+            source.is_available(),
+            &format!("{} config: {}", source.name(), path.display()),
+            "check the path given on the command line or in the config file",
+        );
+    }
+
+    for command in &opts.source_commands {
+        let program = command.split_whitespace().next().unwrap_or_default();
+        all_ok &= checked(
+            find_binary(program).is_some(),
+            &format!("{} on PATH, for --source-cmd", program),
+            "install it, or fix the --source-cmd command",
+        );
+    }
+
+    if all_ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(EXIT_CHECK_ERRORS)
+    }
+}
+
+/// Finds the executable `name` on `PATH`, or at `name` itself if it's a path.
+fn find_binary(name: &str) -> Option<PathBuf> {
+    if name.contains('/') {
+        return Some(PathBuf::from(name)).filter(|path| path.is_file());
+    }
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+fn conflicts(opts: &DiscoverOptions) -> ExitCode {
+    let keybinds = match discover_all(opts) {
+        Ok(keybinds) => keybinds,
+        Err(code) => return code,
+    };
+
+    let conflicts = keybind::find_conflicts(&keybinds);
+    for group in &conflicts {
+        let mut programs: Vec<&str> = Vec::new();
+        for keybind in group {
+            if !programs.contains(&keybind.program.as_str()) {
+                programs.push(keybind.program.as_str());
+            }
+        }
+        println!("{} [{}]:", group[0].signature(), programs.join(", "));
+        for keybind in group {
+            println!("  {}", keybind);
+        }
+    }
+
+    if conflicts.is_empty() {
+        ExitCode::from(EXIT_NO_KEYBINDS)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn count(opts: &DiscoverOptions) -> ExitCode {
+    let keybinds = match discover_all(opts) {
+        Ok(keybinds) => keybinds,
+        Err(code) => return code,
+    };
+
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for keybind in &keybinds {
+        *counts.entry(keybind.program.as_str()).or_default() += 1;
+    }
+    for (program, count) in &counts {
+        println!("{}: {}", program, count);
+    }
+    println!("total: {}", keybinds.len());
+
+    if keybinds.is_empty() {
+        ExitCode::from(EXIT_NO_KEYBINDS)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn explain(opts: &DiscoverOptions, chord: &Chord) -> ExitCode {
+    let keybinds = match discover_all(opts) {
+        Ok(keybinds) => keybinds,
+        Err(code) => return code,
+    };
+
+    let matching: Vec<&Keybind> = keybinds
+        .iter()
+        .filter(|keybind| keybind.key.is_same_as(chord))
+        .collect();
+    if matching.is_empty() {
+        eprintln!("{} is unbound", chord);
+        return ExitCode::from(EXIT_NO_KEYBINDS);
+    }
+
+    for keybind in matching {
+        println!("{}", keybind);
+    }
+    ExitCode::SUCCESS
+}
+
+fn stats(opts: &DiscoverOptions) -> ExitCode {
+    /// How many of the busiest modifier combinations to list.
+    const TOP_COMBINATIONS: usize = 5;
+
+    let keybinds = match discover_all(opts) {
+        Ok(keybinds) => keybinds,
+        Err(code) => return code,
+    };
+    if keybinds.is_empty() {
+        return ExitCode::from(EXIT_NO_KEYBINDS);
+    }
+
+    println!("modifiers:");
+    for (modifier, count) in keybind::modifier_histogram(&keybinds) {
+        println!("  {}: {}", modifier, count);
+    }
+    println!("  none: {}", keybind::count_bare(&keybinds));
+
+    let mut combinations: HashMap<String, usize> = HashMap::new();
+    for keybind in &keybinds {
+        let modifiers: Vec<String> = keybind
+            .normalized_modifiers()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        if !modifiers.is_empty() {
+            *combinations.entry(modifiers.join("+")).or_default() += 1;
+        }
+    }
+    let mut combinations: Vec<_> = combinations.into_iter().collect();
+    // Most used first, ties alphabetically so the report is stable
+    combinations.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    println!("most used combinations:");
+    for (combination, count) in combinations.iter().take(TOP_COMBINATIONS) {
+        println!("  {}: {}", combination, count);
+    }
+
+    let bound: HashSet<String> = keybinds
+        .iter()
+        .flat_map(|keybind| keybind.key.steps())
+        .map(|(_, key)| key.to_uppercase())
+        .collect();
+    let unused: Vec<String> = ('A'..='Z')
+        .chain('0'..='9')
+        .map(String::from)
+        .filter(|key| !bound.contains(key))
+        .collect();
+    println!("unused keys: {}", unused.join(" "));
+
+    ExitCode::SUCCESS
+}
+
+fn diff(old: &Path, new: &Path) -> ExitCode {
+    let discover = |path: &Path| {
+        NiriSource::new(path.to_path_buf())
+            .discover()
+            .inspect_err(|e| eprintln!("Error discovering niri keybinds: {}", e))
+    };
+    let (Ok(old), Ok(new)) = (discover(old), discover(new)) else {
+        return ExitCode::from(EXIT_ALL_SOURCES_FAILED);
+    };
+
+    let diff = keybind::diff(&old, &new);
+    for keybind in &diff.added {
+        println!("+ {}", keybind);
+    }
+    for keybind in &diff.removed {
+        println!("- {}", keybind);
+    }
+    for (old, new) in &diff.changed {
+        println!(
+            "~ {}: {} -> {} [{}]",
+            new.keys_string(),
+            old.action,
+            new.action,
+            new.program
+        );
+    }
+
+    if diff.is_empty() {
+        ExitCode::from(EXIT_NO_KEYBINDS)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn check(opts: &DiscoverOptions) -> ExitCode {
+    // Merged configs are checked one by one, so lines refer to their own file
+    let sources = if opts.niri_configs.is_empty() {
+        match NiriSource::from_default_config() {
+            Ok(source) => vec![source],
+            Err(e) => {
+                eprintln!("Error checking niri config: {}", e);
+                return ExitCode::from(EXIT_ALL_SOURCES_FAILED);
+            }
+        }
+    } else {
+        opts.niri_configs
+            .iter()
+            .cloned()
+            .map(NiriSource::new)
+            .collect()
+    };
+
+    let mut has_errors = false;
+    for source in &sources {
+        let diagnostics = match source.check() {
+            Ok(diagnostics) => diagnostics,
+            Err(e) => {
+                eprintln!("Error checking niri config: {}", e);
+                return ExitCode::from(EXIT_ALL_SOURCES_FAILED);
+            }
         };
 
-        match niri_source.discover() {
-            Ok(keybinds) => all_keybinds.extend(keybinds),
-            Err(e) => eprintln!("Error discovering niri keybinds: {}", e),
+        // `path:line: severity: ...`, as compilers write it, so editors can jump to each bind
+        let path = source.config_path().display();
+        for diagnostic in &diagnostics {
+            println!(
+                "{}:{}: {}: {}: {}",
+                path, diagnostic.line, diagnostic.severity, diagnostic.combo, diagnostic.message
+            );
         }
+        has_errors |= diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error);
     }
 
-    // Try kitty if specified
-    if args.kitty {
-        let kitty_source = KittySource::new();
-        match kitty_source.discover() {
-            Ok(keybinds) => all_keybinds.extend(keybinds),
-            Err(e) => eprintln!("Error discovering kitty keybinds: {}", e),
+    if has_errors {
+        ExitCode::from(EXIT_CHECK_ERRORS)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn preview(opts: &DiscoverOptions, program: &Program, index: usize) -> ExitCode {
+    let keybinds = match discover_all(opts) {
+        Ok(keybinds) => keybinds,
+        Err(code) => return code,
+    };
+
+    match keybinds
+        .iter()
+        .filter(|keybind| keybind.program == *program)
+        .nth(index)
+    {
+        Some(keybind) => {
+            println!("{}", format::details(keybind));
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("No {} keybind at index {}", program, index);
+            ExitCode::from(EXIT_NO_KEYBINDS)
         }
     }
+}
 
-    // Output all keybinds
-    for keybind in all_keybinds {
-        println!("{}", keybind);
+fn list(args: &ListArgs, opts: &DiscoverOptions) -> ExitCode {
+    // Write keybinds as each source yields them
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+
+    let format = args.format.unwrap_or_default();
+    // No source yields other programs, so an unknown name is most likely a typo
+    for program in args.program.iter().chain(&args.exclude_program) {
+        if !program.is_known() {
+            eprintln!("Warning: unknown program '{}'", program);
+        }
+    }
+    let keep = |keybind: &Keybind| {
+        if args.warn_unknown_keys && keybind.key_kind() == KeyKind::Unknown {
+            eprintln!(
+                "Warning: unknown key '{}' in: {}",
+                keybind.key.first_key(),
+                keybind
+            );
+        }
+
+        args.query.as_deref().is_none_or(|query| {
+            if args.fuzzy {
+                keybind.fuzzy_score(query).is_some()
+            } else {
+                keybind.matches_query(query)
+            }
+        }) && args
+            .action_query
+            .as_deref()
+            .is_none_or(|action| keybind.is_bound_to(action))
+            && (args.program.is_empty() || keybind.program_in(&args.program))
+            && !keybind.program_in(&args.exclude_program)
+            && !(args.respect_overlay_hidden == Some(true) && keybind.hidden_in_overlay)
+            && (args.with_cooldown_only != Some(true) || keybind.has_cooldown())
+            && args
+                .has_property
+                .iter()
+                .all(|name| keybind.has_property(name))
+    };
+
+    // JSON keeps the keys structured and the action whole, and desktop
+    // actions need the action as written to find its command
+    let text_output = !matches!(
+        format,
+        OutputFormat::Ndjson | OutputFormat::Json | OutputFormat::Desktop
+    );
+    // Actions are only shortened once printed, so that merging, deduping and
+    // grouping still tell apart actions sharing a prefix
+    let action_width = args.max_action_width.filter(|_| text_output);
+    let fit = |keybind: &Keybind| match action_width {
+        Some(width) => keybind.clone().with_action_width(width),
+        None => keybind.clone(),
+    };
+
+    let render = |keybind: &Keybind, index: usize| {
+        let keybind = &fit(keybind);
+        if args.keys_only {
+            keybind.keys_string()
+        } else if format == OutputFormat::Tab {
+            format::tab_line(keybind, index, !args.no_program_tag)
+        } else if format == OutputFormat::Ndjson {
+            format::ndjson_line(keybind)
+        } else if format == OutputFormat::Desktop {
+            format::desktop_action(keybind, index)
+        } else {
+            format::plain_line(keybind, !args.no_program_tag)
+        }
+    };
+
+    // Columns, JSON arrays, merging, deduping, shadowing, ordering, ranking and sampling
+    // need every keybind up front; otherwise write them as they arrive
+    let buffered = matches!(format, OutputFormat::Columns | OutputFormat::Json)
+        || args.dedupe
+        || args.conflicts_only
+        || args.collapse_numeric
+        || args.collapse_directions
+        || args.shadowed
+        || args.sort.is_some()
+        || (args.fuzzy && args.query.is_some())
+        || args.group_by_program
+        || args.reverse
+        || args.merge_aliases
+        || args.group_by_modifiers
+        || args.group_by_category
+        || args.sample.is_some()
+        || args.tui;
+    let mut collected = Vec::new();
+    let mut emitted = 0;
+    // Each keybind's position among its program's keybinds, counted before
+    // filtering so that `preview` can find it again by rediscovering
+    let mut next_index: HashMap<Program, usize> = HashMap::new();
+    let mut indices: HashMap<Keybind, usize> = HashMap::new();
+    // Shadowing and conflicts are found among every keybind, so those passing
+    // the filters are only noted here and the rest dropped once they're found
+    let filter_later = args.shadowed || args.conflicts_only;
+    let mut passing: HashSet<Keybind> = HashSet::new();
+    let mut sink = |keybind: Keybind| {
+        let counter = next_index.entry(keybind.program.clone()).or_default();
+        let index = *counter;
+        *counter += 1;
+
+        let kept = keep(&keybind);
+        if !(kept || filter_later)
+            || (!buffered && args.limit.is_some_and(|limit| emitted >= limit))
+        {
+            return;
+        }
+        let keybind = if text_output {
+            NiriSource::with_spawn_display(keybind)
+        } else {
+            keybind
+        };
+        let keybind = if args.pretty_keys && text_output {
+            pretty_keys::with_pretty_keys(keybind)
+        } else {
+            keybind
+        };
+        let keybind = if args.symbols && text_output {
+            format::with_symbols(keybind, &args.chord_separator)
+        } else {
+            keybind.with_chord_separator(&args.chord_separator)
+        };
+        if filter_later && kept {
+            passing.insert(keybind.clone());
+        }
+        emitted += 1;
+        if buffered {
+            if matches!(format, OutputFormat::Tab | OutputFormat::Desktop) {
+                indices.entry(keybind.clone()).or_insert(index);
+            }
+            collected.push(keybind);
+        } else {
+            // Write errors (e.g. fzf exiting early) shouldn't abort discovery
+            let _ = writeln!(out, "{}", render(&keybind, index));
+        }
+    };
+
+    let report = discover::discover_all_into(opts, &mut sink);
+    for (source, e) in &report.errors {
+        eprintln!("Error discovering {} keybinds: {}", source, e);
+    }
+    for note in &report.notes {
+        eprintln!("Note: {}", note);
+    }
+    if args.timings {
+        for timing in &report.timings {
+            eprintln!(
+                "{}: {} keybinds in {:.1?}",
+                timing.name, timing.count, timing.duration
+            );
+        }
+    }
+
+    if buffered {
+        if args.dedupe {
+            keybind::dedupe(&mut collected);
+        }
+        // Shadowing depends on document order, so it's found before any reordering
+        if args.shadowed {
+            collected = keybind::find_shadowed(&collected)
+                .into_iter()
+                .filter(|keybind| passing.contains(keybind))
+                .cloned()
+                .collect();
+            emitted = collected.len();
+        }
+        if args.conflicts_only {
+            collected = keybind::conflicting(&collected, |keybind| passing.contains(keybind));
+            emitted = collected.len();
+        }
+        if args.collapse_numeric {
+            collected = keybind::collapse_numeric(collected);
+            emitted = collected.len();
+        }
+        if args.collapse_directions {
+            collected = keybind::collapse_directions(collected);
+            emitted = collected.len();
+        }
+        if let Some(sort) = args.sort {
+            keybind::sort_keybinds(&mut collected, sort);
+        }
+        // Ranking is stable, so --sort still orders equally good matches
+        if args.fuzzy
+            && let Some(query) = &args.query
+        {
+            keybind::rank_by_query(&mut collected, query);
+        }
+        if args.group_by_program {
+            match args.sort_within_group {
+                Some(within) => {
+                    keybind::group_by_program_with(&mut collected, |a, b| within.compare(a, b))
+                }
+                None => keybind::group_by_program(&mut collected),
+            }
+        }
+        if args.reverse {
+            collected.reverse();
+        }
+        if let Some(n) = args.sample {
+            let mut rng = args.seed.map_or_else(Rng::from_time, Rng::new);
+            sample::sample(&mut collected, n, &mut rng);
+            emitted = emitted.min(n);
+        }
+        if let Some(limit) = args.limit {
+            collected.truncate(limit);
+            emitted = emitted.min(limit);
+        }
+        // Only the picked keybind is printed, in the chosen format
+        if args.tui {
+            collected = pick(collected).into_iter().collect();
+            emitted = collected.len();
+        }
+
+        let groups: Option<Vec<(String, Vec<&Keybind>)>> = if args.group_by_modifiers {
+            Some(
+                keybind::group_by_modifiers(&collected)
+                    .into_iter()
+                    .map(|(modifiers, keybinds)| (format::modifiers_heading(&modifiers), keybinds))
+                    .collect(),
+            )
+        } else if args.group_by_category {
+            Some(
+                keybind::group_by_category(&collected)
+                    .into_iter()
+                    .map(|(category, keybinds)| (category.to_string(), keybinds))
+                    .collect(),
+            )
+        } else {
+            None
+        };
+
+        let lines: Vec<String> = if let Some(groups) = groups {
+            let groups: Vec<(String, Vec<Keybind>)> = groups
+                .into_iter()
+                .map(|(heading, keybinds)| (heading, keybinds.into_iter().map(fit).collect()))
+                .collect();
+            let groups = groups
+                .iter()
+                .map(|(heading, keybinds)| (heading.clone(), keybinds.iter().collect()));
+            format::grouped_lines(groups, !args.no_program_tag)
+        } else if args.merge_aliases {
+            keybind::merge_by_action(&collected)
+                .into_iter()
+                .map(|merged| match action_width {
+                    Some(width) => merged.with_action_width(width),
+                    None => merged,
+                })
+                .map(|merged| format::merged_line(&merged, !args.no_program_tag))
+                .collect()
+        } else {
+            match format {
+                OutputFormat::Columns => {
+                    format::columns(&collected.iter().map(fit).collect::<Vec<_>>())
+                }
+                OutputFormat::Json => vec![format::json(&collected, args.pretty)],
+                OutputFormat::Desktop => collected
+                    .iter()
+                    .map(|keybind| render(keybind, indices.get(keybind).copied().unwrap_or(0)))
+                    .collect(),
+                OutputFormat::Plain | OutputFormat::Tab | OutputFormat::Ndjson => {
+                    let mut lines = Vec::new();
+                    for (i, keybind) in collected.iter().enumerate() {
+                        if let Some(separator) = &args.group_separator
+                            && i > 0
+                            && collected[i - 1].program != keybind.program
+                        {
+                            lines.push(separator.clone());
+                        }
+                        lines.push(render(keybind, indices.get(keybind).copied().unwrap_or(0)));
+                    }
+                    lines
+                }
+            }
+        };
+        for line in lines {
+            let _ = writeln!(out, "{}", line);
+        }
+    }
+
+    let _ = out.flush();
+
+    if !report.errors.is_empty() && report.errors.len() == opts.source_count() {
+        ExitCode::from(EXIT_ALL_SOURCES_FAILED)
+    } else if emitted == 0 {
+        ExitCode::from(EXIT_NO_KEYBINDS)
+    } else {
+        ExitCode::SUCCESS
     }
 }
@@ -0,0 +1,78 @@
+use crate::keybind::Keybind;
+
+/// Keysym names of punctuation keys and the glyphs they type, e.g. niri's `Mod+Comma`.
+///
+/// Names are matched case-insensitively, since niri accepts both `comma` and `Comma`.
+pub const PUNCTUATION_GLYPHS: &[(&str, &str)] = &[
+    ("comma", ","),
+    ("period", "."),
+    ("slash", "/"),
+    ("backslash", "\\"),
+    ("semicolon", ";"),
+    ("colon", ":"),
+    ("apostrophe", "'"),
+    ("quotedbl", "\""),
+    ("grave", "`"),
+    ("asciitilde", "~"),
+    ("minus", "-"),
+    ("underscore", "_"),
+    ("equal", "="),
+    ("plus", "+"),
+    ("bracketleft", "["),
+    ("bracketright", "]"),
+    ("braceleft", "{"),
+    ("braceright", "}"),
+    ("parenleft", "("),
+    ("parenright", ")"),
+    ("less", "<"),
+    ("greater", ">"),
+    ("question", "?"),
+    ("exclam", "!"),
+    ("at", "@"),
+    ("numbersign", "#"),
+    ("dollar", "$"),
+    ("percent", "%"),
+    ("asciicircum", "^"),
+    ("ampersand", "&"),
+    ("asterisk", "*"),
+    ("bar", "|"),
+];
+
+/// The glyph for a punctuation keysym like `comma`; any other key is returned as is.
+pub fn pretty_key(key: &str) -> &str {
+    PUNCTUATION_GLYPHS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .map_or(key, |(_, glyph)| glyph)
+}
+
+/// The keybind with every step's key passed through [`pretty_key`], for display only.
+pub fn with_pretty_keys(mut keybind: Keybind) -> Keybind {
+    for (_, key) in &mut keybind.key.0 {
+        *key = pretty_key(key).to_string();
+    }
+    keybind
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keybind::Modifier;
+
+    #[test]
+    fn test_pretty_key() {
+        assert_eq!(pretty_key("comma"), ",");
+        assert_eq!(pretty_key("Comma"), ",");
+        assert_eq!(pretty_key("slash"), "/");
+        assert_eq!(pretty_key("Foo"), "Foo");
+    }
+
+    #[test]
+    fn test_with_pretty_keys() {
+        let keybind = Keybind::new("niri", "Comma")
+            .modifiers(vec![Modifier::Mod])
+            .action("consume-window-into-column");
+
+        assert_eq!(with_pretty_keys(keybind).keys_string(), "Mod+,");
+    }
+}
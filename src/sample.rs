@@ -0,0 +1,72 @@
+/// A small SplitMix64 generator: fast, seedable and stable across platforms,
+/// so a given seed always draws the same sample.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    /// Seeds from the clock, for when no `--seed` is given.
+    pub fn from_time() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Rng(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`; `bound` must be non-zero.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+/// Keeps `n` items picked at random, in random order.
+///
+/// This is a partial Fisher-Yates shuffle, so every subset is equally likely.
+/// With `n` at least `items.len()` every item is kept, shuffled.
+pub fn sample<T>(items: &mut Vec<T>, n: usize, rng: &mut Rng) {
+    let n = n.min(items.len());
+    for i in 0..n {
+        let j = i + rng.below(items.len() - i);
+        items.swap(i, j);
+    }
+    items.truncate(n);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sampled(seed: u64) -> Vec<u32> {
+        let mut items: Vec<u32> = (0..100).collect();
+        sample(&mut items, 10, &mut Rng::new(seed));
+        items
+    }
+
+    #[test]
+    fn test_same_seed_same_sample() {
+        assert_eq!(sampled(42), sampled(42));
+    }
+
+    #[test]
+    fn test_different_seeds_differ() {
+        assert_ne!(sampled(1), sampled(2));
+    }
+
+    #[test]
+    fn test_sample_larger_than_items_keeps_all() {
+        let mut items = vec![1, 2, 3];
+        sample(&mut items, 10, &mut Rng::new(0));
+        items.sort();
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+}
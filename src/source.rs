@@ -1,8 +1,56 @@
+use crate::error::SourceError;
 use std::fmt::Display;
 
 pub trait Source {
     type Item: Display;
 
     fn name(&self) -> &str;
-    fn discover(&self) -> Result<Vec<Self::Item>, Box<dyn std::error::Error>>;
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError>;
+
+    /// Whether discovery has anything to read, e.g. the config file exists.
+    ///
+    /// Defaults to `true`; sources with a cheap check should override it.
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    /// Passes each discovered item to `sink` as it becomes available.
+    ///
+    /// The default implementation runs `discover` and then feeds the results
+    /// through; sources that can yield items incrementally may override it.
+    fn discover_into(&self, sink: &mut dyn FnMut(Self::Item)) -> Result<(), SourceError> {
+        for item in self.discover()? {
+            sink(item);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeSource;
+
+    impl Source for FakeSource {
+        type Item = String;
+
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+            Ok(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        }
+    }
+
+    #[test]
+    fn test_discover_into_calls_sink_per_item() {
+        let mut received = Vec::new();
+        FakeSource
+            .discover_into(&mut |item| received.push(item))
+            .unwrap();
+
+        assert_eq!(received, vec!["a", "b", "c"]);
+    }
 }
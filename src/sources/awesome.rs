@@ -0,0 +1,348 @@
+use crate::error::SourceError;
+use crate::keybind::{Keybind, Modifier, Program};
+use crate::source::Source;
+use std::fs;
+use std::path::PathBuf;
+
+/// Source for discovering keybinds from an awesome `rc.lua`.
+///
+/// # Discovery Method
+///
+/// The config is scanned statically, it is never executed. `awful.key(...)`
+/// calls are located outside of comments and strings, and their arguments are
+/// extracted by balanced-paren matching:
+/// - `awful.key({ modkey, "Shift" }, "Return", function () awful.spawn(terminal) end,
+///   {description = "open a terminal", group = "launcher"})`
+///
+/// The body of an inline `function () ... end` becomes the action, and the
+/// `description` field of the trailing table becomes the description. Keys
+/// built at runtime, such as `"#" .. i + 9` in a loop, are skipped.
+pub struct AwesomeSource {
+    config_path: PathBuf,
+}
+
+impl AwesomeSource {
+    pub fn new(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    pub fn from_default_config() -> Result<Self, Box<dyn std::error::Error>> {
+        let config_path = if let Ok(home) = std::env::var("HOME") {
+            PathBuf::from(home).join(".config/awesome/rc.lua")
+        } else {
+            return Err("HOME environment variable not set".into());
+        };
+
+        Ok(Self::new(config_path))
+    }
+
+    fn parse_config(&self, content: &str) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+        let mut keybinds = Vec::new();
+
+        for args in Self::find_calls(content, "awful.key") {
+            let args = Self::split_args(args);
+            let Some(modifiers) = args.first().and_then(|list| Self::parse_modifiers(list)) else {
+                continue;
+            };
+            let Some(key) = args.get(1).and_then(|key| Self::string_literal(key)) else {
+                continue;
+            };
+
+            let action = match args.get(2) {
+                Some(action) => Self::function_body(action),
+                None => "unknown".to_string(),
+            };
+            let description = args
+                .get(3)
+                .and_then(|table| Self::table_field(table, "description"));
+
+            keybinds.push(
+                Keybind::new(Program::Awesome, key)
+                    .modifiers(modifiers)
+                    .action(action)
+                    .description(description),
+            );
+        }
+
+        Ok(keybinds)
+    }
+
+    /// Parses a modifier table like `{ modkey, "Shift" }`.
+    fn parse_modifiers(list: &str) -> Option<Vec<Modifier>> {
+        let list = list.trim().strip_prefix('{')?.strip_suffix('}')?;
+
+        let mut modifiers = Vec::new();
+        for token in Self::split_args(list) {
+            let token = Self::string_literal(token).unwrap_or_else(|| token.to_string());
+            modifiers.push(Self::parse_modifier(&token).ok()?);
+        }
+        Some(modifiers)
+    }
+
+    fn parse_modifier(name: &str) -> Result<Modifier, Box<dyn std::error::Error>> {
+        match name {
+            "modkey" => Ok(Modifier::Mod),
+            "Shift" => Ok(Modifier::Shift),
+            "Control" => Ok(Modifier::Ctrl),
+            "Mod1" => Ok(Modifier::Alt),
+            "Mod4" => Ok(Modifier::Super),
+            "Mod5" => Ok(Modifier::IsoLevel3Shift),
+            "Mod3" => Ok(Modifier::IsoLevel5Shift),
+            _ => Err(format!("Unknown modifier: {}", name).into()),
+        }
+    }
+
+    /// Unwraps `function () body end` to `body`; anything else is kept as written.
+    fn function_body(text: &str) -> String {
+        let text = Self::collapse_whitespace(text);
+        let body = text
+            .strip_prefix("function")
+            .and_then(|rest| rest.trim_start().strip_prefix('('))
+            .and_then(|rest| rest.split_once(')'))
+            .and_then(|(_, rest)| rest.trim().strip_suffix("end"))
+            .map(str::trim);
+
+        match body {
+            Some(body) if !body.is_empty() => body.to_string(),
+            _ => text,
+        }
+    }
+
+    /// Reads the string value of `field` from a table like `{description = "...", group = "..."}`.
+    fn table_field(table: &str, field: &str) -> Option<String> {
+        let table = table.trim().strip_prefix('{')?.strip_suffix('}')?;
+        Self::split_args(table).into_iter().find_map(|entry| {
+            let (name, value) = entry.split_once('=')?;
+            if name.trim() == field {
+                Self::string_literal(value)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Finds calls to `name`, returning the text between each call's parens.
+    fn find_calls<'a>(text: &'a str, name: &str) -> Vec<&'a str> {
+        let bytes = text.as_bytes();
+        let mut calls = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'-' if bytes.get(i + 1) == Some(&b'-') => i = Self::skip_comment(bytes, i),
+                b'"' | b'\'' => i = Self::skip_string(bytes, i),
+                c if c.is_ascii_alphabetic() || c == b'_' => {
+                    let start = i;
+                    while i < bytes.len()
+                        && (bytes[i].is_ascii_alphanumeric()
+                            || bytes[i] == b'_'
+                            || bytes[i] == b'.')
+                    {
+                        i += 1;
+                    }
+                    let open = i + text[i..].len() - text[i..].trim_start().len();
+                    if &text[start..i] == name
+                        && bytes.get(open) == Some(&b'(')
+                        && let Some(end) = Self::matching_paren(bytes, open)
+                    {
+                        calls.push(&text[open + 1..end]);
+                        i = end + 1;
+                    }
+                }
+                _ => i += 1,
+            }
+        }
+
+        calls
+    }
+
+    /// Returns the index of the closer matching the opener at `open`.
+    fn matching_paren(bytes: &[u8], open: usize) -> Option<usize> {
+        let mut depth = 0usize;
+        let mut i = open;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                    i = Self::skip_comment(bytes, i);
+                    continue;
+                }
+                b'"' | b'\'' => {
+                    i = Self::skip_string(bytes, i);
+                    continue;
+                }
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => {
+                    depth = depth.checked_sub(1)?;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Splits a call's argument text on top-level commas.
+    fn split_args(text: &str) -> Vec<&str> {
+        let bytes = text.as_bytes();
+        let mut args = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                    i = Self::skip_comment(bytes, i);
+                    continue;
+                }
+                b'"' | b'\'' => {
+                    i = Self::skip_string(bytes, i);
+                    continue;
+                }
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+                b',' if depth == 0 => {
+                    args.push(text[start..i].trim());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        let last = text[start..].trim();
+        if !last.is_empty() {
+            args.push(last);
+        }
+
+        args
+    }
+
+    /// Skips a `--` line comment or a `--[[ ]]` block comment starting at `i`.
+    fn skip_comment(bytes: &[u8], mut i: usize) -> usize {
+        if bytes[i..].starts_with(b"--[[") {
+            return match bytes[i..].windows(2).position(|pair| pair == b"]]") {
+                Some(end) => i + end + 2,
+                None => bytes.len(),
+            };
+        }
+
+        while i < bytes.len() && bytes[i] != b'\n' {
+            i += 1;
+        }
+        i
+    }
+
+    /// Skips a quoted string starting at `i`, returning the index just past its closing quote.
+    fn skip_string(bytes: &[u8], mut i: usize) -> usize {
+        let quote = bytes[i];
+        i += 1;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 2,
+                c if c == quote => return i + 1,
+                _ => i += 1,
+            }
+        }
+        i
+    }
+
+    fn string_literal(text: &str) -> Option<String> {
+        let text = text.trim();
+        let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let inner = text.strip_prefix(quote)?.strip_suffix(quote)?;
+        // Concatenations like `"#" .. i` aren't a single literal
+        if inner.contains(quote) && !inner.contains(&format!("\\{}", quote)) {
+            return None;
+        }
+        Some(inner.replace(&format!("\\{}", quote), &quote.to_string()))
+    }
+
+    fn collapse_whitespace(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Source for AwesomeSource {
+    type Item = Keybind;
+
+    fn name(&self) -> &str {
+        "awesome"
+    }
+
+    fn is_available(&self) -> bool {
+        self.config_path.is_file()
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        let content = fs::read_to_string(&self.config_path)
+            .map_err(|e| SourceError::from_io(&self.config_path, e))?;
+        self.parse_config(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_with_description() {
+        let source = AwesomeSource::new(PathBuf::new());
+        let content = r#"
+local modkey = "Mod4"
+
+globalkeys = gears.table.join(
+    -- awful.key({ modkey }, "x", function () commented.out() end),
+    awful.key({ modkey, "Shift" }, "Return", function () awful.spawn(terminal) end,
+              {description = "open a terminal", group = "launcher"}),
+    awful.key({ "Mod1", "Control" }, "q", awesome.quit,
+              {group = "awesome", description = 'quit "awesome"'})
+)
+"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 2);
+
+        let terminal = &keybinds[0];
+        assert_eq!(terminal.key.modifiers(), [Modifier::Mod, Modifier::Shift]);
+        assert_eq!(terminal.key.first_key(), "Return");
+        assert_eq!(terminal.action, "awful.spawn(terminal)");
+        assert_eq!(terminal.description.as_deref(), Some("open a terminal"));
+        assert_eq!(terminal.program, Program::Awesome);
+
+        assert_eq!(keybinds[1].key.modifiers(), [Modifier::Alt, Modifier::Ctrl]);
+        assert_eq!(keybinds[1].action, "awesome.quit");
+        assert_eq!(keybinds[1].description.as_deref(), Some("quit \"awesome\""));
+    }
+
+    #[test]
+    fn test_parse_key_without_description() {
+        let source = AwesomeSource::new(PathBuf::new());
+        let content = r##"
+clientkeys = gears.table.join(
+    awful.key({ modkey }, "f",
+        function (c)
+            c.fullscreen = not c.fullscreen
+            c:raise()
+        end),
+    awful.key({ modkey }, "#" .. i + 9, function () end)
+)
+"##;
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 1);
+
+        assert_eq!(keybinds[0].keys_string(), "Mod+f");
+        assert_eq!(
+            keybinds[0].action,
+            "c.fullscreen = not c.fullscreen c:raise()"
+        );
+        assert_eq!(keybinds[0].description, None);
+    }
+}
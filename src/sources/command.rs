@@ -0,0 +1,104 @@
+use crate::error::SourceError;
+use crate::format;
+use crate::keybind::Keybind;
+use crate::source::Source;
+use std::process::Command;
+
+/// Source for keybinds listed by an external command, e.g. `--source-cmd 'my-tool --list'`.
+///
+/// # Discovery Method
+///
+/// The command is run with `sh -c` and must print keybinds in the format of
+/// `--format ndjson` (or `--format json`) on stdout. This lets any program be
+/// searched without a source built into the crate; the keybinds keep whatever
+/// `program` the command gives them.
+///
+/// A command that can't be started or exits unsuccessfully makes the source
+/// unavailable, with its stderr as the reason.
+pub struct CommandSource {
+    command: String,
+}
+
+impl CommandSource {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+        }
+    }
+
+    fn parse_output(&self, stdout: &[u8]) -> Result<Vec<Keybind>, SourceError> {
+        format::load_json(stdout)
+            .map_err(|e| SourceError::Parse(format!("output of `{}`: {}", self.command, e)))
+    }
+}
+
+impl Source for CommandSource {
+    type Item = Keybind;
+
+    fn name(&self) -> &str {
+        &self.command
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .output()
+            .map_err(|e| {
+                SourceError::Unavailable(format!("can't run `{}`: {}", self.command, e))
+            })?;
+
+        if !output.status.success() {
+            return Err(SourceError::Unavailable(format!(
+                "`{}` failed ({}): {}",
+                self.command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        self.parse_output(&output.stdout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keybind::{Modifier, Program};
+
+    #[test]
+    fn test_parse_ndjson_output() {
+        let source = CommandSource::new("my-tool --list");
+        let stdout = br#"{"modifiers":["Ctrl"],"key":"p","action":"open","description":"Open file","program":"my-tool","repeat":null,"cooldown_ms":null,"allow_when_locked":null,"allow_inhibiting":null}
+{"modifiers":[],"key":"q","action":"quit","description":null,"program":"my-tool","repeat":null,"cooldown_ms":null,"allow_when_locked":null,"allow_inhibiting":null}
+"#;
+
+        let keybinds = source.parse_output(stdout).unwrap();
+
+        assert_eq!(keybinds.len(), 2);
+        assert_eq!(keybinds[0].key.modifiers(), [Modifier::Ctrl]);
+        assert_eq!(keybinds[0].description.as_deref(), Some("Open file"));
+        assert_eq!(keybinds[1].keys_string(), "q");
+        assert_eq!(keybinds[1].program, Program::Other("my-tool".to_string()));
+    }
+
+    #[test]
+    fn test_discover_runs_command() {
+        let line = r#"{"modifiers":[],"key":"x","action":"cut","description":null,"program":"tool","repeat":null,"cooldown_ms":null,"allow_when_locked":null,"allow_inhibiting":null}"#;
+        let source = CommandSource::new(format!("printf '%s\\n%s\\n' '{}' '{}'", line, line));
+
+        let keybinds = source.discover().unwrap();
+
+        assert_eq!(keybinds.len(), 2);
+        assert_eq!(keybinds[0].action, "cut");
+    }
+
+    #[test]
+    fn test_failing_command_unavailable() {
+        let source = CommandSource::new("echo oops >&2; exit 3");
+
+        match source.discover() {
+            Err(SourceError::Unavailable(reason)) => assert!(reason.contains("oops")),
+            other => panic!("expected an unavailable source, got {:?}", other),
+        }
+    }
+}
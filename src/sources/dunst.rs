@@ -0,0 +1,153 @@
+use crate::error::SourceError;
+use crate::keybind::{Keybind, Modifier, Program};
+use crate::source::Source;
+use crate::sources::ini;
+use std::fs;
+use std::path::PathBuf;
+
+/// Source for discovering keybinds from a dunst `dunstrc`.
+///
+/// # Discovery Method
+///
+/// dunst's config is INI-like; keybinds live in the `[shortcuts]` section as
+/// `action = combo` pairs (e.g. `close = ctrl+space`). Each pair is inverted so
+/// the combo becomes the keybind and the left-hand name becomes the action.
+/// Shortcuts set to `none` are disabled in dunst and are skipped.
+pub struct DunstSource {
+    config_path: PathBuf,
+}
+
+impl DunstSource {
+    pub fn new(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    pub fn from_default_config() -> Result<Self, Box<dyn std::error::Error>> {
+        let config_path = if let Ok(home) = std::env::var("HOME") {
+            PathBuf::from(home).join(".config/dunst/dunstrc")
+        } else {
+            return Err("HOME environment variable not set".into());
+        };
+
+        Ok(Self::new(config_path))
+    }
+
+    fn parse_config(&self, content: &str) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+        let mut keybinds = Vec::new();
+
+        for (section, action, combo) in ini::parse_ini(content) {
+            if section != "shortcuts" {
+                continue;
+            }
+
+            let combo = combo.trim_matches('"');
+            if combo.eq_ignore_ascii_case("none") {
+                continue;
+            }
+
+            let Ok((modifiers, key)) = Self::parse_key_combination(combo) else {
+                continue;
+            };
+
+            keybinds.push(
+                Keybind::new(Program::Dunst, key)
+                    .modifiers(modifiers)
+                    .action(action),
+            );
+        }
+
+        Ok(keybinds)
+    }
+
+    fn parse_key_combination(
+        combo: &str,
+    ) -> Result<(Vec<Modifier>, String), Box<dyn std::error::Error>> {
+        let parts: Vec<&str> = combo.split('+').map(str::trim).collect();
+
+        let mut modifiers = Vec::new();
+        let key = parts[parts.len() - 1].to_string();
+
+        if key.is_empty() {
+            return Err(format!("Missing key in '{}'", combo).into());
+        }
+
+        for part in &parts[..parts.len() - 1] {
+            let modifier = match part.to_lowercase().as_str() {
+                "ctrl" | "control" => Modifier::Ctrl,
+                "shift" => Modifier::Shift,
+                "mod1" | "alt" => Modifier::Alt,
+                "mod4" | "super" => Modifier::Super,
+                _ => return Err(format!("Unknown modifier: {}", part).into()),
+            };
+            modifiers.push(modifier);
+        }
+
+        Ok((modifiers, key))
+    }
+}
+
+impl Source for DunstSource {
+    type Item = Keybind;
+
+    fn name(&self) -> &str {
+        "dunst"
+    }
+
+    fn is_available(&self) -> bool {
+        self.config_path.is_file()
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        let content = fs::read_to_string(&self.config_path)
+            .map_err(|e| SourceError::from_io(&self.config_path, e))?;
+        self.parse_config(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shortcut() {
+        let source = DunstSource::new(PathBuf::new());
+        let content = "
+[global]
+    font = Monospace 8
+
+[shortcuts]
+    # Close the top notification
+    close = ctrl+space
+    context = mod4+shift+period
+";
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 2);
+
+        assert_eq!(keybinds[0].key.modifiers(), [Modifier::Ctrl]);
+        assert_eq!(keybinds[0].key.first_key(), "space");
+        assert_eq!(keybinds[0].action, "close");
+        assert_eq!(keybinds[0].program, Program::Dunst);
+
+        assert_eq!(
+            keybinds[1].key.modifiers(),
+            [Modifier::Super, Modifier::Shift]
+        );
+        assert_eq!(keybinds[1].key.first_key(), "period");
+    }
+
+    #[test]
+    fn test_none_shortcut_skipped() {
+        let source = DunstSource::new(PathBuf::new());
+        let content = "
+[shortcuts]
+    close = none
+    history = ctrl+grave
+";
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 1);
+        assert_eq!(keybinds[0].action, "history");
+    }
+}
@@ -0,0 +1,381 @@
+use crate::error::SourceError;
+use crate::keybind::{Keybind, Modifier, Program};
+use crate::source::Source;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Source for discovering keybinds from dwm's `config.h`.
+///
+/// # Discovery Method
+///
+/// dwm is configured at build time, so there's no standard location to read
+/// from. The C source is scanned for the `keys[]` array, and each row is
+/// extracted by balanced-brace matching outside of comments and strings:
+/// - `{ MODKEY|ShiftMask, XK_Return, spawn, {.v = termcmd } }`
+///
+/// `MODKEY` (or any other modifier macro) is resolved from its `#define`, and
+/// `XK_` keysyms become key names like `Return`. The action is the function
+/// name, followed by its argument unless that's the empty `{0}`. Rows produced
+/// by macros such as `TAGKEYS(XK_1, 0)` can't be resolved statically and are skipped.
+pub struct DwmSource {
+    config_path: PathBuf,
+}
+
+impl DwmSource {
+    pub fn new(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    fn parse_config(&self, content: &str) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+        let content = Self::strip_comments(content);
+        let defines = Self::parse_defines(&content);
+
+        let Some(rows) = Self::find_keys_array(&content) else {
+            return Err("No keys[] array found".into());
+        };
+
+        let mut keybinds = Vec::new();
+        for row in Self::brace_groups(rows) {
+            let fields = Self::split_top_level(row);
+            let [modifiers, key, function, rest @ ..] = fields.as_slice() else {
+                continue;
+            };
+            let (Some(modifiers), Some(key)) = (
+                Self::parse_modifiers(modifiers, &defines),
+                Self::keysym_name(key),
+            ) else {
+                continue;
+            };
+
+            let mut action = function.to_string();
+            if let Some(argument) = rest.first()
+                && Self::collapse_whitespace(argument) != "{0}"
+            {
+                action.push(' ');
+                action.push_str(&Self::collapse_whitespace(argument));
+            }
+
+            keybinds.push(
+                Keybind::new(Program::Dwm, key)
+                    .modifiers(modifiers)
+                    .action(action),
+            );
+        }
+
+        Ok(keybinds)
+    }
+
+    /// Collects simple `#define NAME value` lines, e.g. `MODKEY` -> `Mod4Mask`.
+    fn parse_defines(content: &str) -> HashMap<&str, &str> {
+        content
+            .lines()
+            .filter_map(|line| {
+                let rest = line.trim_start().strip_prefix('#')?.trim_start();
+                let mut words = rest.strip_prefix("define")?.split_whitespace();
+                let name = words.next().filter(|name| !name.contains('('))?;
+                let value = words.next()?;
+                Some((name, value))
+            })
+            .collect()
+    }
+
+    /// Returns the text between the braces of the `keys[] = { ... }` initializer.
+    fn find_keys_array(content: &str) -> Option<&str> {
+        let bytes = content.as_bytes();
+        let mut search_from = 0;
+
+        while let Some(offset) = content[search_from..].find("keys") {
+            let start = search_from + offset;
+            search_from = start + "keys".len();
+
+            // `keys` must be a whole identifier, as in `static const Key keys[] = {`
+            let before = start.checked_sub(1).map(|i| bytes[i]);
+            if before.is_some_and(|c| c.is_ascii_alphanumeric() || c == b'_') {
+                continue;
+            }
+            let Some(rest) = content[search_from..].trim_start().strip_prefix('[') else {
+                continue;
+            };
+            let Some(rest) = rest.trim_start().strip_prefix(']') else {
+                continue;
+            };
+            let Some(rest) = rest.trim_start().strip_prefix('=') else {
+                continue;
+            };
+            if !rest.trim_start().starts_with('{') {
+                continue;
+            }
+
+            let open = content.len() - rest.trim_start().len();
+            let close = Self::matching_brace(bytes, open)?;
+            return Some(&content[open + 1..close]);
+        }
+
+        None
+    }
+
+    fn parse_modifiers(text: &str, defines: &HashMap<&str, &str>) -> Option<Vec<Modifier>> {
+        let mut modifiers = Vec::new();
+        for mask in text.split('|').map(str::trim) {
+            // Follow macros like MODKEY, which may expand to several masks
+            match defines.get(mask) {
+                Some(value) if *value != mask => {
+                    modifiers.extend(Self::parse_modifiers(value, &HashMap::new())?)
+                }
+                _ => {
+                    if let Some(modifier) = Self::parse_mask(mask)? {
+                        modifiers.push(modifier);
+                    }
+                }
+            }
+        }
+        Some(modifiers)
+    }
+
+    /// Maps an X11 modifier mask; `Some(None)` for the empty mask `0`.
+    fn parse_mask(mask: &str) -> Option<Option<Modifier>> {
+        let modifier = match mask.trim_matches(|c| c == '(' || c == ')') {
+            "0" => return Some(None),
+            "ShiftMask" => Modifier::Shift,
+            "ControlMask" => Modifier::Ctrl,
+            "Mod1Mask" => Modifier::Alt,
+            "Mod4Mask" => Modifier::Super,
+            "Mod5Mask" => Modifier::IsoLevel3Shift,
+            "Mod3Mask" => Modifier::IsoLevel5Shift,
+            _ => return None,
+        };
+        Some(Some(modifier))
+    }
+
+    /// `XK_Return` -> `Return`, `XF86XK_AudioMute` -> `XF86AudioMute`.
+    fn keysym_name(keysym: &str) -> Option<String> {
+        if let Some(name) = keysym.strip_prefix("XF86XK_") {
+            return Some(format!("XF86{}", name));
+        }
+        keysym
+            .strip_prefix("XK_")
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Removes `//` and `/* */` comments outside of string and character literals.
+    fn strip_comments(content: &str) -> String {
+        let mut result = String::with_capacity(content.len());
+        let mut chars = content.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match (c, chars.peek()) {
+                ('/', Some('/')) => while chars.next_if(|&next| next != '\n').is_some() {},
+                ('/', Some('*')) => {
+                    chars.next();
+                    let mut previous = None;
+                    for next in chars.by_ref() {
+                        if previous == Some('*') && next == '/' {
+                            break;
+                        }
+                        previous = Some(next);
+                    }
+                    result.push(' ');
+                }
+                ('"' | '\'', _) => {
+                    result.push(c);
+                    while let Some(next) = chars.next() {
+                        result.push(next);
+                        if next == '\\' {
+                            result.extend(chars.next());
+                        } else if next == c {
+                            break;
+                        }
+                    }
+                }
+                _ => result.push(c),
+            }
+        }
+
+        result
+    }
+
+    /// Returns the index of the closer matching the bracket at `open`.
+    fn matching_brace(bytes: &[u8], open: usize) -> Option<usize> {
+        let mut depth = 0usize;
+        let mut i = open;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' | b'\'' => {
+                    i = Self::skip_string(bytes, i);
+                    continue;
+                }
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => {
+                    depth = depth.checked_sub(1)?;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Returns the contents of each top-level `{ ... }` group, skipping anything else.
+    ///
+    /// Macro rows like `TAGKEYS(XK_1, 0)` often lack a trailing comma, so rows
+    /// are found by their braces rather than by splitting on commas.
+    fn brace_groups(text: &str) -> Vec<&str> {
+        let bytes = text.as_bytes();
+        let mut groups = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' | b'\'' => i = Self::skip_string(bytes, i),
+                b'(' | b'{' => {
+                    let Some(close) = Self::matching_brace(bytes, i) else {
+                        break;
+                    };
+                    if bytes[i] == b'{' {
+                        groups.push(&text[i + 1..close]);
+                    }
+                    i = close + 1;
+                }
+                _ => i += 1,
+            }
+        }
+
+        groups
+    }
+
+    /// Splits on top-level commas, dropping empty pieces such as after a trailing comma.
+    fn split_top_level(text: &str) -> Vec<&str> {
+        let bytes = text.as_bytes();
+        let mut parts = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' | b'\'' => {
+                    i = Self::skip_string(bytes, i);
+                    continue;
+                }
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+                b',' if depth == 0 => {
+                    parts.push(text[start..i].trim());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        parts.push(text[start..].trim());
+
+        parts.retain(|part| !part.is_empty());
+        parts
+    }
+
+    /// Skips a quoted literal starting at `i`, returning the index just past its closing quote.
+    fn skip_string(bytes: &[u8], mut i: usize) -> usize {
+        let quote = bytes[i];
+        i += 1;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 2,
+                c if c == quote => return i + 1,
+                _ => i += 1,
+            }
+        }
+        i
+    }
+
+    fn collapse_whitespace(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Source for DwmSource {
+    type Item = Keybind;
+
+    fn name(&self) -> &str {
+        "dwm"
+    }
+
+    fn is_available(&self) -> bool {
+        self.config_path.is_file()
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        let content = fs::read_to_string(&self.config_path)
+            .map_err(|e| SourceError::from_io(&self.config_path, e))?;
+        self.parse_config(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys_array() {
+        let source = DwmSource::new(PathBuf::new());
+        let content = r#"
+/* key definitions */
+#define MODKEY Mod4Mask
+#define TAGKEYS(KEY,TAG) \
+	{ MODKEY,                       KEY,      view,           {.ui = 1 << TAG} },
+
+static const char *termcmd[]  = { "st", NULL };
+
+static const Key keys[] = {
+	/* modifier                     key        function        argument */
+	{ MODKEY|ShiftMask,             XK_Return, spawn,          {.v = termcmd } },
+	// { MODKEY, XK_x, killclient, {0} },
+	{MODKEY,XK_j,focusstack,{.i = +1 }},
+	{ 0,                            XF86XK_AudioMute, spawn,   SHCMD("pamixer -t") },
+	TAGKEYS(                        XK_1,                      0)
+	{ MODKEY|ControlMask|ShiftMask, XK_q,      quit,           {0} },
+};
+
+static const Button buttons[] = {
+	{ ClkTagBar, MODKEY, Button1, tag, {0} },
+};
+"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 4);
+
+        assert_eq!(
+            keybinds[0].key.modifiers(),
+            [Modifier::Super, Modifier::Shift]
+        );
+        assert_eq!(keybinds[0].key.first_key(), "Return");
+        assert_eq!(keybinds[0].action, "spawn {.v = termcmd }");
+        assert_eq!(keybinds[0].program, Program::Dwm);
+
+        assert_eq!(keybinds[1].keys_string(), "Super+j");
+        assert_eq!(keybinds[1].action, "focusstack {.i = +1 }");
+
+        assert!(keybinds[2].key.modifiers().is_empty());
+        assert_eq!(keybinds[2].key.first_key(), "XF86AudioMute");
+        assert_eq!(keybinds[2].action, "spawn SHCMD(\"pamixer -t\")");
+
+        assert_eq!(
+            keybinds[3].key.modifiers(),
+            [Modifier::Super, Modifier::Ctrl, Modifier::Shift]
+        );
+        assert_eq!(keybinds[3].action, "quit");
+    }
+
+    #[test]
+    fn test_missing_keys_array() {
+        let source = DwmSource::new(PathBuf::new());
+        assert!(source.parse_config("#define MODKEY Mod1Mask\n").is_err());
+    }
+}
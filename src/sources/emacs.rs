@@ -0,0 +1,378 @@
+use crate::error::SourceError;
+use crate::keybind::{Chord, Keybind, Modifier, Program};
+use crate::source::Source;
+use std::fs;
+use std::path::PathBuf;
+
+/// Source for discovering keybinds from an Emacs init file.
+///
+/// # Discovery Method
+///
+/// The init file is read with a small, tolerant s-expression reader that only
+/// understands what is needed to find keybinding forms:
+/// - `(global-set-key (kbd "C-x C-f") #'find-file)`
+/// - `(define-key some-mode-map (kbd "C-c a") 'org-agenda)`
+///
+/// Forms are found at any nesting depth, so bindings inside `with-eval-after-load`
+/// or `use-package` blocks are picked up too. Nothing is evaluated; keys that are
+/// not written with `kbd` (or as a plain string in `kbd` notation) are skipped.
+pub struct EmacsSource {
+    init_path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Sexp {
+    Atom(String),
+    Str(String),
+    List(Vec<Sexp>),
+}
+
+impl EmacsSource {
+    pub fn new(init_path: PathBuf) -> Self {
+        Self { init_path }
+    }
+
+    pub fn from_default_config() -> Result<Self, Box<dyn std::error::Error>> {
+        let home = std::env::var("HOME").map_err(|_| "HOME environment variable not set")?;
+        let home = PathBuf::from(home);
+
+        let candidates = [
+            home.join(".emacs.d/init.el"),
+            home.join(".config/emacs/init.el"),
+            home.join(".emacs"),
+        ];
+
+        let init_path = candidates
+            .iter()
+            .find(|path| path.exists())
+            .cloned()
+            .unwrap_or_else(|| candidates[0].clone());
+
+        Ok(Self::new(init_path))
+    }
+
+    fn parse_config(&self, content: &str) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+        let forms = Self::read_forms(content);
+
+        let mut keybinds = Vec::new();
+        for form in &forms {
+            Self::collect_keybinds(form, &mut keybinds);
+        }
+
+        Ok(keybinds)
+    }
+
+    fn collect_keybinds(form: &Sexp, keybinds: &mut Vec<Keybind>) {
+        let Sexp::List(items) = form else {
+            return;
+        };
+
+        if let Some(keybind) = Self::parse_binding_form(items) {
+            keybinds.push(keybind);
+            return;
+        }
+
+        for item in items {
+            Self::collect_keybinds(item, keybinds);
+        }
+    }
+
+    fn parse_binding_form(items: &[Sexp]) -> Option<Keybind> {
+        let Some(Sexp::Atom(head)) = items.first() else {
+            return None;
+        };
+
+        let (keymap, key_form, command) = match head.as_str() {
+            "global-set-key" => (None, items.get(1)?, items.get(2)?),
+            "define-key" => {
+                let keymap = match items.get(1)? {
+                    Sexp::Atom(name) => Some(Self::strip_quote(name).to_string()),
+                    _ => None,
+                };
+                (keymap, items.get(2)?, items.get(3)?)
+            }
+            _ => return None,
+        };
+
+        let key_string = Self::key_string(key_form)?;
+        let key = Self::parse_key_sequence(&key_string).ok()?;
+
+        let mut keybind = Keybind::new(Program::Emacs, key).action(Self::command_name(command));
+
+        // Bindings in a specific mode map are only active in that mode
+        if let Some(keymap) = keymap.filter(|name| name != "global-map") {
+            keybind.description = Some(format!("{} ({})", keybind.action, keymap));
+        }
+
+        Some(keybind)
+    }
+
+    fn key_string(form: &Sexp) -> Option<String> {
+        match form {
+            Sexp::Str(s) => Some(s.clone()),
+            Sexp::List(items) => match items.as_slice() {
+                [Sexp::Atom(head), Sexp::Str(s)] if head == "kbd" => Some(s.clone()),
+                _ => None,
+            },
+            Sexp::Atom(_) => None,
+        }
+    }
+
+    fn command_name(form: &Sexp) -> String {
+        match form {
+            Sexp::Atom(name) => Self::strip_quote(name).to_string(),
+            Sexp::Str(s) => format!("\"{}\"", s),
+            Sexp::List(items) => match items.first() {
+                Some(Sexp::Atom(head)) if head == "quote" || head == "function" => items
+                    .get(1)
+                    .map(Self::command_name)
+                    .unwrap_or_else(|| "unknown".to_string()),
+                Some(Sexp::Atom(head)) => Self::strip_quote(head).to_string(),
+                _ => "unknown".to_string(),
+            },
+        }
+    }
+
+    fn strip_quote(name: &str) -> &str {
+        name.trim_start_matches("#'").trim_start_matches('\'')
+    }
+
+    /// Parses a `kbd`-style key description such as `C-x C-f` or `M-x`.
+    ///
+    /// Each whitespace-separated step becomes a step of the chord, matching how
+    /// kitty sequences are represented (e.g. `C-x C-f` displays as `Ctrl+x>Ctrl+f`).
+    fn parse_key_sequence(sequence: &str) -> Result<Chord, Box<dyn std::error::Error>> {
+        let steps = sequence
+            .split_whitespace()
+            .map(Self::parse_key_step)
+            .collect::<Result<Vec<_>, _>>()?;
+        if steps.is_empty() {
+            return Err("Empty key sequence".into());
+        }
+
+        Ok(Chord(steps))
+    }
+
+    fn parse_key_step(step: &str) -> Result<(Vec<Modifier>, String), Box<dyn std::error::Error>> {
+        let mut modifiers = Vec::new();
+        let mut rest = step;
+
+        // A modifier prefix is a single letter followed by '-', as long as something follows it
+        while rest.len() > 2 && rest.as_bytes()[1] == b'-' {
+            let modifier = match &rest[..1] {
+                "C" => Modifier::Ctrl,
+                "M" => Modifier::Meta,
+                "S" => Modifier::Shift,
+                "s" => Modifier::Super,
+                "H" => Modifier::Hyper,
+                "A" => Modifier::Alt,
+                _ => break,
+            };
+            modifiers.push(modifier);
+            rest = &rest[2..];
+        }
+
+        if rest.is_empty() {
+            return Err(format!("Missing key in '{}'", step).into());
+        }
+
+        let key = rest
+            .strip_prefix('<')
+            .and_then(|k| k.strip_suffix('>'))
+            .unwrap_or(rest);
+
+        Ok((modifiers, key.to_string()))
+    }
+
+    /// Reads all top-level forms, stopping quietly at the first unbalanced form.
+    fn read_forms(content: &str) -> Vec<Sexp> {
+        let chars: Vec<char> = content.chars().collect();
+        let mut pos = 0;
+        let mut forms = Vec::new();
+
+        while let Some(form) = Self::read_form(&chars, &mut pos) {
+            forms.push(form);
+        }
+
+        forms
+    }
+
+    fn read_form(chars: &[char], pos: &mut usize) -> Option<Sexp> {
+        Self::skip_whitespace_and_comments(chars, pos);
+
+        let c = *chars.get(*pos)?;
+        match c {
+            '(' | '[' => {
+                let close = if c == '(' { ')' } else { ']' };
+                *pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    Self::skip_whitespace_and_comments(chars, pos);
+                    match chars.get(*pos) {
+                        None => return None,
+                        Some(&ch) if ch == close => {
+                            *pos += 1;
+                            return Some(Sexp::List(items));
+                        }
+                        Some(')') | Some(']') => {
+                            // Mismatched closer: treat it as the end of this list
+                            *pos += 1;
+                            return Some(Sexp::List(items));
+                        }
+                        Some(_) => items.push(Self::read_form(chars, pos)?),
+                    }
+                }
+            }
+            ')' | ']' => {
+                // Stray closer at top level; skip it and keep going
+                *pos += 1;
+                Self::read_form(chars, pos)
+            }
+            '"' => {
+                *pos += 1;
+                let mut s = String::new();
+                while let Some(&ch) = chars.get(*pos) {
+                    *pos += 1;
+                    match ch {
+                        '"' => return Some(Sexp::Str(s)),
+                        '\\' => {
+                            if let Some(&escaped) = chars.get(*pos) {
+                                *pos += 1;
+                                s.push(escaped);
+                            }
+                        }
+                        _ => s.push(ch),
+                    }
+                }
+                None
+            }
+            '\'' | '`' | ',' => {
+                *pos += 1;
+                // 'symbol is kept as the plain symbol
+                Self::read_form(chars, pos)
+            }
+            '#' if chars.get(*pos + 1) == Some(&'\'') => {
+                *pos += 2;
+                Self::read_form(chars, pos)
+            }
+            _ => {
+                let start = *pos;
+                while let Some(&ch) = chars.get(*pos) {
+                    if ch.is_whitespace() || matches!(ch, '(' | ')' | '[' | ']' | '"' | ';') {
+                        break;
+                    }
+                    if ch == '\\' {
+                        *pos += 1;
+                    }
+                    *pos += 1;
+                }
+                let end = (*pos).min(chars.len());
+                Some(Sexp::Atom(chars[start..end].iter().collect()))
+            }
+        }
+    }
+
+    fn skip_whitespace_and_comments(chars: &[char], pos: &mut usize) {
+        while let Some(&ch) = chars.get(*pos) {
+            if ch.is_whitespace() {
+                *pos += 1;
+            } else if ch == ';' {
+                while let Some(&ch) = chars.get(*pos) {
+                    if ch == '\n' {
+                        break;
+                    }
+                    *pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+impl Source for EmacsSource {
+    type Item = Keybind;
+
+    fn name(&self) -> &str {
+        "emacs"
+    }
+
+    fn is_available(&self) -> bool {
+        self.init_path.is_file()
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        let content = fs::read_to_string(&self.init_path)
+            .map_err(|e| SourceError::from_io(&self.init_path, e))?;
+        self.parse_config(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_sequence_chord() {
+        let chord = EmacsSource::parse_key_sequence("C-x C-f").unwrap();
+        assert_eq!(
+            chord.steps(),
+            [
+                (vec![Modifier::Ctrl], "x".to_string()),
+                (vec![Modifier::Ctrl], "f".to_string()),
+            ]
+        );
+        assert_eq!(chord.to_string(), "Ctrl+x>Ctrl+f");
+    }
+
+    #[test]
+    fn test_parse_key_sequence_meta() {
+        let chord = EmacsSource::parse_key_sequence("M-x").unwrap();
+        assert_eq!(chord, Chord::single(vec![Modifier::Meta], "x"));
+    }
+
+    #[test]
+    fn test_parse_key_sequence_hyper_and_alt() {
+        let chord = EmacsSource::parse_key_sequence("H-A-k").unwrap();
+        assert_eq!(
+            chord,
+            Chord::single(vec![Modifier::Hyper, Modifier::Alt], "k")
+        );
+    }
+
+    #[test]
+    fn test_parse_key_sequence_function_key() {
+        let chord = EmacsSource::parse_key_sequence("s-<f5>").unwrap();
+        assert_eq!(chord, Chord::single(vec![Modifier::Super], "f5"));
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let source = EmacsSource::new(PathBuf::new());
+        let content = r#"
+;; (global-set-key (kbd "C-c x") 'commented-out)
+(global-set-key (kbd "M-x") #'counsel-M-x)
+(with-eval-after-load 'org
+  (define-key org-mode-map (kbd "C-c a") 'org-agenda))
+(global-set-key (kbd "C-x C-f") 'find-file) ; trailing comment
+"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 3);
+
+        assert_eq!(keybinds[0].key.modifiers(), [Modifier::Meta]);
+        assert_eq!(keybinds[0].key.first_key(), "x");
+        assert_eq!(keybinds[0].action, "counsel-M-x");
+        assert_eq!(keybinds[0].program, Program::Emacs);
+
+        assert_eq!(keybinds[1].action, "org-agenda");
+        assert_eq!(
+            keybinds[1].description.as_deref(),
+            Some("org-agenda (org-mode-map)")
+        );
+
+        assert_eq!(keybinds[2].keys_string(), "Ctrl+x>Ctrl+f");
+        assert_eq!(keybinds[2].action, "find-file");
+    }
+}
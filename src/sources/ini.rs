@@ -0,0 +1,75 @@
+/// Parses an INI-like config into `(section, key, value)` entries, in file order.
+///
+/// Lines starting with `#` or `;` are comments, `[name]` starts a section, and
+/// `key = value` lines are split at the first `=`, so values may contain `=`.
+/// Keys and values are trimmed; entries before any header have an empty section.
+/// Lines that are none of these are ignored.
+pub fn parse_ini(content: &str) -> Vec<(String, String, String)> {
+    let mut entries = Vec::new();
+    let mut section = String::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            entries.push((
+                section.clone(),
+                key.trim().to_string(),
+                value.trim().to_string(),
+            ));
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(section: &str, key: &str, value: &str) -> (String, String, String) {
+        (section.to_string(), key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_parse_ini_sections_and_comments() {
+        let content = r#"
+top = level
+# A comment
+[global]
+    font = Monospace 8
+; another comment
+[shortcuts]
+close=ctrl+space
+not an entry
+"#;
+
+        assert_eq!(
+            parse_ini(content),
+            vec![
+                entry("", "top", "level"),
+                entry("global", "font", "Monospace 8"),
+                entry("shortcuts", "close", "ctrl+space"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ini_value_containing_equals() {
+        let content = "[urgency_low]\nformat = <b>%s</b> a=b\n";
+
+        assert_eq!(
+            parse_ini(content),
+            vec![entry("urgency_low", "format", "<b>%s</b> a=b")]
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use crate::error::SourceError;
+use crate::keybind::{Keybind, Modifier, Program};
+use crate::source::Source;
+use std::fs;
+use std::path::PathBuf;
+
+/// Source for discovering keybinds from a Kakoune `kakrc`.
+///
+/// # Discovery Method
+///
+/// Mappings are `map [-docstring <text>] <scope> <mode> <key> <keys>` commands,
+/// e.g. `map global user f ':fzf-mode<ret>'`. Each line starting with `map` is
+/// split into words, honouring Kakoune's quoting (`'...'`, `"..."` and `%{...}`),
+/// so `map`s inside a `hook` block are found too. The mode becomes the
+/// description, followed by the docstring if there is one, and the mapped keys
+/// become the action.
+///
+/// Keys are either plain, like `x` or `,`, or in angle-bracket notation, like
+/// `<c-p>`, `<a-x>` or `<c-a-ret>`.
+pub struct KakouneSource {
+    config_path: PathBuf,
+}
+
+impl KakouneSource {
+    pub fn new(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    pub fn from_default_config() -> Result<Self, Box<dyn std::error::Error>> {
+        let config_path = if let Ok(home) = std::env::var("HOME") {
+            PathBuf::from(home).join(".config/kak/kakrc")
+        } else {
+            return Err("HOME environment variable not set".into());
+        };
+
+        Ok(Self::new(config_path))
+    }
+
+    fn parse_config(&self, content: &str) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+        let mut keybinds = Vec::new();
+
+        for line in content.lines() {
+            let words = Self::split_words(line.trim_start());
+            if words.first().map(String::as_str) != Some("map") {
+                continue;
+            }
+
+            // Switches come before the positional arguments
+            let mut docstring = None;
+            let mut args = Vec::new();
+            let mut words = words.into_iter().skip(1);
+            while let Some(word) = words.next() {
+                match word.as_str() {
+                    "-docstring" => docstring = words.next(),
+                    "--" => args.extend(words.by_ref()),
+                    _ => args.push(word),
+                }
+            }
+
+            let [_scope, mode, key, command] = args.as_slice() else {
+                continue;
+            };
+            let Ok((modifiers, key)) = Self::parse_key(key) else {
+                continue;
+            };
+
+            let description = match docstring {
+                Some(docstring) => format!("{}: {}", mode, docstring),
+                None => mode.clone(),
+            };
+            keybinds.push(
+                Keybind::new(Program::Kakoune, key)
+                    .modifiers(modifiers)
+                    .action(command.clone())
+                    .description(description),
+            );
+        }
+
+        Ok(keybinds)
+    }
+
+    /// Splits a command line into words, stopping at a `#` comment.
+    ///
+    /// Quotes are doubled to escape them (`'it''s'`), and `%{...}` (or `%(...)`,
+    /// `%[...]`, `%<...>`) nests its own brackets.
+    fn split_words(line: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut chars = line.chars().peekable();
+
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+            if c == '#' {
+                break;
+            }
+
+            let mut word = String::new();
+            match c {
+                '\'' | '"' => {
+                    chars.next();
+                    while let Some(next) = chars.next() {
+                        if next == c {
+                            if chars.peek() == Some(&c) {
+                                chars.next();
+                            } else {
+                                break;
+                            }
+                        }
+                        word.push(next);
+                    }
+                }
+                '%' => {
+                    chars.next();
+                    let close = match chars.next() {
+                        Some('{') => ('{', '}'),
+                        Some('(') => ('(', ')'),
+                        Some('[') => ('[', ']'),
+                        Some('<') => ('<', '>'),
+                        Some(other) => {
+                            word.push('%');
+                            word.push(other);
+                            word.extend(chars.by_ref().take_while(|c| !c.is_whitespace()));
+                            words.push(word);
+                            continue;
+                        }
+                        None => {
+                            words.push("%".to_string());
+                            break;
+                        }
+                    };
+                    let mut depth = 1;
+                    for next in chars.by_ref() {
+                        if next == close.0 {
+                            depth += 1;
+                        } else if next == close.1 {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        word.push(next);
+                    }
+                }
+                _ => {
+                    while let Some(&next) = chars.peek() {
+                        if next.is_whitespace() {
+                            break;
+                        }
+                        word.push(next);
+                        chars.next();
+                    }
+                }
+            }
+            words.push(word);
+        }
+
+        words
+    }
+
+    /// Parses a key like `x`, `<c-p>` or `<c-a-ret>` into its modifiers and key name.
+    ///
+    /// The escapes for characters that are special in key lists, like `<lt>` and
+    /// `<minus>`, become the characters themselves.
+    fn parse_key(key: &str) -> Result<(Vec<Modifier>, String), Box<dyn std::error::Error>> {
+        let Some(inner) = key.strip_prefix('<').and_then(|k| k.strip_suffix('>')) else {
+            if key.chars().count() != 1 {
+                return Err(format!("Expected a single key, got '{}'", key).into());
+            }
+            return Ok((Vec::new(), key.to_string()));
+        };
+
+        let parts: Vec<&str> = inner.split('-').collect();
+        // `<a-->`/`<c-minus>`: a trailing `-` is the minus key itself
+        let (modifier_parts, name) = match parts.as_slice() {
+            [modifiers @ .., "", ""] => (modifiers, "-"),
+            [modifiers @ .., name] => (modifiers, *name),
+            [] => unreachable!("split yields at least one part"),
+        };
+        if name.is_empty() {
+            return Err(format!("Missing key in '{}'", key).into());
+        }
+
+        let mut modifiers = Vec::new();
+        for part in modifier_parts {
+            let modifier = match *part {
+                "c" => Modifier::Ctrl,
+                "a" => Modifier::Alt,
+                "s" => Modifier::Shift,
+                _ => return Err(format!("Unknown modifier: {}", part).into()),
+            };
+            modifiers.push(modifier);
+        }
+
+        let name = match name {
+            "lt" => "<",
+            "gt" => ">",
+            "minus" => "-",
+            "plus" => "+",
+            "semicolon" => ";",
+            other => other,
+        };
+        Ok((modifiers, name.to_string()))
+    }
+}
+
+impl Source for KakouneSource {
+    type Item = Keybind;
+
+    fn name(&self) -> &str {
+        "kakoune"
+    }
+
+    fn is_available(&self) -> bool {
+        self.config_path.is_file()
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        let content = fs::read_to_string(&self.config_path)
+            .map_err(|e| SourceError::from_io(&self.config_path, e))?;
+        self.parse_config(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ctrl_mapping() {
+        let source = KakouneSource::new(PathBuf::new());
+        let content = "
+# Find files
+map global normal <c-p> ': fzf-mode<ret>' -docstring 'fuzzy finder'
+map -docstring 'select down' global normal <a-J> 'J'
+";
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 2);
+
+        assert_eq!(keybinds[0].key.modifiers(), [Modifier::Ctrl]);
+        assert_eq!(keybinds[0].key.first_key(), "p");
+        assert_eq!(keybinds[0].action, ": fzf-mode<ret>");
+        assert_eq!(
+            keybinds[0].description.as_deref(),
+            Some("normal: fuzzy finder")
+        );
+        assert_eq!(keybinds[0].program, Program::Kakoune);
+
+        assert_eq!(keybinds[1].key.modifiers(), [Modifier::Alt]);
+        assert_eq!(keybinds[1].key.first_key(), "J");
+        assert_eq!(
+            keybinds[1].description.as_deref(),
+            Some("normal: select down")
+        );
+    }
+
+    #[test]
+    fn test_parse_plain_key_mapping() {
+        let source = KakouneSource::new(PathBuf::new());
+        let content = "
+hook global WinSetOption filetype=rust %{
+    map window user f %{:format<ret>}
+}
+map global insert <c-a-minus> <esc>
+";
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 2);
+
+        assert!(keybinds[0].key.modifiers().is_empty());
+        assert_eq!(keybinds[0].key.first_key(), "f");
+        assert_eq!(keybinds[0].action, ":format<ret>");
+        assert_eq!(keybinds[0].description.as_deref(), Some("user"));
+
+        assert_eq!(keybinds[1].key.modifiers(), [Modifier::Ctrl, Modifier::Alt]);
+        assert_eq!(keybinds[1].key.first_key(), "-");
+        assert_eq!(keybinds[1].action, "<esc>");
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(
+            KakouneSource::split_words(r#"map global user q 'it''s' "a ""b""" # comment"#),
+            vec!["map", "global", "user", "q", "it's", r#"a "b""#]
+        );
+        assert_eq!(
+            KakouneSource::split_words("map global user x %{exec %{x}}"),
+            vec!["map", "global", "user", "x", "exec %{x}"]
+        );
+    }
+}
@@ -0,0 +1,576 @@
+use crate::error::SourceError;
+use crate::keybind::{Chord, KeyKind, Keybind, Modifier, Program};
+use crate::source::Source;
+use kdl::{KdlDocument, KdlNode, KdlValue};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A bind property written on a bind node, e.g. `repeat=false`, and the
+/// [`Keybind`] field it sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindProperty {
+    /// A string describing the bind; `null` (or `false`) hides it from overlays.
+    Title,
+    Repeat,
+    CooldownMs,
+    AllowWhenLocked,
+    AllowInhibiting,
+}
+
+/// A problem with a binding in a KDL config, e.g. one that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDiagnostic {
+    /// 1-based line of the binding in the config file.
+    pub line: usize,
+    /// The key combination as written, e.g. `Hyper+T`.
+    pub combo: String,
+    pub message: String,
+    pub severity: Severity,
+}
+
+/// How serious a [`ParseDiagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The bind works, but probably not as intended.
+    Warning,
+    /// The bind is broken; the program would reject it.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {}: {}: {}",
+            self.line, self.severity, self.combo, self.message
+        )
+    }
+}
+
+/// Turns a node of another top-level section into a keybind, if it is one;
+/// see [`KdlKeybindSource::with_section`].
+pub type SectionParser = fn(&KdlKeybindSource, &KdlNode) -> Option<Keybind>;
+
+/// Source for keybinds in a KDL config laid out like niri's:
+///
+/// ```kdl
+/// binds {
+///     Mod+Shift+T repeat=false { spawn "alacritty"; }
+/// }
+/// ```
+///
+/// Every top-level node named after the section holds binds. Each bind is a
+/// `Mod+Key` combination whose properties are mapped to keybind fields with
+/// [`KdlKeybindSource::with_property`], and whose children are its actions,
+/// formatted back as KDL and joined with `, `. Unknown properties are ignored.
+/// Other sections holding keybinds are read with [`KdlKeybindSource::with_section`].
+///
+/// [`NiriSource`](crate::sources::niri::NiriSource) is this source configured
+/// for niri, plus what only niri has (`switch-events`, merged configs, ...).
+pub struct KdlKeybindSource {
+    config_path: PathBuf,
+    program: Program,
+    section: String,
+    properties: Vec<(String, BindProperty)>,
+    sections: Vec<(String, SectionParser)>,
+    describe_action: Option<fn(&str) -> Option<&'static str>>,
+    bare_numeric_args: bool,
+    include_empty_action: bool,
+}
+
+impl KdlKeybindSource {
+    /// Reads binds for `program` from the `section` blocks of `config_path`.
+    pub fn new(config_path: PathBuf, program: Program, section: impl Into<String>) -> Self {
+        Self {
+            config_path,
+            program,
+            section: section.into(),
+            properties: Vec::new(),
+            sections: Vec::new(),
+            describe_action: None,
+            bare_numeric_args: false,
+            include_empty_action: false,
+        }
+    }
+
+    /// Sets `property` from the bind property called `name`.
+    pub fn with_property(mut self, name: impl Into<String>, property: BindProperty) -> Self {
+        self.properties.push((name.into(), property));
+        self
+    }
+
+    /// Reads the children of every top-level `name` node with `parse`, e.g.
+    /// niri's `switch-events`, keeping the keybinds it returns in document order.
+    pub fn with_section(mut self, name: impl Into<String>, parse: SectionParser) -> Self {
+        self.sections.push((name.into(), parse));
+        self
+    }
+
+    /// Describes binds without a [`BindProperty::Title`] by their first action's name.
+    pub fn with_action_descriptions(
+        mut self,
+        describe: Option<fn(&str) -> Option<&'static str>>,
+    ) -> Self {
+        self.describe_action = describe;
+        self
+    }
+
+    /// Write numeric-looking string arguments such as `"+10%"` without quotes.
+    pub fn with_bare_numeric_args(mut self, enabled: bool) -> Self {
+        self.bare_numeric_args = enabled;
+        self
+    }
+
+    /// Keep binds without an action, listing their action as `unknown`.
+    pub fn with_include_empty_action(mut self, enabled: bool) -> Self {
+        self.include_empty_action = enabled;
+        self
+    }
+
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+
+    /// The name of the top-level nodes holding binds, e.g. `binds`.
+    pub fn section(&self) -> &str {
+        &self.section
+    }
+
+    /// Parses a bind combination like `Mod+Shift+T` into a one-step chord.
+    ///
+    /// A modifier named more than once, as in `Mod+Mod+T`, counts once; see
+    /// [`KdlKeybindSource::repeated_modifiers`].
+    pub fn parse_key_combination(combo: &str) -> Result<Chord, Box<dyn std::error::Error>> {
+        let (modifiers, key) = Self::split_combination(combo)?;
+
+        let mut unique = Vec::new();
+        for modifier in modifiers {
+            if !unique.contains(&modifier) {
+                unique.push(modifier);
+            }
+        }
+
+        Ok(Chord::single(unique, key))
+    }
+
+    /// The modifiers `combo` names more than once, e.g. `Mod` in `Mod+Mod+T`
+    /// or `Super` in `Super+Win+T`.
+    pub fn repeated_modifiers(combo: &str) -> Vec<Modifier> {
+        let Ok((modifiers, _)) = Self::split_combination(combo) else {
+            return Vec::new();
+        };
+
+        let mut repeated = Vec::new();
+        for (i, modifier) in modifiers.iter().enumerate() {
+            if modifiers[..i].contains(modifier) && !repeated.contains(modifier) {
+                repeated.push(modifier.clone());
+            }
+        }
+        repeated
+    }
+
+    /// The modifiers a combination names, in order and with any repeats, and its key.
+    fn split_combination(
+        combo: &str,
+    ) -> Result<(Vec<Modifier>, String), Box<dyn std::error::Error>> {
+        // `Mod++` binds the `+` key itself, as kitty writes it; keysym names
+        // like `Mod+plus` need no special handling
+        let (modifier_part, key) = match combo.strip_suffix("++") {
+            Some(modifier_part) => (modifier_part, "+"),
+            None => combo.rsplit_once('+').unwrap_or(("", combo)),
+        };
+        if key.is_empty() {
+            return Err("Missing key".into());
+        }
+
+        let mut modifiers = Vec::new();
+        let parts = modifier_part
+            .split('+')
+            .filter(|_| !modifier_part.is_empty());
+        for part in parts {
+            let modifier = match part {
+                "Mod" => Modifier::Mod,
+                "Super" | "Win" => Modifier::Super,
+                "Alt" => Modifier::Alt,
+                "Ctrl" | "Control" => Modifier::Ctrl,
+                "Shift" => Modifier::Shift,
+                "ISO_Level3_Shift" | "Mod5" => Modifier::IsoLevel3Shift,
+                "ISO_Level5_Shift" | "Mod3" => Modifier::IsoLevel5Shift,
+                _ => return Err(format!("Unknown modifier: {}", part).into()),
+            };
+            modifiers.push(modifier);
+        }
+
+        Ok((modifiers, key.to_string()))
+    }
+
+    /// Whether `node`, inside a section, is a bind rather than a stray node.
+    pub fn is_bind_node(&self, node: &KdlNode) -> bool {
+        // Key names always contain a letter or digit (`comma`, `F1`, ...), so a
+        // name made only of punctuation is a stray node rather than a key
+        if !node.name().value().chars().any(char::is_alphanumeric) {
+            return false;
+        }
+        if Self::has_action(node) {
+            return true;
+        }
+
+        // Without an action, only a recognizable key makes a node a bind whose
+        // action is missing rather than a stray node like `stray`
+        self.include_empty_action
+            && Self::parse_key_combination(node.name().value()).is_ok_and(|key| {
+                Keybind::new(self.program.clone(), key).key_kind() != KeyKind::Unknown
+            })
+    }
+
+    pub fn has_action(node: &KdlNode) -> bool {
+        node.children()
+            .is_some_and(|children| !children.nodes().is_empty())
+    }
+
+    pub fn parse_bind(&self, node: &KdlNode) -> Result<Keybind, Box<dyn std::error::Error>> {
+        let key = Self::parse_key_combination(node.name().value())?;
+        let mut keybind = Keybind::new(self.program.clone(), key);
+
+        for entry in node.entries() {
+            let Some(property) = entry.name().and_then(|name| self.property(name.value())) else {
+                continue;
+            };
+            let value = entry.value();
+            keybind = match property {
+                BindProperty::Title => keybind
+                    .description(value.as_string().map(str::to_string))
+                    // niri hides binds titled `null`; `false` is accepted too
+                    .hidden_in_overlay(value.is_null() || value.as_bool() == Some(false)),
+                BindProperty::Repeat => keybind.repeat(value.as_bool()),
+                BindProperty::CooldownMs => {
+                    keybind.cooldown_ms(value.as_integer().and_then(|v| u64::try_from(v).ok()))
+                }
+                BindProperty::AllowWhenLocked => keybind.allow_when_locked(value.as_bool()),
+                BindProperty::AllowInhibiting => keybind.allow_inhibiting(value.as_bool()),
+            };
+        }
+
+        if keybind.description.is_none()
+            && let Some(first_action) = node.children().and_then(|c| c.nodes().first())
+        {
+            keybind = keybind.description(self.describe_action(first_action.name().value()));
+        }
+
+        let action = if Self::has_action(node) {
+            self.format_actions(node)
+        } else {
+            "unknown".to_string()
+        };
+
+        Ok(self.with_action_parts(keybind.action(action), node))
+    }
+
+    /// Sets the action's name and arguments on `keybind` if `node` has exactly
+    /// one action, e.g. `spawn` and `["alacritty", "-e", "htop"]` for
+    /// `spawn "alacritty" "-e" "htop"`.
+    pub fn with_action_parts(&self, keybind: Keybind, node: &KdlNode) -> Keybind {
+        let Some([action]) = node.children().map(|children| children.nodes()) else {
+            return keybind;
+        };
+        let args = action
+            .entries()
+            .iter()
+            .filter(|entry| entry.name().is_none())
+            .map(|entry| match entry.value().as_string() {
+                Some(s) => s.to_string(),
+                None => self.format_value(entry.value()),
+            })
+            .collect();
+
+        keybind
+            .action_name(action.name().value().to_string())
+            .action_args(args)
+    }
+
+    /// Formats the children of `node` with [`KdlKeybindSource::format_action`],
+    /// joined with `, `.
+    pub fn format_actions(&self, node: &KdlNode) -> String {
+        node.children()
+            .into_iter()
+            .flat_map(|children| children.nodes())
+            .map(|action| self.format_action(action))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn property(&self, name: &str) -> Option<BindProperty> {
+        self.properties
+            .iter()
+            .find(|(property_name, _)| property_name == name)
+            .map(|&(_, property)| property)
+    }
+
+    pub fn describe_action(&self, action_name: &str) -> Option<String> {
+        self.describe_action
+            .and_then(|describe| describe(action_name))
+            .map(str::to_string)
+    }
+
+    /// Formats an action node back as KDL, e.g. `quit skip-confirmation=true`.
+    pub fn format_action(&self, action_node: &KdlNode) -> String {
+        let action_name = action_node.name().to_string();
+
+        // Collect arguments (entries without names) and properties (entries with names)
+        let mut args = Vec::new();
+        let mut props = Vec::new();
+
+        for entry in action_node.entries() {
+            match entry.name() {
+                None => args.push(self.format_value(entry.value())),
+                Some(name) => {
+                    let value = self.format_value(entry.value());
+                    props.push(format!("{}={}", name.value(), value));
+                }
+            }
+        }
+
+        // Build the action string
+        let mut result = action_name;
+
+        if !args.is_empty() {
+            result.push(' ');
+            result.push_str(&args.join(" "));
+        }
+
+        if !props.is_empty() {
+            result.push(' ');
+            result.push_str(&props.join(" "));
+        }
+
+        result
+    }
+
+    fn format_value(&self, value: &KdlValue) -> String {
+        if let Some(s) = value.as_string() {
+            self.format_string(s)
+        } else if let Some(i) = value.as_integer() {
+            i.to_string()
+        } else if let Some(b) = value.as_bool() {
+            b.to_string()
+        } else if let Some(f) = value.as_float() {
+            f.to_string()
+        } else {
+            "null".to_string()
+        }
+    }
+
+    /// Quotes a string argument, unless bare numeric args are enabled and it looks like one.
+    pub fn format_string(&self, s: &str) -> String {
+        if self.bare_numeric_args && Self::is_numeric_like(s) {
+            s.to_string()
+        } else {
+            format!("\"{}\"", s)
+        }
+    }
+
+    /// Whether `s` is a number with an optional sign and percent sign, e.g. `+10%` or `-0.5`.
+    fn is_numeric_like(s: &str) -> bool {
+        let s = s.strip_prefix(['+', '-']).unwrap_or(s);
+        let s = s.strip_suffix('%').unwrap_or(s);
+        s.parse::<f64>().is_ok() && s.chars().all(|c| c.is_ascii_digit() || c == '.')
+    }
+
+    /// Parses the binds of every section in `content`, in document order.
+    ///
+    /// Binds that fail to parse are skipped.
+    pub fn parse_config(&self, content: &str) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+        let (keybinds, _) = self.parse_config_with_diagnostics(content)?;
+        Ok(keybinds)
+    }
+
+    /// Parses the binds of every section in `content`, in document order,
+    /// reporting binds that failed to parse instead of dropping them.
+    ///
+    /// One malformed bind doesn't prevent the rest of the config from being listed.
+    pub fn parse_config_with_diagnostics(
+        &self,
+        content: &str,
+    ) -> Result<(Vec<Keybind>, Vec<ParseDiagnostic>), Box<dyn std::error::Error>> {
+        let doc: KdlDocument = content.parse()?;
+
+        let mut keybinds = Vec::new();
+        let mut diagnostics = Vec::new();
+
+        for node in doc.nodes() {
+            let Some(children) = node.children() else {
+                continue;
+            };
+
+            let name = node.name().value();
+            if name == self.section {
+                for bind_node in children.nodes() {
+                    // A bind is a key combination with at least one action; anything
+                    // else inside the section isn't a keybind and is ignored
+                    if !self.is_bind_node(bind_node) {
+                        continue;
+                    }
+
+                    match self.parse_bind(bind_node) {
+                        Ok(keybind) => keybinds.push(keybind),
+                        Err(e) => diagnostics.push(ParseDiagnostic {
+                            line: Self::line_of(content, bind_node.span().offset()),
+                            combo: bind_node.name().to_string(),
+                            message: e.to_string(),
+                            severity: Severity::Error,
+                        }),
+                    }
+                }
+            } else if let Some(&(_, parse)) =
+                self.sections.iter().find(|(section, _)| section == name)
+            {
+                keybinds.extend(children.nodes().iter().filter_map(|node| parse(self, node)));
+            }
+        }
+
+        Ok((keybinds, diagnostics))
+    }
+
+    /// Validates every bind in `content`.
+    ///
+    /// Binds that don't parse (e.g. an unknown modifier) and binds without an
+    /// action are errors. A chord bound again is a warning on the later bind,
+    /// since it silently overrides the earlier one, as is a modifier named twice
+    /// like in `Mod+Mod+T`. Diagnostics are in document order.
+    pub fn check(&self, content: &str) -> Result<Vec<ParseDiagnostic>, Box<dyn std::error::Error>> {
+        let doc: KdlDocument = content.parse()?;
+
+        let mut diagnostics = Vec::new();
+        let mut bound_on: HashMap<String, usize> = HashMap::new();
+
+        let binds = doc
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == self.section)
+            .filter_map(|node| node.children());
+        for bind_node in binds.flat_map(|children| children.nodes()) {
+            // Names made only of punctuation are stray nodes, as in discovery
+            if !bind_node.name().value().chars().any(char::is_alphanumeric) {
+                continue;
+            }
+
+            let line = Self::line_of(content, bind_node.span().offset());
+            let diagnostic = |severity, message: String| ParseDiagnostic {
+                line,
+                combo: bind_node.name().to_string(),
+                message,
+                severity,
+            };
+
+            if !Self::has_action(bind_node) {
+                diagnostics.push(diagnostic(
+                    Severity::Error,
+                    "bind has no action".to_string(),
+                ));
+                continue;
+            }
+            match self.parse_bind(bind_node) {
+                Ok(keybind) => {
+                    for modifier in Self::repeated_modifiers(bind_node.name().value()) {
+                        diagnostics.push(diagnostic(
+                            Severity::Warning,
+                            format!("{} is repeated", modifier),
+                        ));
+                    }
+                    if let Some(previous) = bound_on.insert(keybind.signature(), line) {
+                        diagnostics.push(diagnostic(
+                            Severity::Warning,
+                            format!("overrides the bind on line {}", previous),
+                        ));
+                    }
+                }
+                Err(e) => diagnostics.push(diagnostic(Severity::Error, e.to_string())),
+            }
+        }
+
+        Ok(diagnostics)
+    }
+
+    fn line_of(content: &str, offset: usize) -> usize {
+        let offset = offset.min(content.len());
+        // Spans may start at the node's leading whitespace, so count from its first token
+        let leading = content[offset..].len() - content[offset..].trim_start().len();
+        content[..offset + leading].matches('\n').count() + 1
+    }
+}
+
+impl Source for KdlKeybindSource {
+    type Item = Keybind;
+
+    fn name(&self) -> &str {
+        self.program.as_str()
+    }
+
+    fn is_available(&self) -> bool {
+        self.config_path.is_file()
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        let content = fs::read_to_string(&self.config_path)
+            .map_err(|e| SourceError::from_io(&self.config_path, e))?;
+        self.parse_config(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_custom_section_and_properties() {
+        let source = KdlKeybindSource::new(PathBuf::new(), Program::Other("tool".into()), "keys")
+            .with_property("title", BindProperty::Title)
+            .with_property("repeat", BindProperty::Repeat);
+        let content = r#"binds {
+    Mod+Q { quit; }
+}
+keys {
+    Ctrl+T title="New Tab" repeat=false cooldown-ms=150 { new-tab; }
+    Alt+Shift+W { close "pane" force=true; }
+}
+"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+
+        assert_eq!(keybinds.len(), 2);
+        assert_eq!(
+            keybinds[0].to_string(),
+            "Ctrl+T - New Tab (no-repeat) [tool]"
+        );
+        assert_eq!(keybinds[0].repeat, Some(false));
+        // Properties that weren't mapped are ignored
+        assert_eq!(keybinds[0].cooldown_ms, None);
+        assert_eq!(
+            keybinds[1].key.modifiers(),
+            [Modifier::Alt, Modifier::Shift]
+        );
+        assert_eq!(keybinds[1].action, "close \"pane\" force=true");
+    }
+
+    #[test]
+    fn test_is_numeric_like() {
+        for s in ["+10%", "-10%", "50%", "0.5", "-1"] {
+            assert!(KdlKeybindSource::is_numeric_like(s), "{}", s);
+        }
+        for s in ["", "+", "%", "10 %", "1.2.3", "inf", "browser"] {
+            assert!(!KdlKeybindSource::is_numeric_like(s), "{}", s);
+        }
+    }
+}
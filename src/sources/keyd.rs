@@ -0,0 +1,168 @@
+use crate::error::SourceError;
+use crate::keybind::{Keybind, Modifier, Program};
+use crate::source::Source;
+use crate::sources::ini;
+use std::fs;
+use std::path::PathBuf;
+
+/// Source for discovering key remaps from a keyd config, e.g. `/etc/keyd/default.conf`.
+///
+/// # Discovery Method
+///
+/// keyd's config is INI-like: each section is a layer, and each `key = action`
+/// line in it remaps `key` while the layer is active (e.g. `capslock =
+/// overload(control, esc)` in `[main]`). The key becomes the keybind and the
+/// right-hand side the action. The description is the layer name followed by
+/// the action, e.g. `main: overload(control, esc)`, so a remap's layer can be
+/// searched for alongside what it does.
+///
+/// The modifier layers `[control]`, `[shift]`, `[meta]`, `[alt]` and `[altgr]`,
+/// and combinations like `[control+alt]`, apply while those modifiers are held,
+/// so their remaps get the modifiers too. Any other layer, like `[nav]` or
+/// `[nav:C]`, is only described by its name. The `[ids]` and `[global]`
+/// sections hold settings rather than remaps and are skipped.
+pub struct KeydSource {
+    config_path: PathBuf,
+}
+
+impl KeydSource {
+    pub fn new(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    pub fn from_default_config() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::new(PathBuf::from("/etc/keyd/default.conf")))
+    }
+
+    fn parse_config(&self, content: &str) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+        let mut keybinds = Vec::new();
+
+        for (section, key, action) in ini::parse_ini(content) {
+            if section.is_empty() || section == "ids" || section == "global" {
+                continue;
+            }
+
+            // `[nav:C]` is the `nav` layer, acting as Ctrl for unmapped keys
+            let layer = section.split(':').next().unwrap_or_default();
+            let modifiers = Self::layer_modifiers(layer).unwrap_or_default();
+
+            keybinds.push(
+                Keybind::new(Program::Keyd, key)
+                    .modifiers(modifiers)
+                    .description(format!("{}: {}", layer, action))
+                    .action(action),
+            );
+        }
+
+        Ok(keybinds)
+    }
+
+    /// The modifiers held while a modifier layer like `control+alt` is active.
+    ///
+    /// Returns `None` for layers that aren't named after modifiers, including `main`.
+    fn layer_modifiers(layer: &str) -> Option<Vec<Modifier>> {
+        layer
+            .split('+')
+            .map(|name| match name.trim() {
+                "control" | "C" => Some(Modifier::Ctrl),
+                "shift" | "S" => Some(Modifier::Shift),
+                "meta" | "M" => Some(Modifier::Super),
+                "alt" | "A" => Some(Modifier::Alt),
+                "altgr" | "G" => Some(Modifier::IsoLevel3Shift),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Source for KeydSource {
+    type Item = Keybind;
+
+    fn name(&self) -> &str {
+        "keyd"
+    }
+
+    fn is_available(&self) -> bool {
+        self.config_path.is_file()
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        let content = fs::read_to_string(&self.config_path)
+            .map_err(|e| SourceError::from_io(&self.config_path, e))?;
+        self.parse_config(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_main_remap() {
+        let source = KeydSource::new(PathBuf::new());
+        let content = "
+[ids]
+*
+
+[main]
+# Caps Lock is Ctrl when held, Esc when tapped
+capslock = overload(control, esc)
+esc = capslock
+";
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 2);
+
+        assert!(keybinds[0].key.modifiers().is_empty());
+        assert_eq!(keybinds[0].key.first_key(), "capslock");
+        assert_eq!(keybinds[0].action, "overload(control, esc)");
+        assert_eq!(
+            keybinds[0].description.as_deref(),
+            Some("main: overload(control, esc)")
+        );
+        assert_eq!(keybinds[0].program, Program::Keyd);
+
+        assert_eq!(keybinds[1].key.first_key(), "esc");
+        assert_eq!(keybinds[1].action, "capslock");
+    }
+
+    #[test]
+    fn test_parse_layers() {
+        let source = KeydSource::new(PathBuf::new());
+        let content = "
+[global]
+overload_tap_timeout = 200
+
+[main]
+space = overload(nav, space)
+
+[nav:C]
+h = left
+
+[control+alt]
+t = command(alacritty)
+
+[meta]
+q = C-w
+";
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 4);
+
+        assert!(keybinds[1].key.modifiers().is_empty());
+        assert_eq!(keybinds[1].key.first_key(), "h");
+        assert_eq!(keybinds[1].action, "left");
+        assert_eq!(keybinds[1].description.as_deref(), Some("nav: left"));
+
+        assert_eq!(keybinds[2].key.modifiers(), [Modifier::Ctrl, Modifier::Alt]);
+        assert_eq!(keybinds[2].action, "command(alacritty)");
+        assert_eq!(
+            keybinds[2].description.as_deref(),
+            Some("control+alt: command(alacritty)")
+        );
+
+        assert_eq!(keybinds[3].key.modifiers(), [Modifier::Super]);
+        assert_eq!(keybinds[3].to_string(), "Super+q - meta: C-w [keyd]");
+    }
+}
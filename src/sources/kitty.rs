@@ -1,6 +1,24 @@
-use crate::keybind::{Keybind, Modifier};
+use crate::cache::Cache;
+use crate::error::SourceError;
+use crate::keybind::{Chord, Keybind, Modifier, Program};
 use crate::source::Source;
 use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Once;
+use std::time::{Duration, SystemTime};
+
+static PYTHON_INIT: Once = Once::new();
+
+/// How often importing kitty's modules is retried by default, see [`KittySource::with_import_retries`].
+pub const DEFAULT_IMPORT_RETRIES: usize = 2;
+
+/// Pause before the first import retry; each further retry waits this much longer.
+const IMPORT_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Joins the actions of a `combine` mapping, e.g. `new_window ; next_layout`.
+pub const COMBINED_ACTION_SEPARATOR: &str = " ; ";
 
 /// Source for discovering keybinds from kitty terminal.
 ///
@@ -24,7 +42,55 @@ use pyo3::prelude::*;
 ///
 /// - Python with kitty installed must be available
 /// - The kitty Python modules must be importable
-pub struct KittySource;
+///
+/// Without them, discovery falls back to reading `map` lines from `kitty.conf`
+/// directly. That only sees the user's own mappings, not kitty's defaults.
+///
+/// # Caching
+///
+/// Starting the Python interpreter is slow, so results can be cached on disk with
+/// [`KittySource::new_with_cache`]. The cache is invalidated when its TTL expires
+/// or when the mtime of `kitty.conf` changes.
+///
+/// # Threading
+///
+/// The embedded interpreter is initialized once per process and shared, so
+/// discovery may be called from any thread. Each call only holds the GIL for the
+/// duration of the traversal. Callers must not invoke discovery while they
+/// themselves hold the GIL and are blocked waiting on another thread that is
+/// also discovering, as that thread can never acquire the GIL.
+///
+/// # Disabled mappings
+///
+/// kitty unbinds a default by mapping it to `no_op` (or `discard_event`). Those
+/// mappings are dropped unless [`KittySource::with_show_disabled`] is set.
+///
+/// # Diffing against defaults
+///
+/// With [`KittySource::with_diff`], each keybind's description is followed by
+/// whether it was `added` by the user, `changed` from a default, or is an
+/// unchanged `default`, e.g. `new_tab (default)`. This loads `kitty.conf` and
+/// kitty's defaults separately to compare them, so it bypasses the cache and the
+/// `kitty.conf` fallback leaves descriptions as they are.
+///
+/// # Combined actions
+///
+/// `map f1 combine : new_window : next_layout` runs several actions in turn.
+/// They're listed as `new_window ; next_layout`, or as one keybind per action
+/// with [`KittySource::with_split_combined`].
+///
+/// # Retries
+///
+/// On slow systems the first import of kitty's modules occasionally fails
+/// transiently, so it's retried [`DEFAULT_IMPORT_RETRIES`] times with a short
+/// backoff. Errors while reading the config aren't retried.
+pub struct KittySource {
+    cache: Option<Cache>,
+    show_disabled: bool,
+    diff: bool,
+    split_combined: bool,
+    import_retries: usize,
+}
 
 impl Default for KittySource {
     fn default() -> Self {
@@ -34,174 +100,518 @@ impl Default for KittySource {
 
 impl KittySource {
     pub fn new() -> Self {
-        Self
+        Self {
+            cache: None,
+            show_disabled: false,
+            diff: false,
+            split_combined: false,
+            import_retries: DEFAULT_IMPORT_RETRIES,
+        }
+    }
+
+    pub fn new_with_cache(cache_path: PathBuf, ttl: Duration) -> Self {
+        Self {
+            cache: Some(Cache::new(cache_path, ttl)),
+            show_disabled: false,
+            diff: false,
+            split_combined: false,
+            import_retries: DEFAULT_IMPORT_RETRIES,
+        }
+    }
+
+    /// Keep mappings that unbind a key (`no_op`, `discard_event`) instead of dropping them.
+    pub fn with_show_disabled(mut self, enabled: bool) -> Self {
+        self.show_disabled = enabled;
+        self
+    }
+
+    /// Describe each keybind as `added`, `changed` or `default` relative to kitty's defaults.
+    pub fn with_diff(mut self, enabled: bool) -> Self {
+        self.diff = enabled;
+        self
+    }
+
+    /// List each action of a `combine` mapping as its own keybind on the same keys.
+    pub fn with_split_combined(mut self, enabled: bool) -> Self {
+        self.split_combined = enabled;
+        self
+    }
+
+    fn split_combined_actions(&self, keybinds: Vec<Keybind>) -> Vec<Keybind> {
+        if !self.split_combined {
+            return keybinds;
+        }
+        keybinds
+            .into_iter()
+            .flat_map(|keybind| {
+                let actions: Vec<String> = keybind
+                    .action
+                    .split(COMBINED_ACTION_SEPARATOR)
+                    .map(str::to_string)
+                    .collect();
+                actions
+                    .into_iter()
+                    .map(move |action| keybind.clone().action(action))
+            })
+            .collect()
+    }
+
+    /// How many more times to try importing kitty's modules after the first attempt fails.
+    pub fn with_import_retries(mut self, retries: usize) -> Self {
+        self.import_retries = retries;
+        self
+    }
+
+    /// Calls `attempt` until it succeeds, at most `retries` more times after the
+    /// first failure, returning the last error if every attempt fails.
+    ///
+    /// `backoff` is called with the retry number (starting at 1) before each retry.
+    fn retry<T, E>(
+        retries: usize,
+        mut attempt: impl FnMut() -> Result<T, E>,
+        mut backoff: impl FnMut(usize),
+    ) -> Result<T, E> {
+        let mut result = attempt();
+        for retry in 1..=retries {
+            if result.is_ok() {
+                break;
+            }
+            backoff(retry);
+            result = attempt();
+        }
+        result
+    }
+
+    fn is_disabled_action(action: &str) -> bool {
+        matches!(
+            action.split_whitespace().next(),
+            Some("no_op" | "no-op" | "discard_event")
+        )
     }
 
-    fn get_keybinds_from_python() -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+    fn retain_enabled(&self, mut keybinds: Vec<Keybind>) -> Vec<Keybind> {
+        if !self.show_disabled {
+            keybinds.retain(|keybind| !Self::is_disabled_action(&keybind.action));
+        }
+        keybinds
+    }
+
+    /// Location of `kitty.conf`, following kitty's own lookup order.
+    pub fn config_path() -> Option<PathBuf> {
+        if let Ok(dir) = std::env::var("KITTY_CONFIG_DIRECTORY") {
+            return Some(PathBuf::from(dir).join("kitty.conf"));
+        }
+        if let Ok(dir) = std::env::var("XDG_CONFIG_HOME")
+            && !dir.is_empty()
+        {
+            return Some(PathBuf::from(dir).join("kitty/kitty.conf"));
+        }
+        std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".config/kitty/kitty.conf"))
+    }
+
+    /// Imports kitty's Python config module, returning why it failed if it did.
+    ///
+    /// Without it, only the `map` lines in `kitty.conf` can be listed.
+    pub fn check_python() -> Result<(), String> {
+        PYTHON_INIT.call_once(pyo3::prepare_freethreaded_python);
+        Python::with_gil(|py| py.import_bound("kitty.config").map(drop)).map_err(|e| e.to_string())
+    }
+
+    fn config_mtime() -> Option<SystemTime> {
+        let path = Self::config_path()?;
+        std::fs::metadata(path).ok()?.modified().ok()
+    }
+
+    fn get_keybinds_from_python(
+        diff: bool,
+        import_retries: usize,
+    ) -> Result<Vec<Keybind>, SourceError> {
+        // Initialize the interpreter for every thread up front, rather than lazily
+        // from whichever thread first asks for the GIL
+        PYTHON_INIT.call_once(pyo3::prepare_freethreaded_python);
+
         Python::with_gil(|py| {
-            // Import kitty modules
-            let kitty_config = py.import_bound("kitty.config").map_err(|e| {
-                format!(
+            // Import kitty modules, releasing the GIL while backing off
+            let import = |name: &str| {
+                Self::retry(
+                    import_retries,
+                    || py.import_bound(name),
+                    |retry| py.allow_threads(|| std::thread::sleep(IMPORT_BACKOFF * retry as u32)),
+                )
+            };
+            let kitty_config = import("kitty.config").map_err(|e| {
+                SourceError::Unavailable(format!(
                     "Failed to import kitty.config. Is kitty installed? Error: {}",
                     e
-                )
+                ))
             })?;
-            let kitty_types = py
-                .import_bound("kitty.types")
-                .map_err(|e| format!("Failed to import kitty.types: {}", e))?;
-
-            // Load kitty configuration
-            let load_config_fn = kitty_config.getattr("load_config")?;
-            let opts = load_config_fn.call0()?;
-
-            // Get kitty_mod value
-            let kitty_mod: i32 = opts.getattr("kitty_mod")?.extract()?;
-
-            // Get the expanded kitty_mod names (e.g., "ctrl+shift")
-            let mod_to_names_fn = kitty_types.getattr("mod_to_names")?;
-            let kitty_mod_names_gen = mod_to_names_fn.call1((kitty_mod,))?;
-            // mod_to_names returns a generator, convert to list
-            let list_builtin = py.eval_bound("list", None, None)?;
-            let kitty_mod_names_list = list_builtin.call1((kitty_mod_names_gen,))?;
-            let kitty_mod_names_vec: Vec<String> = kitty_mod_names_list.extract()?;
-            let kitty_mod_expanded = kitty_mod_names_vec.join("+");
-
-            // Get keyboard_modes
-            let keyboard_modes = opts.getattr("keyboard_modes")?;
-
-            // Get the Shortcut class
-            let shortcut_class = kitty_types.getattr("Shortcut")?;
-
-            let mut keybinds = Vec::new();
-
-            // Iterate through keyboard modes
-            let modes_items = keyboard_modes.call_method0("items")?;
-            for mode_item in modes_items.iter()? {
-                let mode_item = mode_item?;
-
-                // Extract mode object using getitem
-                let mode_obj = mode_item.get_item(1)?;
-
-                // Get the keymap from this mode
-                let keymap = mode_obj.getattr("keymap")?;
-                let keymap_items = keymap.call_method0("items")?;
-
-                // Iterate through keybinds in this mode
-                for item in keymap_items.iter()? {
-                    let item = item?;
-
-                    // Extract key and actions using getitem
-                    let key = item.get_item(0)?;
-                    let actions = item.get_item(1)?;
-
-                    // actions is a list of KeyDefinition objects
-                    // Each action might have a different complete key sequence (for multi-key bindings)
-                    let actions_len: usize = actions.len()?;
-                    if actions_len == 0 {
-                        continue;
-                    }
-
-                    // Process each action separately, as they may have different key sequences
-                    for i in 0..actions_len {
-                        let action = actions.get_item(i)?;
-
-                        // Create Shortcut from the action's key sequence
-                        let is_sequence: bool = action.getattr("is_sequence")?.extract()?;
-
-                        let shortcut = if is_sequence {
-                            // For sequences: Shortcut((trigger,) + rest)
-                            let trigger = action.getattr("trigger")?;
-                            let rest = action.getattr("rest")?;
-
-                            // Use Python to concatenate tuples: (trigger,) + rest
-                            let trigger_tuple = pyo3::types::PyTuple::new_bound(py, vec![trigger]);
-                            let keys_tuple = trigger_tuple.call_method1("__add__", (rest,))?;
-
-                            // Call Shortcut with the tuple
-                            shortcut_class.call1((keys_tuple,))?
-                        } else {
-                            // For non-sequences: Shortcut((key,))
-                            let keys_tuple = pyo3::types::PyTuple::new_bound(py, vec![key.clone()]);
-
-                            // Call Shortcut with the tuple
-                            shortcut_class.call1((keys_tuple,))?
-                        };
-
-                        // Get human-readable key representation
-                        let key_repr: String = shortcut
-                            .call_method1("human_repr", (kitty_mod,))?
-                            .extract()?;
-
-                        // Replace "kitty_mod" with the actual expanded modifiers
-                        let key_repr = key_repr.replace("kitty_mod", &kitty_mod_expanded);
-
-                        // Get action string
-                        let action_str: String = action.call_method0("human_repr")?.extract()?;
-
-                        // Parse the key combination
-                        let (modifiers, key_name) = Self::parse_key_combination(&key_repr)
-                            .map_err(|e| format!("Failed to parse key '{}': {}", key_repr, e))?;
-
-                        keybinds.push(Keybind {
-                            modifiers,
-                            key: key_name,
-                            action: action_str,
-                            description: None,
-                            program: "kitty".to_string(),
-                            repeat: None,
-                            cooldown_ms: None,
-                            allow_when_locked: None,
-                            allow_inhibiting: None,
-                        });
-                    }
-                }
+            let kitty_types = import("kitty.types").map_err(|e| {
+                SourceError::Unavailable(format!("Failed to import kitty.types: {}", e))
+            })?;
+
+            let load_config = |paths: Vec<String>| -> PyResult<Bound<'_, PyAny>> {
+                kitty_config
+                    .getattr("load_config")?
+                    .call1(pyo3::types::PyTuple::new_bound(py, paths))
+            };
+            let read = |paths: Vec<String>| -> Result<Vec<Keybind>, SourceError> {
+                let opts = load_config(paths).map_err(|e| SourceError::Parse(e.to_string()))?;
+                Self::read_keymaps(py, &opts, &kitty_types)
+                    .map_err(|e| SourceError::Parse(e.to_string()))
+            };
+
+            if !diff {
+                return read(Vec::new());
             }
 
+            // Given no paths, load_config only yields kitty's defaults, so the
+            // user's config is passed to compare against them
+            let user_config = Self::config_path()
+                .filter(|path| path.exists())
+                .map(|path| path.to_string_lossy().into_owned());
+            let mut keybinds = read(user_config.into_iter().collect())?;
+            let defaults = read(Vec::new())?;
+            Self::tag_categories(&mut keybinds, &defaults);
             Ok(keybinds)
         })
     }
 
-    fn parse_key_combination(
-        combo: &str,
-    ) -> Result<(Vec<Modifier>, String), Box<dyn std::error::Error>> {
-        // Special case: if combo ends with "++", the key is "+"
-        if let Some(mod_part) = combo.strip_suffix("++") {
-            let mut modifiers = Vec::new();
-            if !mod_part.is_empty() {
-                for part in mod_part.split('+') {
-                    modifiers.push(Self::parse_modifier(part)?);
+    /// Follows each keybind's description, or its action if it has none, with
+    /// how it differs from kitty's `defaults`, e.g. `new_tab (default)`.
+    ///
+    /// A chord mapped in the defaults to the same action is `default`, to another
+    /// action is `changed`, and a chord kitty doesn't map by default is `added`.
+    /// The action stays in the description since listings show it in the action's place.
+    fn tag_categories(keybinds: &mut [Keybind], defaults: &[Keybind]) {
+        let default_actions: HashMap<String, Vec<&str>> =
+            defaults
+                .iter()
+                .fold(HashMap::new(), |mut actions, keybind| {
+                    actions
+                        .entry(keybind.signature())
+                        .or_default()
+                        .push(keybind.action.as_str());
+                    actions
+                });
+
+        for keybind in keybinds {
+            let category = match default_actions.get(&keybind.signature()) {
+                Some(actions) if actions.contains(&keybind.action.as_str()) => "default",
+                Some(_) => "changed",
+                None => "added",
+            };
+            let shown = keybind.description.as_deref().unwrap_or(&keybind.action);
+            keybind.description = Some(format!("{} ({})", shown, category));
+        }
+    }
+
+    /// Reads the user's `map` lines from `kitty.conf`, for when Python is unavailable.
+    fn get_keybinds_from_config() -> Result<Vec<Keybind>, SourceError> {
+        let path = Self::config_path()
+            .ok_or_else(|| SourceError::Unavailable("Can't locate kitty.conf".to_string()))?;
+        let content = fs::read_to_string(&path).map_err(|e| SourceError::from_io(&path, e))?;
+        Ok(Self::parse_config(&content))
+    }
+
+    /// Parses `map <combo> <action>` lines, expanding `kitty_mod` as kitty would.
+    ///
+    /// `include` directives aren't followed, and mappings that can't be parsed are skipped.
+    fn parse_config(content: &str) -> Vec<Keybind> {
+        let lines: Vec<Vec<&str>> = content
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .filter(|words| words.first().is_some_and(|word| !word.starts_with('#')))
+            .collect();
+
+        // kitty_mod applies to every mapping, wherever it's set; the last setting wins
+        let kitty_mod = lines
+            .iter()
+            .rev()
+            .find(|words| words[0] == "kitty_mod" && words.len() > 1)
+            .map(|words| words[1])
+            .unwrap_or("ctrl+shift");
+
+        let mut keybinds = Vec::new();
+        for words in &lines {
+            if words[0] != "map" {
+                continue;
+            }
+
+            // Skip options such as `--when-focus-on title:vim` or `--mode=resize`,
+            // keeping the focus condition and the mode
+            let mut rest = &words[1..];
+            let mut mode = String::new();
+            let mut when_focus_on = String::new();
+            while let Some(option) = rest.first().filter(|word| word.starts_with("--")) {
+                let (name, value, skip) = match option.split_once('=') {
+                    Some((name, value)) => (name, Some(value), 1),
+                    None => (*option, rest.get(1).copied(), 2),
+                };
+                match name {
+                    "--mode" => mode = value.unwrap_or_default().to_string(),
+                    "--when-focus-on" => when_focus_on = value.unwrap_or_default().to_string(),
+                    _ => {}
                 }
+                rest = rest.get(skip..).unwrap_or_default();
             }
-            return Ok((modifiers, "+".to_string()));
+
+            let Some((combo, action)) = rest.split_first() else {
+                continue;
+            };
+            if action.is_empty() {
+                continue;
+            }
+
+            let combo = combo.replace("kitty_mod", kitty_mod);
+            let Ok(key) = Self::parse_key_combination(&combo) else {
+                continue;
+            };
+
+            let action = Self::normalize_action(&action.join(" "));
+            keybinds.push(
+                Keybind::new(Program::Kitty, key)
+                    .description(Self::conditional_description(
+                        &action,
+                        &mode,
+                        &when_focus_on,
+                    ))
+                    .action(action),
+            );
         }
 
-        // Handle multi-key sequences (e.g., "ctrl+f>2")
-        if combo.contains('>') {
-            // Multi-key sequence: split on '+' before the '>'
-            let sequence_parts: Vec<&str> = combo.split('>').collect();
-            let first_part = sequence_parts[0];
-            let rest = sequence_parts[1..].join(">");
+        keybinds
+    }
+
+    /// Describes a mapping that only applies in a keyboard `--mode` or
+    /// `--when-focus-on` some windows, e.g. `neighboring_window left (in
+    /// resize mode, when focus on title:vim)`.
+    ///
+    /// The action comes first since listings show the description in its place.
+    /// Mappings in the default mode, named `""`, that apply everywhere have no
+    /// description.
+    fn conditional_description(action: &str, mode: &str, when_focus_on: &str) -> Option<String> {
+        let mut conditions = Vec::new();
+        if !mode.is_empty() {
+            conditions.push(format!("in {} mode", mode));
+        }
+        if !when_focus_on.is_empty() {
+            conditions.push(format!("when focus on {}", when_focus_on));
+        }
+        (!conditions.is_empty()).then(|| format!("{} ({})", action, conditions.join(", ")))
+    }
+
+    fn read_keymaps(
+        py: Python<'_>,
+        opts: &Bound<'_, PyAny>,
+        kitty_types: &Bound<'_, PyModule>,
+    ) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+        // Get kitty_mod value
+        let kitty_mod: i32 = opts.getattr("kitty_mod")?.extract()?;
+
+        // Get the expanded kitty_mod names (e.g., "ctrl+shift")
+        let mod_to_names_fn = kitty_types.getattr("mod_to_names")?;
+        let kitty_mod_names_gen = mod_to_names_fn.call1((kitty_mod,))?;
+        // mod_to_names returns a generator, convert to list
+        let list_builtin = py.eval_bound("list", None, None)?;
+        let kitty_mod_names_list = list_builtin.call1((kitty_mod_names_gen,))?;
+        let kitty_mod_names_vec: Vec<String> = kitty_mod_names_list.extract()?;
+        let kitty_mod_expanded = kitty_mod_names_vec.join("+");
+
+        // Get keyboard_modes
+        let keyboard_modes = opts.getattr("keyboard_modes")?;
+
+        // Get the Shortcut class
+        let shortcut_class = kitty_types.getattr("Shortcut")?;
+
+        let mut keybinds = Vec::new();
+
+        // Iterate through keyboard modes
+        let modes_items = keyboard_modes.call_method0("items")?;
+        for mode_item in modes_items.iter()? {
+            let mode_item = mode_item?;
+
+            // Extract mode name and object using getitem; the default mode is named ""
+            let mode: String = mode_item.get_item(0)?.extract()?;
+            let mode_obj = mode_item.get_item(1)?;
+
+            // Get the keymap from this mode
+            let keymap = mode_obj.getattr("keymap")?;
+            let keymap_items = keymap.call_method0("items")?;
+
+            // Iterate through keybinds in this mode
+            for item in keymap_items.iter()? {
+                let item = item?;
+
+                // Extract key and actions using getitem
+                let key = item.get_item(0)?;
+                let actions = item.get_item(1)?;
+
+                // actions is a list of KeyDefinition objects
+                // Each action might have a different complete key sequence (for multi-key bindings)
+                let actions_len: usize = actions.len()?;
+                if actions_len == 0 {
+                    continue;
+                }
 
-            let parts: Vec<&str> = first_part.split('+').collect();
-            if parts.is_empty() {
-                return Err("Empty key combination".into());
+                // Process each action separately, as they may have different key sequences
+                for i in 0..actions_len {
+                    let action = actions.get_item(i)?;
+
+                    // Create Shortcut from the action's key sequence
+                    let is_sequence: bool = action.getattr("is_sequence")?.extract()?;
+
+                    let shortcut = if is_sequence {
+                        // For sequences: Shortcut((trigger,) + rest)
+                        let trigger = action.getattr("trigger")?;
+                        let rest = action.getattr("rest")?;
+
+                        // Use Python to concatenate tuples: (trigger,) + rest
+                        let trigger_tuple = pyo3::types::PyTuple::new_bound(py, vec![trigger]);
+                        let keys_tuple = trigger_tuple.call_method1("__add__", (rest,))?;
+
+                        // Call Shortcut with the tuple
+                        shortcut_class.call1((keys_tuple,))?
+                    } else {
+                        // For non-sequences: Shortcut((key,))
+                        let keys_tuple = pyo3::types::PyTuple::new_bound(py, vec![key.clone()]);
+
+                        // Call Shortcut with the tuple
+                        shortcut_class.call1((keys_tuple,))?
+                    };
+
+                    // Get human-readable key representation
+                    let key_repr: String = shortcut
+                        .call_method1("human_repr", (kitty_mod,))?
+                        .extract()?;
+
+                    // Replace "kitty_mod" with the actual expanded modifiers
+                    let key_repr = key_repr.replace("kitty_mod", &kitty_mod_expanded);
+
+                    // Get action string
+                    let action_str: String = action.call_method0("human_repr")?.extract()?;
+                    let action_str = Self::normalize_action(&action_str);
+
+                    // Older kitty versions have no map options
+                    let when_focus_on: String = action
+                        .getattr("options")
+                        .and_then(|options| options.getattr("when_focus_on"))
+                        .and_then(|condition| condition.extract())
+                        .unwrap_or_default();
+
+                    // Parse the key combination
+                    let key = Self::parse_key_combination(&key_repr)
+                        .map_err(|e| format!("Failed to parse key '{}': {}", key_repr, e))?;
+
+                    keybinds.push((
+                        mode.clone(),
+                        Keybind::new(Program::Kitty, key)
+                            .description(Self::conditional_description(
+                                &action_str,
+                                &mode,
+                                &when_focus_on,
+                            ))
+                            .action(action_str),
+                    ));
+                }
             }
+        }
 
-            let mut modifiers = Vec::new();
+        Ok(Self::sort_by_mode(keybinds))
+    }
+
+    /// Orders keybinds by mode, then as [`Keybind`]s sort (chord, then action).
+    ///
+    /// kitty's keymaps are dicts whose order can change between runs; a stable
+    /// order keeps the cache and diffs of the output from churning.
+    fn sort_by_mode(mut keybinds: Vec<(String, Keybind)>) -> Vec<Keybind> {
+        keybinds.sort();
+        keybinds.into_iter().map(|(_, keybind)| keybind).collect()
+    }
+
+    fn discover_all_mappings(&self) -> Result<Vec<Keybind>, SourceError> {
+        let config_mtime = Self::config_mtime();
+        let now = SystemTime::now();
+
+        // Cached keybinds don't carry the categories, so diffing always asks Python
+        let cache = self.cache.as_ref().filter(|_| !self.diff);
+
+        if let Some(cache) = cache
+            && let Some(keybinds) = cache.load(config_mtime, now)
+        {
+            return Ok(keybinds);
+        }
 
-            // All parts before the last are modifiers
-            for part in &parts[..parts.len() - 1] {
-                modifiers.push(Self::parse_modifier(part)?);
+        match Self::get_keybinds_from_python(self.diff, self.import_retries) {
+            Ok(keybinds) => {
+                if let Some(cache) = cache {
+                    // A cache that can't be written only costs speed on the next run
+                    let _ = cache.store(&keybinds, config_mtime, now);
+                }
+                Ok(keybinds)
+            }
+            // Reading kitty.conf is cheap, and only partial, so its results aren't cached
+            Err(SourceError::Unavailable(reason)) => {
+                Self::get_keybinds_from_config().map_err(|_| SourceError::Unavailable(reason))
             }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Collapses an action onto one line with single spaces, and lists the
+    /// actions of a `combine` joined with [`COMBINED_ACTION_SEPARATOR`].
+    ///
+    /// `human_repr` includes the literal text of actions like `send_text`,
+    /// which may span several lines and would otherwise break line-based output.
+    fn normalize_action(action: &str) -> String {
+        let action = action.split_whitespace().collect::<Vec<_>>().join(" ");
+        match Self::combined_actions(&action) {
+            Some(actions) => actions.join(COMBINED_ACTION_SEPARATOR),
+            None => action,
+        }
+    }
 
-            // Last part of first sequence + the rest forms the key
-            let key = format!("{}{}{}", parts[parts.len() - 1], ">", rest);
+    /// The actions of `combine <sep> a <sep> b`, where `<sep>` is any word, usually `:`.
+    fn combined_actions(action: &str) -> Option<Vec<&str>> {
+        let (separator, rest) = action.strip_prefix("combine ")?.split_once(' ')?;
+        let actions: Vec<&str> = rest
+            .split(separator)
+            .map(str::trim)
+            .filter(|action| !action.is_empty())
+            .collect();
+        (!actions.is_empty()).then_some(actions)
+    }
 
-            return Ok((modifiers, key));
+    fn parse_key_combination(combo: &str) -> Result<Chord, Box<dyn std::error::Error>> {
+        // Multi-key sequences join their steps with '>' (e.g., "ctrl+f>2"),
+        // unless the key itself is '>' (e.g., "ctrl+>")
+        let steps: Vec<&str> = combo.split('>').collect();
+        if steps.iter().any(|step| step.is_empty()) {
+            return Ok(Chord(vec![Self::parse_key_step(combo)?]));
+        }
+
+        let steps = steps
+            .into_iter()
+            .map(Self::parse_key_step)
+            .collect::<Result<_, _>>()?;
+        Ok(Chord(steps))
+    }
+
+    fn parse_key_step(step: &str) -> Result<(Vec<Modifier>, String), Box<dyn std::error::Error>> {
+        // Special case: if the step ends with "++", the key is "+"
+        if let Some(mod_part) = step.strip_suffix("++") {
+            let mut modifiers = Vec::new();
+            if !mod_part.is_empty() {
+                for part in mod_part.split('+') {
+                    modifiers.push(Self::parse_modifier(part)?);
+                }
+            }
+            return Ok((modifiers, "+".to_string()));
         }
 
         // Normal key combination (e.g., "ctrl+shift+c")
-        let parts: Vec<&str> = combo.split('+').collect();
+        let parts: Vec<&str> = step.split('+').collect();
 
         if parts.is_empty() {
             return Err("Empty key combination".into());
@@ -224,6 +634,8 @@ impl KittySource {
             "shift" => Ok(Modifier::Shift),
             "alt" | "opt" | "option" => Ok(Modifier::Alt),
             "super" | "cmd" | "command" => Ok(Modifier::Super),
+            "hyper" => Ok(Modifier::Hyper),
+            "meta" => Ok(Modifier::Meta),
             "kitty_mod" => Ok(Modifier::Mod), // kitty_mod is a configurable modifier
             _ => Err(format!("Unknown modifier: {}", name).into()),
         }
@@ -237,8 +649,14 @@ impl Source for KittySource {
         "kitty"
     }
 
-    fn discover(&self) -> Result<Vec<Self::Item>, Box<dyn std::error::Error>> {
-        Self::get_keybinds_from_python()
+    fn is_available(&self) -> bool {
+        Self::check_python().is_ok() || Self::config_path().is_some_and(|path| path.is_file())
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        // The cache keeps disabled mappings, so the filter applies on the way out
+        self.discover_all_mappings()
+            .map(|keybinds| self.split_combined_actions(self.retain_enabled(keybinds)))
     }
 }
 
@@ -246,38 +664,317 @@ impl Source for KittySource {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_retry_succeeds_after_one_failure() {
+        let mut attempts = 0;
+        let mut backoffs = Vec::new();
+
+        let result = KittySource::retry(
+            DEFAULT_IMPORT_RETRIES,
+            || {
+                attempts += 1;
+                if attempts == 1 {
+                    Err("not yet")
+                } else {
+                    Ok("kitty.config")
+                }
+            },
+            |retry| backoffs.push(retry),
+        );
+
+        assert_eq!(result, Ok("kitty.config"));
+        assert_eq!(attempts, 2);
+        assert_eq!(backoffs, vec![1]);
+    }
+
+    #[test]
+    fn test_retry_returns_last_error() {
+        let mut attempts = 0;
+
+        let result: Result<(), _> = KittySource::retry(
+            2,
+            || {
+                attempts += 1;
+                Err(attempts)
+            },
+            |_| {},
+        );
+
+        assert_eq!(result, Err(3));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_normalize_send_text_action() {
+        let action = "send_text all  Hello,\n  world!\r\n\tBye";
+
+        assert_eq!(
+            KittySource::normalize_action(action),
+            "send_text all Hello, world! Bye"
+        );
+    }
+
+    #[test]
+    fn test_normalize_combine_action() {
+        assert_eq!(
+            KittySource::normalize_action("combine : new_window : next_layout"),
+            "new_window ; next_layout"
+        );
+        assert_eq!(
+            KittySource::normalize_action(
+                "combine | launch --cwd=current vim |  goto_layout stack"
+            ),
+            "launch --cwd=current vim ; goto_layout stack"
+        );
+        // Only a leading `combine` is special
+        assert_eq!(
+            KittySource::normalize_action("send_text all combine : x"),
+            "send_text all combine : x"
+        );
+    }
+
+    #[test]
+    fn test_split_combined() {
+        let keybinds = KittySource::parse_config("map f1 combine : new_window : next_layout\n");
+        assert_eq!(keybinds[0].action, "new_window ; next_layout");
+
+        let split = KittySource::new()
+            .with_split_combined(true)
+            .split_combined_actions(keybinds);
+        let actions: Vec<(String, &str)> = split
+            .iter()
+            .map(|kb| (kb.keys_string(), kb.action.as_str()))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("f1".to_string(), "new_window"),
+                ("f1".to_string(), "next_layout")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_mode_is_deterministic() {
+        let keybinds = KittySource::parse_config(
+            "map ctrl+shift+t new_tab\n\
+             map ctrl+shift+enter new_window\n\
+             map ctrl+a paste\n\
+             map ctrl+shift+t new_tab_with_cwd\n",
+        );
+        let in_mode = |mode: &str, keybinds: &[Keybind]| {
+            keybinds
+                .iter()
+                .map(|keybind| (mode.to_string(), keybind.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        // The same keymaps, iterated in two different orders
+        let mut first = in_mode("resize", &keybinds);
+        first.extend(in_mode("", &keybinds));
+        let mut second = in_mode("", &keybinds);
+        second.extend(in_mode("resize", &keybinds).into_iter().rev());
+
+        let first = KittySource::sort_by_mode(first);
+        let second = KittySource::sort_by_mode(second);
+        assert_eq!(first, second);
+
+        let order: Vec<String> = first[..4].iter().map(|kb| kb.to_string()).collect();
+        assert_eq!(
+            order,
+            vec![
+                "Ctrl+a - paste [kitty]",
+                "Ctrl+Shift+enter - new_window [kitty]",
+                "Ctrl+Shift+t - new_tab [kitty]",
+                "Ctrl+Shift+t - new_tab_with_cwd [kitty]",
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_key_combination() {
-        let (mods, key) = KittySource::parse_key_combination("ctrl+shift+t").unwrap();
-        assert_eq!(mods, vec![Modifier::Ctrl, Modifier::Shift]);
-        assert_eq!(key, "t");
+        let chord = KittySource::parse_key_combination("ctrl+shift+t").unwrap();
+        assert_eq!(
+            chord,
+            Chord::single(vec![Modifier::Ctrl, Modifier::Shift], "t")
+        );
     }
 
     #[test]
     fn test_parse_key_combination_no_modifiers() {
-        let (mods, key) = KittySource::parse_key_combination("f1").unwrap();
-        assert_eq!(mods, vec![]);
-        assert_eq!(key, "f1");
+        let chord = KittySource::parse_key_combination("f1").unwrap();
+        assert_eq!(chord, Chord::single(vec![], "f1"));
     }
 
     #[test]
     fn test_parse_multi_key_sequence() {
-        let (mods, key) = KittySource::parse_key_combination("ctrl+f>2").unwrap();
-        assert_eq!(mods, vec![Modifier::Ctrl]);
-        assert_eq!(key, "f>2");
+        let chord = KittySource::parse_key_combination("ctrl+f>2").unwrap();
+        assert!(chord.is_sequence());
+        assert_eq!(
+            chord.steps(),
+            [
+                (vec![Modifier::Ctrl], "f".to_string()),
+                (vec![], "2".to_string()),
+            ]
+        );
+        assert_eq!(chord.to_string(), "Ctrl+f>2");
+    }
+
+    #[test]
+    fn test_parse_sequence_with_modifiers_in_each_step() {
+        let chord = KittySource::parse_key_combination("ctrl+x>ctrl+shift+y").unwrap();
+        assert_eq!(chord.steps().len(), 2);
+        assert_eq!(chord.to_string(), "Ctrl+x>Ctrl+Shift+y");
+    }
+
+    #[test]
+    fn test_parse_greater_than_key() {
+        let chord = KittySource::parse_key_combination("ctrl+>").unwrap();
+        assert_eq!(chord, Chord::single(vec![Modifier::Ctrl], ">"));
     }
 
     #[test]
     fn test_parse_plus_key() {
-        let (mods, key) = KittySource::parse_key_combination("ctrl+shift++").unwrap();
-        assert_eq!(mods, vec![Modifier::Ctrl, Modifier::Shift]);
-        assert_eq!(key, "+");
+        let chord = KittySource::parse_key_combination("ctrl+shift++").unwrap();
+        assert_eq!(
+            chord,
+            Chord::single(vec![Modifier::Ctrl, Modifier::Shift], "+")
+        );
     }
 
     #[test]
     fn test_parse_kitty_mod() {
-        let (mods, key) = KittySource::parse_key_combination("kitty_mod+c").unwrap();
-        assert_eq!(mods, vec![Modifier::Mod]);
-        assert_eq!(key, "c");
+        let chord = KittySource::parse_key_combination("kitty_mod+c").unwrap();
+        assert_eq!(chord, Chord::single(vec![Modifier::Mod], "c"));
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let content = "
+# Sample kitty.conf
+font_size 11.0
+kitty_mod ctrl+alt
+
+map kitty_mod+t new_tab_with_cwd
+map ctrl+shift+enter launch --cwd=current --type=window
+map --when-focus-on title:vim ctrl+h neighboring_window left
+map ctrl+f>2 set_font_size 20
+#map ctrl+q quit
+map bogus+x noop_never_parsed
+";
+
+        let keybinds = KittySource::parse_config(content);
+
+        assert_eq!(keybinds.len(), 4);
+        assert_eq!(keybinds[0].key.modifiers(), [Modifier::Ctrl, Modifier::Alt]);
+        assert_eq!(keybinds[0].key.first_key(), "t");
+        assert_eq!(keybinds[0].action, "new_tab_with_cwd");
+        assert_eq!(keybinds[1].key.first_key(), "enter");
+        assert_eq!(keybinds[1].action, "launch --cwd=current --type=window");
+        assert_eq!(keybinds[2].keys_string(), "Ctrl+h");
+        assert_eq!(keybinds[2].action, "neighboring_window left");
+        assert_eq!(
+            keybinds[2].description.as_deref(),
+            Some("neighboring_window left (when focus on title:vim)")
+        );
+        assert_eq!(keybinds[3].keys_string(), "Ctrl+f>2");
+        assert_eq!(keybinds[3].description, None);
+        assert!(keybinds.iter().all(|kb| kb.program == Program::Kitty));
+    }
+
+    #[test]
+    fn test_parse_config_default_kitty_mod() {
+        let keybinds = KittySource::parse_config("map kitty_mod+c copy_to_clipboard\n");
+
+        assert_eq!(
+            keybinds[0].key.modifiers(),
+            [Modifier::Ctrl, Modifier::Shift]
+        );
+    }
+
+    #[test]
+    fn test_no_op_excluded_by_default() {
+        let keybinds = KittySource::parse_config(
+            "map ctrl+shift+t no_op\nmap ctrl+shift+w discard_event\nmap ctrl+shift+n new_os_window\n",
+        );
+        assert_eq!(keybinds.len(), 3);
+
+        let enabled = KittySource::new().retain_enabled(keybinds.clone());
+        assert_eq!(enabled.len(), 1);
+        assert_eq!(enabled[0].action, "new_os_window");
+
+        let all = KittySource::new()
+            .with_show_disabled(true)
+            .retain_enabled(keybinds);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].action, "no_op");
+    }
+
+    #[test]
+    fn test_when_focus_on_condition() {
+        let keybinds = KittySource::parse_config(
+            "map --when-focus-on=var:in_editor --mode=resize ctrl+j neighboring_window down\n",
+        );
+
+        assert_eq!(keybinds.len(), 1);
+        assert_eq!(
+            keybinds[0].to_string(),
+            "Ctrl+j - neighboring_window down (in resize mode, when focus on var:in_editor) [kitty]"
+        );
+
+        let mut tagged = keybinds.clone();
+        KittySource::tag_categories(&mut tagged, &[]);
+        assert_eq!(
+            tagged[0].description.as_deref(),
+            Some("neighboring_window down (in resize mode, when focus on var:in_editor) (added)")
+        );
+    }
+
+    #[test]
+    fn test_mode_name_captured() {
+        let keybinds = KittySource::parse_config(
+            "map --mode resize w resize_window narrower
+map ctrl+shift+t new_tab
+",
+        );
+
+        assert_eq!(
+            keybinds[0].to_string(),
+            "w - resize_window narrower (in resize mode) [kitty]"
+        );
+        // The default mode isn't mentioned
+        assert_eq!(keybinds[1].description, None);
+        assert_eq!(
+            KittySource::conditional_description("new_tab", "", ""),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tag_categories() {
+        let defaults =
+            KittySource::parse_config("map ctrl+shift+t new_tab\nmap ctrl+shift+w close_window\n");
+        let mut keybinds = KittySource::parse_config(
+            "map ctrl+shift+t new_tab\nmap ctrl+shift+w close_tab\nmap ctrl+shift+n new_os_window\n",
+        );
+
+        KittySource::tag_categories(&mut keybinds, &defaults);
+
+        let categories: Vec<Option<&str>> = keybinds
+            .iter()
+            .map(|keybind| keybind.description.as_deref())
+            .collect();
+        assert_eq!(
+            categories,
+            vec![
+                Some("new_tab (default)"),
+                Some("close_tab (changed)"),
+                Some("new_os_window (added)")
+            ]
+        );
+        assert_eq!(
+            keybinds[0].to_string(),
+            "Ctrl+Shift+t - new_tab (default) [kitty]"
+        );
     }
 }
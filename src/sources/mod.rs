@@ -1,2 +1,14 @@
+pub mod awesome;
+pub mod command;
+pub mod dunst;
+pub mod dwm;
+pub mod emacs;
+pub mod ini;
+pub mod kak;
+pub mod kdl_binds;
+pub mod keyd;
 pub mod kitty;
 pub mod niri;
+pub mod niri_actions;
+pub mod qtile;
+pub mod wt;
@@ -1,197 +1,386 @@
-use crate::keybind::{Keybind, Modifier};
+use crate::error::SourceError;
+use crate::keybind::{Keybind, Program};
 use crate::source::Source;
-use kdl::KdlDocument;
+use crate::sources::kdl_binds::{BindProperty, KdlKeybindSource};
+pub use crate::sources::kdl_binds::{ParseDiagnostic, Severity};
+use crate::sources::niri_actions;
+use kdl::KdlNode;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+/// Source for discovering keybinds from niri's KDL config file.
+///
+/// The config is parsed by a [`KdlKeybindSource`] configured with niri's bind
+/// properties, which also reads `switch-events` as keybinds.
 pub struct NiriSource {
-    config_path: PathBuf,
+    binds: KdlKeybindSource,
+    /// Further configs merged after the main one, see [`NiriSource::with_extra_configs`].
+    extra_config_paths: Vec<PathBuf>,
+    /// What the last discovery noted, see [`NiriSource::shadowing_notes`].
+    shadowing_notes: RefCell<Vec<String>>,
+    mtime: bool,
 }
 
 impl NiriSource {
     pub fn new(config_path: PathBuf) -> Self {
-        Self { config_path }
+        let binds = KdlKeybindSource::new(config_path, Program::Niri, "binds")
+            .with_property("hotkey-overlay-title", BindProperty::Title)
+            .with_property("repeat", BindProperty::Repeat)
+            .with_property("cooldown-ms", BindProperty::CooldownMs)
+            .with_property("allow-when-locked", BindProperty::AllowWhenLocked)
+            .with_property("allow-inhibiting", BindProperty::AllowInhibiting)
+            .with_section("switch-events", Self::parse_switch_node);
+
+        Self {
+            binds,
+            extra_config_paths: Vec::new(),
+            shadowing_notes: RefCell::default(),
+            mtime: false,
+        }
+    }
+
+    /// Reads `paths` after the main config, as if they were appended to it.
+    ///
+    /// This is for binds split across files that don't `include` each other.
+    /// Their binds are listed in order, so a chord bound again in a later file
+    /// shadows the earlier bind, as it would within one file; see
+    /// [`NiriSource::shadowing_notes`].
+    pub fn with_extra_configs(mut self, paths: Vec<PathBuf>) -> Self {
+        self.extra_config_paths = paths;
+        self
+    }
+
+    /// Note on each bind when its config file was last modified, see [`Keybind::config_mtime`].
+    pub fn with_mtime(mut self, enabled: bool) -> Self {
+        self.mtime = enabled;
+        self
+    }
+
+    /// Describe binds without a `hotkey-overlay-title` using a table of known niri actions.
+    pub fn with_action_descriptions(mut self, enabled: bool) -> Self {
+        self.binds = self
+            .binds
+            .with_action_descriptions(enabled.then_some(niri_actions::describe as _));
+        self
+    }
+
+    /// Write numeric-looking string arguments such as `"+10%"` without quotes,
+    /// e.g. `set-column-width +10%`; other strings stay quoted.
+    ///
+    /// The result is easier to read but no longer valid KDL, so it's off by default.
+    pub fn with_bare_numeric_args(mut self, enabled: bool) -> Self {
+        self.binds = self.binds.with_bare_numeric_args(enabled);
+        self
     }
 
+    /// Keep binds without an action, e.g. `Mod+T { }`, listing their action as `unknown`.
+    ///
+    /// niri rejects such binds, so they're usually config errors and are dropped by default.
+    pub fn with_include_empty_action(mut self, enabled: bool) -> Self {
+        self.binds = self.binds.with_include_empty_action(enabled);
+        self
+    }
+
+    /// Finds the config to read when no path is given, in order of precedence:
+    ///
+    /// 1. `$FZF_KEYS_NIRI_CONFIG`, used as is even if it doesn't exist
+    /// 2. the first existing config of `$XDG_CONFIG_HOME/niri/config.kdl`,
+    ///    `$HOME/.config/niri/config.kdl` and `/etc/niri/config.kdl`
+    ///
+    /// An explicit `--niri-config` (or `niri-config` in the config file) bypasses
+    /// this lookup, so it takes precedence over all of these.
     pub fn from_default_config() -> Result<Self, Box<dyn std::error::Error>> {
-        let config_path = if let Ok(home) = std::env::var("HOME") {
-            PathBuf::from(home).join(".config/niri/config.kdl")
-        } else {
-            return Err("HOME environment variable not set".into());
-        };
+        if let Some(config_path) = Self::config_path_override() {
+            return Ok(Self::new(config_path));
+        }
+
+        let candidates = Self::default_config_candidates(
+            std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+            std::env::var_os("HOME").map(PathBuf::from),
+        );
 
-        Ok(Self::new(config_path))
+        Ok(Self::new(Self::first_existing(&candidates)?))
     }
 
-    fn format_action(action_node: &kdl::KdlNode) -> String {
-        let action_name = action_node.name().to_string();
+    /// `$FZF_KEYS_NIRI_CONFIG`, for setups where passing `--niri-config` is awkward.
+    fn config_path_override() -> Option<PathBuf> {
+        std::env::var_os("FZF_KEYS_NIRI_CONFIG")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    }
 
-        // Collect arguments (entries without names) and properties (entries with names)
-        let mut args = Vec::new();
-        let mut props = Vec::new();
+    fn default_config_candidates(
+        xdg_config_home: Option<PathBuf>,
+        home: Option<PathBuf>,
+    ) -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
 
-        for entry in action_node.entries() {
-            if entry.name().is_none() {
-                // This is an argument
-                args.push(Self::format_value(entry.value()));
-            } else {
-                // This is a property (like skip-confirmation=true)
-                if let Some(name) = entry.name() {
-                    let value = Self::format_value(entry.value());
-                    props.push(format!("{}={}", name.value(), value));
-                }
+        // An empty XDG_CONFIG_HOME is treated as unset, per the spec
+        if let Some(config_home) = xdg_config_home.filter(|dir| !dir.as_os_str().is_empty()) {
+            candidates.push(config_home.join("niri/config.kdl"));
+        }
+        if let Some(home) = home {
+            let path = home.join(".config/niri/config.kdl");
+            if !candidates.contains(&path) {
+                candidates.push(path);
             }
         }
+        candidates.push(PathBuf::from("/etc/niri/config.kdl"));
 
-        // Build the action string
-        let mut result = action_name;
+        candidates
+    }
 
-        if !args.is_empty() {
-            result.push(' ');
-            result.push_str(&args.join(" "));
-        }
+    fn first_existing(candidates: &[PathBuf]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        candidates
+            .iter()
+            .find(|path| path.is_file())
+            .cloned()
+            .ok_or_else(|| {
+                let checked: Vec<String> = candidates
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                format!("No niri config found (checked {})", checked.join(", ")).into()
+            })
+    }
 
-        if !props.is_empty() {
-            result.push(' ');
-            result.push_str(&props.join(" "));
+    /// Shows how a niri keybind's `spawn` or `spawn-sh` action runs its command.
+    ///
+    /// `spawn "alacritty" "-e" "htop"` starts the program directly, so it reads
+    /// as its argv list, `spawn ["alacritty", "-e", "htop"]`, while
+    /// `spawn-sh "pkill orca || exec orca"` goes through a shell and reads
+    /// `spawn-sh sh -c 'pkill orca || exec orca'`. This is for display only;
+    /// JSON output keeps the action as niri writes it. Other actions and
+    /// programs are left alone.
+    pub fn with_spawn_display(mut keybind: Keybind) -> Keybind {
+        if keybind.program == Program::Niri
+            && let Some(action) = Self::spawn_display(&keybind)
+        {
+            keybind.action = action;
         }
+        keybind
+    }
 
-        result
+    fn spawn_display(keybind: &Keybind) -> Option<String> {
+        let args = &keybind.action_args;
+
+        match (keybind.action_name.as_deref()?, args.as_slice()) {
+            ("spawn", [_, ..]) => {
+                let argv: Vec<String> = args.iter().map(|arg| format!("{:?}", arg)).collect();
+                Some(format!("spawn [{}]", argv.join(", ")))
+            }
+            ("spawn-sh", [command]) => Some(format!(
+                "spawn-sh sh -c '{}'",
+                command.replace('\'', r"'\''")
+            )),
+            _ => None,
+        }
     }
 
-    fn format_value(value: &kdl::KdlValue) -> String {
-        if let Some(s) = value.as_string() {
-            format!("\"{}\"", s)
-        } else if let Some(i) = value.as_integer() {
-            i.to_string()
-        } else if let Some(b) = value.as_bool() {
-            b.to_string()
-        } else if let Some(f) = value.as_float() {
-            f.to_string()
-        } else {
-            "null".to_string()
+    /// The program and arguments a keybind's `spawn` or `spawn-sh` action runs,
+    /// e.g. `["sh", "-c", "pkill orca"]` for `spawn-sh "pkill orca"`.
+    ///
+    /// Returns `None` for other actions.
+    pub fn spawn_argv(keybind: &Keybind) -> Option<Vec<String>> {
+        let args = &keybind.action_args;
+
+        match (keybind.action_name.as_deref()?, args.as_slice()) {
+            ("spawn", [_, ..]) => Some(args.clone()),
+            ("spawn-sh", [command]) => Some(vec!["sh".into(), "-c".into(), command.clone()]),
+            _ => None,
         }
     }
 
-    fn parse_keybind_node(
+    /// Discovers keybinds, reporting bindings that failed to parse instead of dropping them.
+    ///
+    /// One malformed binding doesn't prevent the rest of the config from being listed.
+    pub fn discover_with_diagnostics(
         &self,
-        node: &kdl::KdlNode,
-    ) -> Result<Keybind, Box<dyn std::error::Error>> {
-        let name = node.name().to_string();
-
-        let (modifiers, key) = Self::parse_key_combination(&name)?;
-
-        // Extract properties from entries
-        let mut description = None;
-        let mut repeat = None;
-        let mut cooldown_ms = None;
-        let mut allow_when_locked = None;
-        let mut allow_inhibiting = None;
-
-        for entry in node.entries() {
-            if let Some(entry_name) = entry.name() {
-                match entry_name.value() {
-                    "hotkey-overlay-title" => {
-                        description = entry.value().as_string().map(|s| s.to_string());
-                    }
-                    "repeat" => {
-                        repeat = entry.value().as_bool();
-                    }
-                    "cooldown-ms" => {
-                        cooldown_ms = entry.value().as_integer().and_then(|v| {
-                            if v >= 0 && v <= u64::MAX as i128 {
-                                Some(v as u64)
-                            } else {
-                                None
-                            }
-                        });
-                    }
-                    "allow-when-locked" => {
-                        allow_when_locked = entry.value().as_bool();
-                    }
-                    "allow-inhibiting" => {
-                        allow_inhibiting = entry.value().as_bool();
-                    }
-                    _ => {}
-                }
-            }
-        }
+    ) -> Result<(Vec<Keybind>, Vec<ParseDiagnostic>), SourceError> {
+        let content = self.read_config()?;
+        self.parse_config_with_diagnostics(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
 
-        let action = if let Some(children) = node.children() {
-            children
-                .nodes()
-                .iter()
-                .map(Self::format_action)
-                .collect::<Vec<_>>()
-                .join(", ")
-        } else {
-            "unknown".to_string()
-        };
+    /// Validates every bind in the config, for `fzf-keys check`; see
+    /// [`KdlKeybindSource::check`].
+    pub fn check(&self) -> Result<Vec<ParseDiagnostic>, SourceError> {
+        let content = self.read_config()?;
+        self.binds
+            .check(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
 
-        Ok(Keybind {
-            modifiers,
-            key,
-            action,
-            description,
-            program: "niri".to_string(),
-            repeat,
-            cooldown_ms,
-            allow_when_locked,
-            allow_inhibiting,
-        })
+    /// The config path as given, before resolving symlinks.
+    pub fn config_path(&self) -> &std::path::Path {
+        self.binds.config_path()
+    }
+
+    /// The config path with symlinks resolved.
+    ///
+    /// On NixOS and with home-manager the config is a symlink into the store, so
+    /// the resolved path is what changes when the config is rebuilt.
+    pub fn canonical_config_path(&self) -> PathBuf {
+        let config_path = self.config_path();
+        fs::canonicalize(config_path).unwrap_or_else(|_| config_path.to_path_buf())
     }
 
-    fn parse_key_combination(
-        combo: &str,
-    ) -> Result<(Vec<Modifier>, String), Box<dyn std::error::Error>> {
-        let parts: Vec<&str> = combo.split('+').collect();
+    fn read_config(&self) -> Result<String, SourceError> {
+        fs::read_to_string(self.canonical_config_path())
+            .map_err(|e| SourceError::from_io(self.config_path(), e))
+    }
+
+    /// Every config read by discovery: the main one, then the extra ones.
+    pub fn config_paths(&self) -> impl Iterator<Item = &std::path::Path> {
+        std::iter::once(self.config_path())
+            .chain(self.extra_config_paths.iter().map(PathBuf::as_path))
+    }
 
-        if parts.is_empty() {
-            return Err("Empty key combination".into());
+    /// Parses every config in order, noting chords a later file binds again.
+    fn discover_configs(&self) -> Result<(Vec<Keybind>, Vec<String>), SourceError> {
+        let mut keybinds = Vec::new();
+        let mut notes = Vec::new();
+        let mut bound_in: HashMap<String, &std::path::Path> = HashMap::new();
+
+        for path in self.config_paths() {
+            let content =
+                fs::read_to_string(fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()))
+                    .map_err(|e| SourceError::from_io(path, e))?;
+            let parsed = self
+                .parse_config(&content)
+                .map_err(|e| SourceError::Parse(format!("{}: {}", path.display(), e)))?;
+            let parsed = self.with_config_mtime(parsed, path);
+
+            for keybind in &parsed {
+                if let Some(previous) = bound_in.insert(keybind.signature(), path)
+                    && previous != path
+                {
+                    notes.push(format!(
+                        "{} in {} shadows the bind in {}",
+                        keybind.keys_string(),
+                        path.display(),
+                        previous.display()
+                    ));
+                }
+            }
+            keybinds.extend(parsed);
         }
 
-        let mut modifiers = Vec::new();
-        let key = parts[parts.len() - 1].to_string();
-
-        for part in &parts[..parts.len() - 1] {
-            let modifier = match *part {
-                "Mod" => Modifier::Mod,
-                "Super" | "Win" => Modifier::Super,
-                "Alt" => Modifier::Alt,
-                "Ctrl" | "Control" => Modifier::Ctrl,
-                "Shift" => Modifier::Shift,
-                "ISO_Level3_Shift" | "Mod5" => Modifier::IsoLevel3Shift,
-                "ISO_Level5_Shift" | "Mod3" => Modifier::IsoLevel5Shift,
-                _ => return Err(format!("Unknown modifier: {}", part).into()),
-            };
-            modifiers.push(modifier);
+        Ok((keybinds, notes))
+    }
+
+    /// Stamps `keybinds` with the modification time of `path`, if enabled and known.
+    fn with_config_mtime(&self, keybinds: Vec<Keybind>, path: &std::path::Path) -> Vec<Keybind> {
+        let mtime = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .filter(|_| self.mtime)
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok());
+        match mtime {
+            Some(mtime) => keybinds
+                .into_iter()
+                .map(|keybind| keybind.config_mtime(mtime.as_secs()))
+                .collect(),
+            None => keybinds,
         }
+    }
 
-        Ok((modifiers, key))
+    /// Describes each chord bound in more than one config file, e.g.
+    /// `Mod+T in b.kdl shadows the bind in a.kdl`.
+    ///
+    /// Only the last file's bind takes effect. Chords bound twice within one
+    /// file are left to [`NiriSource::check`]. The notes are those of the last
+    /// discovery, so that the configs aren't parsed again; before discovering,
+    /// there are none.
+    pub fn shadowing_notes(&self) -> Vec<String> {
+        self.shadowing_notes.borrow().clone()
     }
 
     fn parse_config(&self, content: &str) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
-        let doc: KdlDocument = content.parse()?;
+        self.binds.parse_config(content)
+    }
 
-        let mut keybinds = Vec::new();
+    fn parse_config_with_diagnostics(
+        &self,
+        content: &str,
+    ) -> Result<(Vec<Keybind>, Vec<ParseDiagnostic>), Box<dyn std::error::Error>> {
+        self.binds.parse_config_with_diagnostics(content)
+    }
 
-        for node in doc.nodes() {
-            if node.name().to_string() == "binds"
-                && let Some(children) = node.children()
-            {
-                for bind_node in children.nodes() {
-                    let name = bind_node.name().to_string();
-
-                    if (name.contains('+') || !name.chars().next().unwrap_or(' ').is_lowercase())
-                        && let Ok(keybind) = self.parse_keybind_node(bind_node)
-                    {
-                        keybinds.push(keybind);
-                    }
-                }
+    /// Turns a `switch-events` entry like `lid-close { spawn "lock"; }` into a
+    /// keybind keyed by the event name, with no modifiers.
+    fn parse_switch_node(binds: &KdlKeybindSource, node: &KdlNode) -> Option<Keybind> {
+        KdlKeybindSource::has_action(node).then(|| {
+            let keybind =
+                Keybind::new(Program::Niri, node.name().value()).action(binds.format_actions(node));
+            binds.with_action_parts(keybind, node)
+        })
+    }
+}
+
+/// How long a burst of file events must go quiet before re-discovering.
+#[cfg(feature = "watch")]
+const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(100);
+
+#[cfg(feature = "watch")]
+impl NiriSource {
+    /// Watches the config file, passing freshly discovered keybinds to
+    /// `on_change` after each modification.
+    ///
+    /// The config's directory is watched rather than the file itself, since
+    /// editors and home-manager replace the file instead of writing to it. A
+    /// burst of events from one save is debounced into a single re-discovery,
+    /// and a config that fails to parse mid-edit is skipped until the next
+    /// change. This blocks for as long as the watcher runs.
+    pub fn watch(&self, on_change: impl FnMut(Vec<Keybind>)) -> Result<(), SourceError> {
+        use notify::{RecursiveMode, Watcher};
+
+        let directory = match self.config_path().parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (sender, events) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)
+            .map_err(|e| SourceError::Unavailable(format!("can't watch config: {}", e)))?;
+        watcher
+            .watch(&directory, RecursiveMode::NonRecursive)
+            .map_err(|e| {
+                SourceError::Unavailable(format!("can't watch {}: {}", directory.display(), e))
+            })?;
+
+        self.handle_watch_events(&events, on_change);
+        Ok(())
+    }
+
+    /// Re-discovers once per debounced burst of changes to the config, until `events` closes.
+    fn handle_watch_events(
+        &self,
+        events: &std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+        mut on_change: impl FnMut(Vec<Keybind>),
+    ) {
+        while let Ok(event) = events.recv() {
+            if !event.is_ok_and(|event| self.is_config_change(&event)) {
+                continue;
+            }
+            while events.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+
+            if let Ok(keybinds) = self.discover() {
+                on_change(keybinds);
             }
         }
+    }
+
+    fn is_config_change(&self, event: &notify::Event) -> bool {
+        use notify::EventKind;
 
-        Ok(keybinds)
+        matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+            && event
+                .paths
+                .iter()
+                .any(|path| path.file_name() == self.config_path().file_name())
     }
 }
 
@@ -202,34 +391,543 @@ impl Source for NiriSource {
         "niri"
     }
 
-    fn discover(&self) -> Result<Vec<Self::Item>, Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(&self.config_path)?;
+    fn is_available(&self) -> bool {
+        self.canonical_config_path().is_file()
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        if !self.extra_config_paths.is_empty() {
+            let (keybinds, notes) = self.discover_configs()?;
+            *self.shadowing_notes.borrow_mut() = notes;
+            return Ok(keybinds);
+        }
+
+        let content = self.read_config()?;
         self.parse_config(&content)
+            .map(|keybinds| self.with_config_mtime(keybinds, self.config_path()))
+            .map_err(|e| SourceError::Parse(e.to_string()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::keybind::{Chord, Modifier};
 
     #[test]
     fn test_parse_key_combination() {
-        let (mods, key) = NiriSource::parse_key_combination("Mod+Shift+T").unwrap();
-        assert_eq!(mods, vec![Modifier::Mod, Modifier::Shift]);
-        assert_eq!(key, "T");
+        let chord = KdlKeybindSource::parse_key_combination("Mod+Shift+T").unwrap();
+        assert!(!chord.is_sequence());
+        assert_eq!(
+            chord.steps(),
+            [(vec![Modifier::Mod, Modifier::Shift], "T".to_string())]
+        );
     }
 
     #[test]
     fn test_parse_key_combination_no_modifiers() {
-        let (mods, key) = NiriSource::parse_key_combination("XF86AudioRaiseVolume").unwrap();
-        assert_eq!(mods, vec![]);
-        assert_eq!(key, "XF86AudioRaiseVolume");
+        let chord = KdlKeybindSource::parse_key_combination("XF86AudioRaiseVolume").unwrap();
+        assert_eq!(chord, Chord::single(vec![], "XF86AudioRaiseVolume"));
     }
 
     #[test]
     fn test_parse_key_combination_multiple_modifiers() {
-        let (mods, key) = NiriSource::parse_key_combination("Mod+Shift+Ctrl+L").unwrap();
-        assert_eq!(mods, vec![Modifier::Mod, Modifier::Shift, Modifier::Ctrl]);
-        assert_eq!(key, "L");
+        let chord = KdlKeybindSource::parse_key_combination("Mod+Shift+Ctrl+L").unwrap();
+        assert_eq!(
+            chord,
+            Chord::single(vec![Modifier::Mod, Modifier::Shift, Modifier::Ctrl], "L")
+        );
+    }
+
+    #[test]
+    fn test_parse_config_with_diagnostics() {
+        let source = NiriSource::new(PathBuf::new());
+        let content = r#"binds {
+    Mod+T { spawn "alacritty"; }
+    Hyper+Q { close-window; }
+}
+"#;
+
+        let (keybinds, diagnostics) = source.parse_config_with_diagnostics(content).unwrap();
+
+        assert_eq!(keybinds.len(), 1);
+        assert_eq!(keybinds[0].key.first_key(), "T");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 3);
+        assert_eq!(diagnostics[0].combo, "Hyper+Q");
+        assert!(diagnostics[0].message.contains("Unknown modifier"));
+    }
+
+    #[test]
+    fn test_parse_config_single_lowercase_key() {
+        let source = NiriSource::new(PathBuf::new());
+        let content = r#"binds {
+    q { close-window; }
+}
+"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+
+        assert_eq!(keybinds.len(), 1);
+        assert!(keybinds[0].key.modifiers().is_empty());
+        assert_eq!(keybinds[0].key.first_key(), "q");
+        assert_eq!(keybinds[0].action, "close-window");
+    }
+
+    #[test]
+    fn test_parse_config_ignores_non_bind_nodes() {
+        let source = NiriSource::new(PathBuf::new());
+        let content = r#"binds {
+    some-setting "value"
+    Mod+Q { close-window; }
+}
+"#;
+
+        let (keybinds, diagnostics) = source.parse_config_with_diagnostics(content).unwrap();
+
+        assert_eq!(keybinds.len(), 1);
+        assert_eq!(keybinds[0].key.first_key(), "Q");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_parse_config_ignores_stray_nodes() {
+        let source = NiriSource::new(PathBuf::new());
+        let content = r#"binds {
+    stray
+    "---" { spawn "oops"; }
+    Mod+Q { close-window; }
+    "+" {}
+    Mod+T { spawn "alacritty"; }
+}
+"#;
+
+        let (keybinds, diagnostics) = source.parse_config_with_diagnostics(content).unwrap();
+
+        assert_eq!(keybinds.len(), 2);
+        assert_eq!(keybinds[0].key.first_key(), "Q");
+        assert_eq!(keybinds[1].key.first_key(), "T");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_parse_key_combination_repeated_modifier() {
+        assert_eq!(
+            KdlKeybindSource::parse_key_combination("Mod+Mod+T").unwrap(),
+            KdlKeybindSource::parse_key_combination("Mod+T").unwrap()
+        );
+        assert_eq!(
+            KdlKeybindSource::parse_key_combination("Super+Win+Shift+T")
+                .unwrap()
+                .modifiers(),
+            [Modifier::Super, Modifier::Shift]
+        );
+        assert_eq!(
+            KdlKeybindSource::repeated_modifiers("Mod+Shift+Mod+Mod+T"),
+            [Modifier::Mod]
+        );
+        assert!(KdlKeybindSource::repeated_modifiers("Mod+Shift+T").is_empty());
+    }
+
+    #[test]
+    fn test_check_warns_about_repeated_modifier() {
+        let root = temp_dir("repeated-modifier");
+        let path = root.join("config.kdl");
+        fs::write(&path, "binds {\n    Mod+Mod+T { spawn \"foot\"; }\n}\n").unwrap();
+        let source = NiriSource::new(path);
+
+        let keybinds = source.discover().unwrap();
+        assert_eq!(keybinds[0].keys_string(), "Mod+T");
+
+        let diagnostics = source.check().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "line 2: warning: Mod+Mod+T: Mod is repeated"
+        );
+    }
+
+    #[test]
+    fn test_parse_key_combination_missing_key() {
+        assert!(KdlKeybindSource::parse_key_combination("Mod+").is_err());
+        assert!(KdlKeybindSource::parse_key_combination("").is_err());
+    }
+
+    #[test]
+    fn test_parse_key_combination_plus_key() {
+        assert_eq!(
+            KdlKeybindSource::parse_key_combination("Mod++").unwrap(),
+            Chord::single(vec![Modifier::Mod], "+")
+        );
+        assert_eq!(
+            KdlKeybindSource::parse_key_combination("Mod+Shift++").unwrap(),
+            Chord::single(vec![Modifier::Mod, Modifier::Shift], "+")
+        );
+        assert_eq!(
+            KdlKeybindSource::parse_key_combination("Mod+plus").unwrap(),
+            Chord::single(vec![Modifier::Mod], "plus")
+        );
+        assert_eq!(
+            KdlKeybindSource::parse_key_combination("Mod+Minus").unwrap(),
+            Chord::single(vec![Modifier::Mod], "Minus")
+        );
+        assert!(KdlKeybindSource::parse_key_combination("Mod++Shift").is_err());
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("fzf-keys-niri-test-{}", std::process::id()))
+            .join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_config(dir: &std::path::Path) -> PathBuf {
+        let path = dir.join("niri/config.kdl");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "binds {\n}\n").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_default_config_prefers_xdg_config_home() {
+        let root = temp_dir("xdg");
+        let xdg = root.join("xdg");
+        let home = root.join("home");
+        let xdg_config = write_config(&xdg);
+        write_config(&home.join(".config"));
+
+        let candidates = NiriSource::default_config_candidates(Some(xdg), Some(home));
+
+        assert_eq!(NiriSource::first_existing(&candidates).unwrap(), xdg_config);
+    }
+
+    #[test]
+    fn test_default_config_falls_back_to_home() {
+        let root = temp_dir("fallback");
+        let xdg = root.join("xdg");
+        let home = root.join("home");
+        let home_config = write_config(&home.join(".config"));
+
+        let candidates = NiriSource::default_config_candidates(Some(xdg), Some(home));
+
+        assert_eq!(
+            candidates.last().unwrap(),
+            &PathBuf::from("/etc/niri/config.kdl")
+        );
+        assert_eq!(
+            NiriSource::first_existing(&candidates).unwrap(),
+            home_config
+        );
+    }
+
+    #[test]
+    fn test_default_config_lists_checked_paths() {
+        let root = temp_dir("missing");
+        let candidates =
+            NiriSource::default_config_candidates(Some(root.join("xdg")), Some(root.join("home")));
+        let missing: Vec<PathBuf> = candidates[..2].to_vec();
+
+        let message = NiriSource::first_existing(&missing)
+            .unwrap_err()
+            .to_string();
+
+        assert!(message.contains(&root.join("xdg/niri/config.kdl").display().to_string()));
+        assert!(
+            message.contains(
+                &root
+                    .join("home/.config/niri/config.kdl")
+                    .display()
+                    .to_string()
+            )
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_discover_through_symlink() {
+        let root = temp_dir("symlink");
+        let config = root.join("store-config.kdl");
+        fs::write(&config, "binds {\n    Mod+Q { close-window; }\n}\n").unwrap();
+        let link = root.join("config.kdl");
+        std::os::unix::fs::symlink(&config, &link).unwrap();
+
+        let source = NiriSource::new(link);
+
+        assert_eq!(
+            source.canonical_config_path(),
+            fs::canonicalize(&config).unwrap()
+        );
+        assert!(source.is_available());
+        let keybinds = source.discover().unwrap();
+        assert_eq!(keybinds.len(), 1);
+        assert_eq!(keybinds[0].action, "close-window");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dangling_symlink_is_unavailable() {
+        let root = temp_dir("dangling");
+        let link = root.join("config.kdl");
+        std::os::unix::fs::symlink(root.join("gone.kdl"), &link).unwrap();
+
+        let source = NiriSource::new(link.clone());
+
+        assert!(!source.is_available());
+        assert!(matches!(source.discover(), Err(SourceError::NotFound(path)) if path == link));
+    }
+
+    #[test]
+    fn test_hotkey_overlay_hidden() {
+        let source = NiriSource::new(PathBuf::new());
+        for title in ["false", "null"] {
+            let content = format!(
+                "binds {{\n    Mod+O hotkey-overlay-title={} {{ toggle-overview; }}\n}}\n",
+                title
+            );
+
+            let keybinds = source.parse_config(&content).unwrap();
+
+            assert!(keybinds[0].hidden_in_overlay, "{}", title);
+            assert_eq!(keybinds[0].description, None);
+            assert_eq!(
+                keybinds[0].to_string(),
+                "Mod+O - toggle-overview (overlay-hidden) [niri]"
+            );
+        }
+
+        let content = r#"binds {
+    Mod+T hotkey-overlay-title="Open a Terminal" { spawn "alacritty"; }
+}
+"#;
+        let keybinds = source.parse_config(content).unwrap();
+        assert!(!keybinds[0].hidden_in_overlay);
+    }
+
+    #[test]
+    fn test_to_niri_kdl_round_trip() {
+        let source = NiriSource::new(PathBuf::new());
+        let content = r#"binds {
+    Mod+Shift+T hotkey-overlay-title="Open a Terminal" repeat=false cooldown-ms=150 { spawn "alacritty"; }
+    Super+Alt+L allow-when-locked=true allow-inhibiting=false { spawn "swaylock"; focus-column-left; }
+    Mod+Shift+E { quit skip-confirmation=true; }
+    Mod+O hotkey-overlay-title=null { toggle-overview; }
+}
+"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+        let serialized: Vec<String> = keybinds.iter().map(Keybind::to_niri_kdl).collect();
+        let reparsed = source
+            .parse_config(&format!("binds {{\n{}\n}}\n", serialized.join("\n")))
+            .unwrap();
+
+        assert_eq!(keybinds.len(), 4);
+        assert_eq!(reparsed, keybinds);
+    }
+
+    #[test]
+    fn test_action_descriptions() {
+        let source = NiriSource::new(PathBuf::new()).with_action_descriptions(true);
+        let content = r#"binds {
+    Mod+H { focus-column-left; }
+    Mod+F { frobnicate-window; }
+    Mod+T hotkey-overlay-title="Open a Terminal" { spawn "alacritty"; }
+}
+"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+
+        assert_eq!(
+            keybinds[0].description.as_deref(),
+            Some("Focus Column to the Left")
+        );
+        assert_eq!(keybinds[1].description, None);
+        assert_eq!(keybinds[1].to_string(), "Mod+F - frobnicate-window [niri]");
+        assert_eq!(keybinds[2].description.as_deref(), Some("Open a Terminal"));
+    }
+
+    #[test]
+    fn test_action_descriptions_disabled_by_default() {
+        let source = NiriSource::new(PathBuf::new());
+        let content = "binds {\n    Mod+H { focus-column-left; }\n}\n";
+
+        let keybinds = source.parse_config(content).unwrap();
+
+        assert_eq!(keybinds[0].description, None);
+    }
+
+    #[test]
+    fn test_include_empty_action_drops_stray_nodes() {
+        let content = r#"binds {
+    stray
+    Mod+E
+}
+"#;
+
+        let keybinds = NiriSource::new(PathBuf::new())
+            .with_include_empty_action(true)
+            .parse_config(content)
+            .unwrap();
+
+        assert_eq!(keybinds.len(), 1);
+        assert_eq!(keybinds[0].key.first_key(), "E");
+    }
+
+    #[test]
+    fn test_childless_bind_dropped_by_default() {
+        let content = r#"binds {
+    Mod+T { spawn "alacritty"; }
+    Mod+E
+    Mod+R { }
+}
+"#;
+
+        let keybinds = NiriSource::new(PathBuf::new())
+            .parse_config(content)
+            .unwrap();
+        assert_eq!(keybinds.len(), 1);
+        assert_eq!(keybinds[0].key.first_key(), "T");
+
+        let keybinds = NiriSource::new(PathBuf::new())
+            .with_include_empty_action(true)
+            .parse_config(content)
+            .unwrap();
+        let actions: Vec<(&str, &str)> = keybinds
+            .iter()
+            .map(|kb| (kb.key.first_key(), kb.action.as_str()))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("T", "spawn \"alacritty\""),
+                ("E", "unknown"),
+                ("R", "unknown"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bare_numeric_args() {
+        let content = r#"binds {
+    Mod+Equal { set-column-width "+10%"; }
+    Mod+1 { focus-workspace "browser"; }
+    Mod+T { spawn "notify-send" "hello world"; }
+}
+"#;
+
+        let bare = NiriSource::new(PathBuf::new()).with_bare_numeric_args(true);
+        let keybinds = bare.parse_config(content).unwrap();
+        assert_eq!(keybinds[0].action, "set-column-width +10%");
+        assert_eq!(keybinds[1].action, "focus-workspace \"browser\"");
+        assert_eq!(keybinds[2].action, "spawn \"notify-send\" \"hello world\"");
+
+        let quoted = NiriSource::new(PathBuf::new())
+            .parse_config(content)
+            .unwrap();
+        assert_eq!(quoted[0].action, "set-column-width \"+10%\"");
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_watch_debounces_config_modifications() {
+        use notify::event::{EventKind, ModifyKind};
+
+        let config_path = write_config(&temp_dir("watch"));
+        fs::write(
+            &config_path,
+            "binds {\n    Mod+T { spawn \"alacritty\"; }\n}\n",
+        )
+        .unwrap();
+        let source = NiriSource::new(config_path.clone());
+
+        let modify = |path: &std::path::Path| {
+            Ok(notify::Event::new(EventKind::Modify(ModifyKind::Any)).add_path(path.to_path_buf()))
+        };
+        let (sender, events) = std::sync::mpsc::channel();
+        // An unrelated file in the same directory, then one save seen as several writes
+        sender
+            .send(modify(&config_path.with_file_name("other.kdl")))
+            .unwrap();
+        for _ in 0..3 {
+            sender.send(modify(&config_path)).unwrap();
+        }
+        drop(sender);
+
+        let mut discoveries = Vec::new();
+        source.handle_watch_events(&events, |keybinds| discoveries.push(keybinds));
+
+        assert_eq!(discoveries.len(), 1);
+        assert_eq!(discoveries[0].len(), 1);
+        assert_eq!(discoveries[0][0].keys_string(), "Mod+T");
+    }
+
+    #[test]
+    fn test_spawn_display() {
+        let content = r#"binds {
+    T { spawn "alacritty" "-e" "htop"; }
+    S { spawn-sh "pkill orca || echo 'no orca'"; }
+    Q { close-window; }
+}
+"#;
+        let [spawn, spawn_sh, other]: [Keybind; 3] = NiriSource::new(PathBuf::new())
+            .parse_config(content)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(spawn.action_name.as_deref(), Some("spawn"));
+        assert_eq!(spawn.action_args, ["alacritty", "-e", "htop"]);
+
+        assert_eq!(
+            NiriSource::with_spawn_display(spawn).action,
+            r#"spawn ["alacritty", "-e", "htop"]"#
+        );
+        assert_eq!(
+            NiriSource::with_spawn_display(spawn_sh).action,
+            r#"spawn-sh sh -c 'pkill orca || echo '\''no orca'\'''"#
+        );
+        assert_eq!(NiriSource::with_spawn_display(other).action, "close-window");
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_watch_rediscovers_per_burst() {
+        use notify::event::{CreateKind, EventKind, ModifyKind};
+
+        let config_path = write_config(&temp_dir("watch-bursts"));
+        fs::write(
+            &config_path,
+            "binds {\n    Mod+T { spawn \"alacritty\"; }\n}\n",
+        )
+        .unwrap();
+        let source = NiriSource::new(config_path.clone());
+
+        let (sender, events) = std::sync::mpsc::channel();
+        let path = config_path.clone();
+        let saves = std::thread::spawn(move || {
+            // An editor replacing the file, then a later save that writes it twice
+            let event = |kind| Ok(notify::Event::new(kind).add_path(path.clone()));
+            sender
+                .send(event(EventKind::Create(CreateKind::File)))
+                .unwrap();
+            sender
+                .send(event(EventKind::Modify(ModifyKind::Any)))
+                .unwrap();
+            std::thread::sleep(WATCH_DEBOUNCE * 3);
+            sender
+                .send(event(EventKind::Modify(ModifyKind::Any)))
+                .unwrap();
+            sender
+                .send(event(EventKind::Modify(ModifyKind::Any)))
+                .unwrap();
+        });
+
+        let mut discoveries = 0;
+        source.handle_watch_events(&events, |_| discoveries += 1);
+        saves.join().unwrap();
+
+        assert_eq!(discoveries, 2);
     }
 }
@@ -0,0 +1,136 @@
+// Readable descriptions for niri actions, keyed by action name. Add entries here as niri grows.
+const ACTION_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("show-hotkey-overlay", "Show Important Hotkeys"),
+    ("quit", "Exit niri"),
+    ("power-off-monitors", "Power Off Monitors"),
+    ("spawn", "Run a Program"),
+    ("spawn-sh", "Run a Shell Command"),
+    ("close-window", "Close Window"),
+    ("toggle-overview", "Open the Overview"),
+    (
+        "toggle-keyboard-shortcuts-inhibit",
+        "Toggle Shortcut Inhibiting",
+    ),
+    ("screenshot", "Take a Screenshot"),
+    ("screenshot-screen", "Screenshot the Screen"),
+    ("screenshot-window", "Screenshot the Window"),
+    ("focus-column-left", "Focus Column to the Left"),
+    ("focus-column-right", "Focus Column to the Right"),
+    ("focus-column-first", "Focus First Column"),
+    ("focus-column-last", "Focus Last Column"),
+    ("focus-window-up", "Focus Window Above"),
+    ("focus-window-down", "Focus Window Below"),
+    (
+        "focus-window-or-workspace-up",
+        "Focus Window or Workspace Above",
+    ),
+    (
+        "focus-window-or-workspace-down",
+        "Focus Window or Workspace Below",
+    ),
+    ("move-column-left", "Move Column Left"),
+    ("move-column-right", "Move Column Right"),
+    ("move-column-to-first", "Move Column to First"),
+    ("move-column-to-last", "Move Column to Last"),
+    ("move-window-up", "Move Window Up"),
+    ("move-window-down", "Move Window Down"),
+    (
+        "move-window-up-or-to-workspace-up",
+        "Move Window Up or to Workspace Above",
+    ),
+    (
+        "move-window-down-or-to-workspace-down",
+        "Move Window Down or to Workspace Below",
+    ),
+    ("focus-monitor-left", "Focus Monitor to the Left"),
+    ("focus-monitor-right", "Focus Monitor to the Right"),
+    ("focus-monitor-up", "Focus Monitor Above"),
+    ("focus-monitor-down", "Focus Monitor Below"),
+    (
+        "move-column-to-monitor-left",
+        "Move Column to Monitor on the Left",
+    ),
+    (
+        "move-column-to-monitor-right",
+        "Move Column to Monitor on the Right",
+    ),
+    ("move-column-to-monitor-up", "Move Column to Monitor Above"),
+    (
+        "move-column-to-monitor-down",
+        "Move Column to Monitor Below",
+    ),
+    ("focus-workspace", "Focus Workspace"),
+    ("focus-workspace-up", "Focus Workspace Above"),
+    ("focus-workspace-down", "Focus Workspace Below"),
+    ("focus-workspace-previous", "Focus Previous Workspace"),
+    ("move-column-to-workspace", "Move Column to Workspace"),
+    (
+        "move-column-to-workspace-up",
+        "Move Column to Workspace Above",
+    ),
+    (
+        "move-column-to-workspace-down",
+        "Move Column to Workspace Below",
+    ),
+    ("move-window-to-workspace", "Move Window to Workspace"),
+    ("move-workspace-up", "Move Workspace Up"),
+    ("move-workspace-down", "Move Workspace Down"),
+    (
+        "consume-or-expel-window-left",
+        "Consume or Expel Window Left",
+    ),
+    (
+        "consume-or-expel-window-right",
+        "Consume or Expel Window Right",
+    ),
+    ("consume-window-into-column", "Consume Window into Column"),
+    ("expel-window-from-column", "Expel Window from Column"),
+    ("switch-preset-column-width", "Cycle Column Width Presets"),
+    ("switch-preset-window-height", "Cycle Window Height Presets"),
+    ("reset-window-height", "Reset Window Height"),
+    ("maximize-column", "Maximize Column"),
+    ("fullscreen-window", "Toggle Fullscreen"),
+    (
+        "expand-column-to-available-width",
+        "Expand Column to Available Width",
+    ),
+    ("center-column", "Center Column"),
+    ("center-visible-columns", "Center Visible Columns"),
+    ("set-column-width", "Adjust Column Width"),
+    ("set-window-height", "Adjust Window Height"),
+    ("toggle-window-floating", "Toggle Floating"),
+    (
+        "switch-focus-between-floating-and-tiling",
+        "Switch Focus Between Floating and Tiling",
+    ),
+    ("toggle-column-tabbed-display", "Toggle Tabbed Column"),
+    ("switch-layout", "Switch Keyboard Layout"),
+];
+
+/// Returns a readable description for a niri action name, if one is known.
+///
+/// Used to describe binds that have no `hotkey-overlay-title`.
+pub fn describe(action_name: &str) -> Option<&'static str> {
+    ACTION_DESCRIPTIONS
+        .iter()
+        .find(|(name, _)| *name == action_name)
+        .map(|(_, description)| *description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_known_action() {
+        assert_eq!(
+            describe("focus-column-left"),
+            Some("Focus Column to the Left")
+        );
+    }
+
+    #[test]
+    fn test_describe_unknown_action() {
+        assert_eq!(describe("frobnicate-window"), None);
+    }
+}
@@ -0,0 +1,335 @@
+use crate::error::SourceError;
+use crate::keybind::{Chord, Keybind, Modifier, Program};
+use crate::source::Source;
+use std::fs;
+use std::path::PathBuf;
+
+/// Source for discovering keybinds from a Qtile `config.py`.
+///
+/// # Discovery Method
+///
+/// The config is scanned statically, it is never executed. `Key(...)` and
+/// `KeyChord(...)` calls are located outside of comments and strings, and their
+/// arguments are extracted by balanced-paren matching:
+/// - `Key([mod, "shift"], "Return", lazy.spawn("term"), desc="Launch terminal")`
+/// - `KeyChord([mod], "z", [Key([], "x", lazy.window.kill())])`
+///
+/// Bindings nested in a `KeyChord` are represented as sequences, e.g. `Mod+z>x`.
+/// Keys built dynamically (loops, comprehensions) can't be resolved statically
+/// and are skipped.
+pub struct QtileSource {
+    config_path: PathBuf,
+}
+
+impl QtileSource {
+    pub fn new(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    pub fn from_default_config() -> Result<Self, Box<dyn std::error::Error>> {
+        let config_path = if let Ok(home) = std::env::var("HOME") {
+            PathBuf::from(home).join(".config/qtile/config.py")
+        } else {
+            return Err("HOME environment variable not set".into());
+        };
+
+        Ok(Self::new(config_path))
+    }
+
+    fn parse_config(&self, content: &str) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+        let mut keybinds = Vec::new();
+        Self::collect_calls(content, None, &mut keybinds);
+        Ok(keybinds)
+    }
+
+    /// Collects every `Key`/`KeyChord` call in `text`, prefixing nested chords.
+    fn collect_calls(text: &str, prefix: Option<&Chord>, keybinds: &mut Vec<Keybind>) {
+        for (name, args) in Self::find_calls(text, &["Key", "KeyChord"]) {
+            let args = Self::split_args(args);
+            let Some(step) = Self::parse_combo(&args) else {
+                continue;
+            };
+
+            let mut chord = prefix.cloned().unwrap_or(Chord(Vec::new()));
+            chord.0.push(step);
+
+            if name == "KeyChord" {
+                if let Some(inner) = args.get(2) {
+                    Self::collect_calls(inner, Some(&chord), keybinds);
+                }
+                continue;
+            }
+
+            let mut actions = Vec::new();
+            let mut description = None;
+            for arg in &args[2..] {
+                match arg.split_once('=') {
+                    Some((kw, value)) if Self::is_identifier(kw.trim()) => {
+                        if kw.trim() == "desc" {
+                            description = Self::string_literal(value);
+                        }
+                    }
+                    _ => actions.push(Self::collapse_whitespace(arg)),
+                }
+            }
+
+            let action = if actions.is_empty() {
+                "unknown".to_string()
+            } else {
+                actions.join(", ")
+            };
+
+            keybinds.push(
+                Keybind::new(Program::Qtile, chord)
+                    .action(action)
+                    .description(description),
+            );
+        }
+    }
+
+    fn parse_combo(args: &[&str]) -> Option<(Vec<Modifier>, String)> {
+        let modifier_list = args.first()?.trim();
+        let modifier_list = modifier_list.strip_prefix('[')?.strip_suffix(']')?;
+
+        let mut modifiers = Vec::new();
+        for token in Self::split_args(modifier_list) {
+            let token = Self::string_literal(token).unwrap_or_else(|| token.trim().to_string());
+            modifiers.push(Self::parse_modifier(&token).ok()?);
+        }
+
+        let key = Self::string_literal(args.get(1)?)?;
+        Some((modifiers, key))
+    }
+
+    fn parse_modifier(name: &str) -> Result<Modifier, Box<dyn std::error::Error>> {
+        match name.to_lowercase().as_str() {
+            "mod" => Ok(Modifier::Mod),
+            "shift" => Ok(Modifier::Shift),
+            "control" | "ctrl" => Ok(Modifier::Ctrl),
+            "mod1" | "alt" => Ok(Modifier::Alt),
+            "mod4" | "super" => Ok(Modifier::Super),
+            "mod5" => Ok(Modifier::IsoLevel3Shift),
+            "mod3" => Ok(Modifier::IsoLevel5Shift),
+            _ => Err(format!("Unknown modifier: {}", name).into()),
+        }
+    }
+
+    /// Finds calls to any of `names`, returning the call name and the text between its parens.
+    ///
+    /// Calls nested inside a found call are not returned separately.
+    fn find_calls<'a>(text: &'a str, names: &[&'a str]) -> Vec<(&'a str, &'a str)> {
+        let bytes = text.as_bytes();
+        let mut calls = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'#' => i = Self::skip_comment(bytes, i),
+                b'"' | b'\'' => i = Self::skip_string(bytes, i),
+                c if c.is_ascii_alphabetic() || c == b'_' => {
+                    let start = i;
+                    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_')
+                    {
+                        i += 1;
+                    }
+                    let ident = &text[start..i];
+                    // Attribute access like `config.Key(` still counts, `MyKey(` does not
+                    if let Some(name) = names.iter().find(|name| **name == ident)
+                        && bytes.get(i) == Some(&b'(')
+                        && let Some(end) = Self::matching_paren(bytes, i)
+                    {
+                        calls.push((*name, &text[i + 1..end]));
+                        i = end + 1;
+                    }
+                }
+                _ => i += 1,
+            }
+        }
+
+        calls
+    }
+
+    /// Returns the index of the closer matching the opener at `open`.
+    fn matching_paren(bytes: &[u8], open: usize) -> Option<usize> {
+        let mut depth = 0usize;
+        let mut i = open;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'#' => {
+                    i = Self::skip_comment(bytes, i);
+                    continue;
+                }
+                b'"' | b'\'' => {
+                    i = Self::skip_string(bytes, i);
+                    continue;
+                }
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => {
+                    depth = depth.checked_sub(1)?;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Splits a call's argument text on top-level commas.
+    fn split_args(text: &str) -> Vec<&str> {
+        let bytes = text.as_bytes();
+        let mut args = Vec::new();
+        let mut depth = 0usize;
+        let mut start = 0;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'#' => {
+                    i = Self::skip_comment(bytes, i);
+                    continue;
+                }
+                b'"' | b'\'' => {
+                    i = Self::skip_string(bytes, i);
+                    continue;
+                }
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+                b',' if depth == 0 => {
+                    args.push(text[start..i].trim());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        let last = text[start..].trim();
+        if !last.is_empty() {
+            args.push(last);
+        }
+
+        args
+    }
+
+    fn skip_comment(bytes: &[u8], mut i: usize) -> usize {
+        while i < bytes.len() && bytes[i] != b'\n' {
+            i += 1;
+        }
+        i
+    }
+
+    /// Skips a quoted string starting at `i`, returning the index just past its closing quote.
+    fn skip_string(bytes: &[u8], mut i: usize) -> usize {
+        let quote = bytes[i];
+        i += 1;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 2,
+                c if c == quote => return i + 1,
+                _ => i += 1,
+            }
+        }
+        i
+    }
+
+    fn string_literal(text: &str) -> Option<String> {
+        let text = text.trim();
+        let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let inner = text.strip_prefix(quote)?.strip_suffix(quote)?;
+        Some(inner.replace(&format!("\\{}", quote), &quote.to_string()))
+    }
+
+    fn is_identifier(text: &str) -> bool {
+        !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    fn collapse_whitespace(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Source for QtileSource {
+    type Item = Keybind;
+
+    fn name(&self) -> &str {
+        "qtile"
+    }
+
+    fn is_available(&self) -> bool {
+        self.config_path.is_file()
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        let content = fs::read_to_string(&self.config_path)
+            .map_err(|e| SourceError::from_io(&self.config_path, e))?;
+        self.parse_config(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multi_modifier_key() {
+        let source = QtileSource::new(PathBuf::new());
+        let content = r#"
+from libqtile.lazy import lazy
+mod = "mod4"
+
+keys = [
+    # Key([mod], "q", lazy.commented.out()),
+    Key([mod, "shift", "control"], "Return", lazy.spawn("alacritty -e 'tmux'"), desc="Launch terminal"),
+    Key([mod], "h", lazy.layout.left()),
+]
+"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 2);
+
+        let terminal = &keybinds[0];
+        assert_eq!(
+            terminal.key.modifiers(),
+            [Modifier::Mod, Modifier::Shift, Modifier::Ctrl]
+        );
+        assert_eq!(terminal.key.first_key(), "Return");
+        assert_eq!(terminal.action, "lazy.spawn(\"alacritty -e 'tmux'\")");
+        assert_eq!(terminal.description.as_deref(), Some("Launch terminal"));
+        assert_eq!(terminal.program, Program::Qtile);
+
+        assert_eq!(keybinds[1].action, "lazy.layout.left()");
+        assert_eq!(keybinds[1].description, None);
+    }
+
+    #[test]
+    fn test_parse_key_chord() {
+        let source = QtileSource::new(PathBuf::new());
+        let content = r#"
+keys = [
+    KeyChord([mod], "z", [
+        Key([], "x", lazy.window.kill()),
+        Key(["shift"], "g", lazy.layout.grow(), lazy.layout.normalize()),
+    ], name="window"),
+]
+"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+        assert_eq!(keybinds.len(), 2);
+
+        assert_eq!(keybinds[0].key.steps().len(), 2);
+        assert_eq!(keybinds[0].keys_string(), "Mod+z>x");
+        assert_eq!(keybinds[0].action, "lazy.window.kill()");
+
+        assert_eq!(keybinds[1].keys_string(), "Mod+z>Shift+g");
+        assert_eq!(
+            keybinds[1].action,
+            "lazy.layout.grow(), lazy.layout.normalize()"
+        );
+    }
+}
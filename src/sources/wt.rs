@@ -0,0 +1,319 @@
+use crate::error::SourceError;
+use crate::keybind::{Keybind, Modifier, Program};
+use crate::source::Source;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Source for discovering keybinds from Windows Terminal's `settings.json`.
+///
+/// # Discovery Method
+///
+/// The settings file is JSONC: comments and trailing commas are stripped before
+/// it is parsed as JSON. Keybinds come from two places:
+/// - `"actions": [{ "command": "closePane", "keys": "ctrl+shift+w" }]`, where
+///   `keys` may also be an array of alternative combos
+/// - `"keybindings": [{ "id": "User.myAction", "keys": "ctrl+k" }]` in newer
+///   versions, with the command looked up from the action that has that `id`
+///
+/// Object commands such as `{ "action": "splitPane", "split": "auto" }` are
+/// shown as `splitPane split="auto"`. An action's `name` becomes the description.
+pub struct WindowsTerminalSource {
+    config_path: PathBuf,
+}
+
+impl WindowsTerminalSource {
+    pub fn new(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    /// `%LOCALAPPDATA%\Packages\Microsoft.WindowsTerminal_8wekyb3d8bbwe\LocalState\settings.json`
+    pub fn from_default_config() -> Result<Self, Box<dyn std::error::Error>> {
+        let config_path = if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+            PathBuf::from(local_app_data)
+                .join("Packages/Microsoft.WindowsTerminal_8wekyb3d8bbwe/LocalState/settings.json")
+        } else {
+            return Err("LOCALAPPDATA environment variable not set".into());
+        };
+
+        Ok(Self::new(config_path))
+    }
+
+    fn parse_config(&self, content: &str) -> Result<Vec<Keybind>, Box<dyn std::error::Error>> {
+        let settings: Value = serde_json::from_str(&Self::strip_jsonc(content))?;
+        let actions = settings
+            .get("actions")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let mut keybinds = Vec::new();
+        let mut commands_by_id = HashMap::new();
+
+        for action in actions {
+            let Some(command) = action.get("command").map(Self::format_command) else {
+                continue;
+            };
+            let description = action.get("name").and_then(Value::as_str);
+
+            if let Some(id) = action.get("id").and_then(Value::as_str) {
+                commands_by_id.insert(id, (command.clone(), description));
+            }
+
+            Self::push_keybinds(&mut keybinds, action.get("keys"), &command, description);
+        }
+
+        if let Some(bindings) = settings.get("keybindings").and_then(Value::as_array) {
+            for binding in bindings {
+                let Some(id) = binding.get("id").and_then(Value::as_str) else {
+                    continue;
+                };
+                let (command, description) = match commands_by_id.get(id) {
+                    Some((command, description)) => (command.clone(), *description),
+                    // Built-in actions aren't listed in settings.json; show their id
+                    None => (id.to_string(), None),
+                };
+
+                Self::push_keybinds(&mut keybinds, binding.get("keys"), &command, description);
+            }
+        }
+
+        Ok(keybinds)
+    }
+
+    /// Adds a keybind for each combo in `keys`, which is a string or an array of strings.
+    fn push_keybinds(
+        keybinds: &mut Vec<Keybind>,
+        keys: Option<&Value>,
+        command: &str,
+        description: Option<&str>,
+    ) {
+        let combos: Vec<&str> = match keys {
+            Some(Value::String(combo)) => vec![combo],
+            Some(Value::Array(combos)) => combos.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+
+        for combo in combos {
+            let Ok((modifiers, key)) = Self::parse_key_combination(combo) else {
+                continue;
+            };
+
+            keybinds.push(
+                Keybind::new(Program::WindowsTerminal, key)
+                    .modifiers(modifiers)
+                    .action(command)
+                    .description(description.map(str::to_string)),
+            );
+        }
+    }
+
+    fn format_command(command: &Value) -> String {
+        let Some(object) = command.as_object() else {
+            return command
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| command.to_string());
+        };
+
+        let mut result = object
+            .get("action")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+            .to_string();
+        for (name, value) in object.iter().filter(|(name, _)| *name != "action") {
+            result.push_str(&format!(" {}={}", name, value));
+        }
+        result
+    }
+
+    fn parse_key_combination(
+        combo: &str,
+    ) -> Result<(Vec<Modifier>, String), Box<dyn std::error::Error>> {
+        let parts: Vec<&str> = combo.split('+').map(str::trim).collect();
+
+        let mut modifiers = Vec::new();
+        let key = parts[parts.len() - 1];
+        if key.is_empty() {
+            return Err(format!("Missing key in '{}'", combo).into());
+        }
+
+        for part in &parts[..parts.len() - 1] {
+            let modifier = match part.to_lowercase().as_str() {
+                "ctrl" => Modifier::Ctrl,
+                "alt" => Modifier::Alt,
+                "shift" => Modifier::Shift,
+                "win" => Modifier::Super,
+                _ => return Err(format!("Unknown modifier: {}", part).into()),
+            };
+            modifiers.push(modifier);
+        }
+
+        Ok((modifiers, key.to_string()))
+    }
+
+    /// Removes `//` and `/* */` comments and trailing commas outside of strings.
+    fn strip_jsonc(content: &str) -> String {
+        let mut without_comments = String::with_capacity(content.len());
+        let mut chars = content.chars().peekable();
+        let mut in_string = false;
+
+        while let Some(c) = chars.next() {
+            if in_string {
+                without_comments.push(c);
+                match c {
+                    '\\' => without_comments.extend(chars.next()),
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+
+            match (c, chars.peek()) {
+                ('/', Some('/')) => while chars.next_if(|&next| next != '\n').is_some() {},
+                ('/', Some('*')) => {
+                    chars.next();
+                    let mut previous = None;
+                    for next in chars.by_ref() {
+                        if previous == Some('*') && next == '/' {
+                            break;
+                        }
+                        previous = Some(next);
+                    }
+                }
+                _ => {
+                    in_string = c == '"';
+                    without_comments.push(c);
+                }
+            }
+        }
+
+        let mut result = String::with_capacity(without_comments.len());
+        let mut in_string = false;
+        let mut escaped = false;
+        for (i, c) in without_comments.char_indices() {
+            if in_string {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+            } else if c == '"' {
+                in_string = true;
+            } else if c == ','
+                && without_comments[i + 1..]
+                    .trim_start()
+                    .starts_with(['}', ']'])
+            {
+                continue;
+            }
+            result.push(c);
+        }
+
+        result
+    }
+}
+
+impl Source for WindowsTerminalSource {
+    type Item = Keybind;
+
+    fn name(&self) -> &str {
+        "windows-terminal"
+    }
+
+    fn is_available(&self) -> bool {
+        self.config_path.is_file()
+    }
+
+    fn discover(&self) -> Result<Vec<Self::Item>, SourceError> {
+        let content = fs::read_to_string(&self.config_path)
+            .map_err(|e| SourceError::from_io(&self.config_path, e))?;
+        self.parse_config(&content)
+            .map_err(|e| SourceError::Parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_string_keys() {
+        let source = WindowsTerminalSource::new(PathBuf::new());
+        let content = r#"{
+    // Copy and paste
+    "actions": [
+        { "command": "closePane", "keys": "ctrl+shift+w" },
+        /* split with a name */
+        {
+            "command": { "action": "splitPane", "split": "auto" },
+            "keys": "alt+shift+d",
+            "name": "Split pane",
+        },
+        { "command": "unbound" },
+    ],
+}"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+
+        assert_eq!(keybinds.len(), 2);
+        assert_eq!(
+            keybinds[0].key.modifiers(),
+            [Modifier::Ctrl, Modifier::Shift]
+        );
+        assert_eq!(keybinds[0].key.first_key(), "w");
+        assert_eq!(keybinds[0].action, "closePane");
+        assert_eq!(keybinds[0].program, Program::WindowsTerminal);
+        assert_eq!(keybinds[1].action, "splitPane split=\"auto\"");
+        assert_eq!(keybinds[1].description.as_deref(), Some("Split pane"));
+    }
+
+    #[test]
+    fn test_parse_array_keys() {
+        let source = WindowsTerminalSource::new(PathBuf::new());
+        let content = r#"{
+    "actions": [
+        { "command": "nextTab", "keys": ["ctrl+tab", "win+pgdn"] }
+    ]
+}"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+
+        assert_eq!(keybinds.len(), 2);
+        assert_eq!(keybinds[0].keys_string(), "Ctrl+tab");
+        assert_eq!(keybinds[1].keys_string(), "Super+pgdn");
+        assert!(keybinds.iter().all(|kb| kb.action == "nextTab"));
+    }
+
+    #[test]
+    fn test_parse_keybindings_by_id() {
+        let source = WindowsTerminalSource::new(PathBuf::new());
+        let content = r#"{
+    "actions": [
+        { "command": { "action": "copy", "singleLine": false }, "id": "User.copy" }
+    ],
+    "keybindings": [
+        { "id": "User.copy", "keys": "ctrl+c" },
+        { "id": "Terminal.FindText", "keys": "ctrl+shift+f" }
+    ]
+}"#;
+
+        let keybinds = source.parse_config(content).unwrap();
+
+        assert_eq!(keybinds.len(), 2);
+        assert_eq!(keybinds[0].action, "copy singleLine=false");
+        assert_eq!(keybinds[1].action, "Terminal.FindText");
+    }
+
+    #[test]
+    fn test_strip_jsonc_keeps_strings() {
+        let content = r#"{ "url": "https://example.com/*x*/", "list": [1, 2,], }"#;
+
+        assert_eq!(
+            WindowsTerminalSource::strip_jsonc(content),
+            r#"{ "url": "https://example.com/*x*/", "list": [1, 2] }"#
+        );
+    }
+}
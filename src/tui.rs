@@ -0,0 +1,221 @@
+//! A built-in picker for `--tui`, for when fzf isn't installed.
+//!
+//! The picker is a search box over a list of keybinds, filtered with
+//! [`Keybind::matches_query`] as the query is typed. It draws on stderr so
+//! that the picked keybind can be piped from stdout, like fzf does.
+
+use crate::keybind::Keybind;
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, List, ListState, Paragraph};
+use std::io;
+
+/// What the picker shows: the query and the keybinds matching it.
+///
+/// This holds no terminal state, so the filtering can be driven without one.
+pub struct Picker {
+    keybinds: Vec<Keybind>,
+    query: String,
+    /// Indices into `keybinds` of the matches, in their original order.
+    matches: Vec<usize>,
+    /// Index into `matches` of the highlighted keybind.
+    selected: usize,
+}
+
+impl Picker {
+    pub fn new(keybinds: Vec<Keybind>) -> Self {
+        let matches = (0..keybinds.len()).collect();
+        Self {
+            keybinds,
+            query: String::new(),
+            matches,
+            selected: 0,
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// The keybinds matching the query, in the order they were discovered.
+    pub fn matches(&self) -> impl Iterator<Item = &Keybind> {
+        self.matches.iter().map(|&i| &self.keybinds[i])
+    }
+
+    /// The highlighted keybind, or `None` if nothing matches.
+    pub fn selection(&self) -> Option<&Keybind> {
+        self.matches.get(self.selected).map(|&i| &self.keybinds[i])
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.query.push(c);
+        self.refilter();
+    }
+
+    pub fn pop_char(&mut self) {
+        if self.query.pop().is_some() {
+            self.refilter();
+        }
+    }
+
+    /// Moves the highlight down, stopping at the last match.
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.matches.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Moves the highlight up, stopping at the first match.
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Recomputes the matches, highlighting the best (first) one.
+    fn refilter(&mut self) {
+        self.matches = self
+            .keybinds
+            .iter()
+            .enumerate()
+            .filter(|(_, keybind)| keybind.matches_query(&self.query))
+            .map(|(i, _)| i)
+            .collect();
+        self.selected = 0;
+    }
+
+    /// Takes the highlighted keybind, consuming the picker.
+    fn into_selection(mut self) -> Option<Keybind> {
+        let index = *self.matches.get(self.selected)?;
+        Some(self.keybinds.swap_remove(index))
+    }
+}
+
+/// Lets the user pick one of `keybinds` interactively.
+///
+/// Enter picks the highlighted keybind; Esc or Ctrl+C returns `None`. The
+/// terminal is restored even if drawing fails.
+pub fn pick(keybinds: Vec<Keybind>) -> io::Result<Option<Keybind>> {
+    enable_raw_mode()?;
+    let mut stderr = io::stderr();
+    if let Err(e) = execute!(stderr, EnterAlternateScreen) {
+        let _ = disable_raw_mode();
+        return Err(e);
+    }
+
+    let result = Terminal::new(CrosstermBackend::new(io::stderr()))
+        .and_then(|mut terminal| run(&mut terminal, Picker::new(keybinds)));
+
+    let _ = execute!(stderr, LeaveAlternateScreen);
+    disable_raw_mode()?;
+    result
+}
+
+fn run(
+    terminal: &mut Terminal<CrosstermBackend<io::Stderr>>,
+    mut picker: Picker,
+) -> io::Result<Option<Keybind>> {
+    loop {
+        terminal.draw(|frame| {
+            let [search, list] =
+                Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(frame.area());
+
+            frame.render_widget(
+                Paragraph::new(picker.query()).block(Block::bordered().title("Search")),
+                search,
+            );
+
+            let items: Vec<String> = picker.matches().map(Keybind::to_string).collect();
+            let count = format!("{}/{}", items.len(), picker.keybinds.len());
+            let mut state = ListState::default().with_selected(Some(picker.selected));
+            frame.render_stateful_widget(
+                List::new(items)
+                    .block(Block::bordered().title(count))
+                    .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+                list,
+                &mut state,
+            );
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => return Ok(picker.into_selection()),
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if ctrl => return Ok(None),
+            KeyCode::Char('n') if ctrl => picker.select_next(),
+            KeyCode::Char('p') if ctrl => picker.select_previous(),
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Backspace => picker.pop_char(),
+            KeyCode::Char(c) => picker.push_char(c),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picker() -> Picker {
+        Picker::new(vec![
+            Keybind::new("niri", "T")
+                .action("spawn alacritty")
+                .description("Open a Terminal".to_string()),
+            Keybind::new("niri", "Q").action("close-window"),
+            Keybind::new("kitty", "t").action("new_tab"),
+        ])
+    }
+
+    #[test]
+    fn test_typing_filters_matches() {
+        let mut picker = picker();
+        assert_eq!(picker.matches().count(), 3);
+
+        for c in "TERM".chars() {
+            picker.push_char(c);
+        }
+        assert_eq!(picker.query(), "TERM");
+        let actions: Vec<&str> = picker.matches().map(|k| k.action.as_str()).collect();
+        assert_eq!(actions, ["spawn alacritty"]);
+
+        picker.push_char('x');
+        assert_eq!(picker.matches().count(), 0);
+        assert!(picker.selection().is_none());
+
+        for _ in 0..5 {
+            picker.pop_char();
+        }
+        assert_eq!(picker.query(), "");
+        assert_eq!(picker.matches().count(), 3);
+    }
+
+    #[test]
+    fn test_selection_moves_within_matches() {
+        let mut picker = picker();
+        picker.select_previous();
+        assert_eq!(picker.selection().unwrap().action, "spawn alacritty");
+
+        picker.select_next();
+        picker.select_next();
+        picker.select_next();
+        assert_eq!(picker.selection().unwrap().action, "new_tab");
+
+        // Narrowing the matches highlights the first one again
+        picker.push_char('w');
+        picker.push_char('i');
+        assert_eq!(picker.selection().unwrap().action, "close-window");
+        assert_eq!(picker.into_selection().unwrap().action, "close-window");
+    }
+}
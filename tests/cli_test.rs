@@ -0,0 +1,888 @@
+use fzf_keys::format;
+use fzf_keys::keybind::{self, SortKey};
+use fzf_keys::source::Source;
+use fzf_keys::sources::niri::NiriSource;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn fzf_keys() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_fzf-keys"));
+    // Keep the user's own config.toml out of the tests
+    command.env("XDG_CONFIG_HOME", env!("CARGO_TARGET_TMPDIR"));
+    command
+}
+
+/// Writes `content` as a config file and returns its path.
+fn write_config(name: &str, content: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn test_exit_code_no_keybinds() {
+    let output = fzf_keys()
+        .args(["--niri-config", "tests/niri-empty-config.kdl"])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_exit_code_all_sources_failed() {
+    let output = fzf_keys()
+        .args(["--niri-config", "tests/does-not-exist.kdl"])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_exit_code_success() {
+    let output = fzf_keys()
+        .args(["--niri-config", "tests/niri-default-config.kdl"])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert!(output.status.success());
+    assert!(!output.stdout.is_empty());
+}
+
+#[test]
+fn test_reverse_with_sort_key() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-default-config.kdl",
+            "--sort",
+            "key",
+            "--reverse",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    let mut expected = NiriSource::new(PathBuf::from("tests/niri-default-config.kdl"))
+        .discover()
+        .expect("Failed to discover keybinds");
+    keybind::sort_keybinds(&mut expected, SortKey::Key);
+    expected.reverse();
+
+    let lines: Vec<String> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    let expected: Vec<String> = expected
+        .iter()
+        .map(|kb| NiriSource::with_spawn_display(kb.clone()).to_string())
+        .collect();
+    assert_eq!(lines, expected);
+}
+
+#[test]
+fn test_niri_config_env_var() {
+    let output = fzf_keys()
+        .env("FZF_KEYS_NIRI_CONFIG", "tests/niri-diff-new.kdl")
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    let expected: Vec<String> = NiriSource::new(PathBuf::from("tests/niri-diff-new.kdl"))
+        .discover()
+        .expect("Failed to discover keybinds")
+        .iter()
+        .map(|kb| NiriSource::with_spawn_display(kb.clone()).to_string())
+        .collect();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_niri_config_flag_overrides_env_var() {
+    let output = fzf_keys()
+        .env("FZF_KEYS_NIRI_CONFIG", "tests/does-not-exist.kdl")
+        .args(["--niri-config", "tests/niri-diff-new.kdl"])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert!(output.status.success());
+    assert!(!output.stdout.is_empty());
+}
+
+#[test]
+fn test_print_config_path() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-diff-new.kdl",
+            "--print-config-path",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "tests/niri-diff-new.kdl\n"
+    );
+
+    let output = fzf_keys()
+        .env("FZF_KEYS_NIRI_CONFIG", "tests/niri-diff-old.kdl")
+        .arg("--print-config-path")
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "tests/niri-diff-old.kdl\n"
+    );
+}
+
+#[test]
+fn test_niri_config_repeated_merges_files() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-diff-old.kdl",
+            "--niri-config",
+            "tests/niri-diff-new.kdl",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mod+T - spawn [\"alacritty\"] [niri]\n\
+         Mod+Q - close-window [niri]\n\
+         Mod+H - focus-column-left [niri]\n\
+         Mod+T - spawn [\"foot\"] [niri]\n\
+         Mod+H - focus-column-left [niri]\n\
+         Mod+L - focus-column-right [niri]\n"
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(
+        "Note: Mod+T in tests/niri-diff-new.kdl shadows the bind in tests/niri-diff-old.kdl"
+    ));
+    assert!(stderr.contains("Note: Mod+H in tests/niri-diff-new.kdl"));
+}
+
+#[test]
+fn test_sample_with_seed() {
+    let run = |seed: &str| {
+        let output = fzf_keys()
+            .args([
+                "--niri-config",
+                "tests/niri-default-config.kdl",
+                "--sample",
+                "5",
+                "--seed",
+                seed,
+            ])
+            .output()
+            .expect("Failed to run fzf-keys");
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let sample = run("1");
+    assert_eq!(sample.lines().count(), 5);
+    assert_eq!(sample, run("1"));
+    assert_ne!(sample, run("2"));
+}
+
+#[test]
+fn test_check_reports_broken_binds() {
+    let output = fzf_keys()
+        .args(["--niri-config", "tests/niri-broken-config.kdl", "check"])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "tests/niri-broken-config.kdl:3: error: Hyper+Q: Unknown modifier: Hyper",
+            "tests/niri-broken-config.kdl:4: error: Mod+E: bind has no action",
+            "tests/niri-broken-config.kdl:6: warning: Shift+Mod+T: overrides the bind on line 5",
+        ]
+    );
+}
+
+#[test]
+fn test_check_warnings_only_succeeds() {
+    let output = fzf_keys()
+        .args(["--niri-config", "tests/niri-conflicts-config.kdl", "check"])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "tests/niri-conflicts-config.kdl:4: warning: Mod+T: overrides the bind on line 2\n"
+    );
+}
+
+#[test]
+fn test_limit_with_sort_key() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-default-config.kdl",
+            "--sort",
+            "key",
+            "--limit",
+            "3",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    let mut expected = NiriSource::new(PathBuf::from("tests/niri-default-config.kdl"))
+        .discover()
+        .expect("Failed to discover keybinds");
+    keybind::sort_keybinds(&mut expected, SortKey::Key);
+
+    let lines: Vec<String> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    let expected: Vec<String> = expected[..3].iter().map(|kb| kb.to_string()).collect();
+    assert_eq!(lines, expected);
+}
+
+#[test]
+fn test_preview_prints_keybind_details() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-default-config.kdl",
+            "preview",
+            "niri",
+            "1",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    let keybinds = NiriSource::new(PathBuf::from("tests/niri-default-config.kdl"))
+        .discover()
+        .expect("Failed to discover keybinds");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim_end(), format::details(&keybinds[1]));
+    assert!(stdout.contains("Keys:        Mod+T"));
+    assert!(stdout.contains("Program:     niri"));
+}
+
+#[test]
+fn test_tab_format_index_matches_preview() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-default-config.kdl",
+            "--format",
+            "tab",
+            "--query",
+            "alacritty",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let fields: Vec<&str> = stdout.lines().next().unwrap().split('\t').collect();
+
+    assert_eq!(fields[0], "niri");
+    assert_eq!(fields[1], "1");
+}
+
+#[test]
+fn test_action_query() {
+    let run = |extra: &[&str]| {
+        fzf_keys()
+            .args(["--niri-config", "tests/niri-default-config.kdl"])
+            .args(extra)
+            .output()
+            .expect("Failed to run fzf-keys")
+    };
+
+    // Only --query searches descriptions, so "terminal" finds Mod+T's title there
+    assert!(run(&["--query", "terminal"]).status.success());
+    assert_eq!(run(&["--action-query", "terminal"]).status.code(), Some(2));
+
+    let output = run(&[
+        "--action-query",
+        "move-column-to-workspace",
+        "--query",
+        "page",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mod+Ctrl+Page_Down - move-column-to-workspace-down [niri]\n\
+         Mod+Ctrl+Page_Up - move-column-to-workspace-up [niri]\n"
+    );
+}
+
+#[test]
+fn test_conflicts_only() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-conflicts-config.kdl",
+            "--conflicts-only",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        "Mod+T - spawn [\"alacritty\"] (conflicts with spawn [\"foot\"] [niri]) [niri]\n\
+         Mod+T - spawn [\"foot\"] (conflicts with spawn [\"alacritty\"] [niri]) [niri]\n"
+    );
+    assert!(!stdout.contains("close-window"));
+}
+
+#[test]
+fn test_conflicts_only_before_filtering() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-conflicts-config.kdl",
+            "--conflicts-only",
+            "--query",
+            "foot",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    // The alacritty bind is filtered out but still conflicts with foot
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mod+T - spawn [\"foot\"] (conflicts with spawn [\"alacritty\"] [niri]) [niri]\n"
+    );
+}
+
+#[test]
+fn test_doctor_missing_niri_config() {
+    let output = fzf_keys()
+        .args(["doctor", "--niri-config", "tests/no-such-config.kdl"])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("[fail] niri config: tests/no-such-config.kdl\n"));
+    assert!(stdout.contains("hint: pass --niri-config"));
+
+    let output = fzf_keys()
+        .args(["doctor", "--niri-config", "tests/niri-default-config.kdl"])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "[ok]   niri config: tests/niri-default-config.kdl\n"
+    );
+}
+
+#[test]
+fn test_conflicts_subcommand() {
+    let output = fzf_keys()
+        .args([
+            "conflicts",
+            "--niri-config",
+            "tests/niri-conflicts-config.kdl",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mod+T [niri]:\n  Mod+T - spawn \"alacritty\" [niri]\n  Mod+T - spawn \"foot\" [niri]\n"
+    );
+}
+
+#[test]
+fn test_shadowed() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-conflicts-config.kdl",
+            "--shadowed",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mod+T - spawn [\"alacritty\"] [niri]\n"
+    );
+}
+
+#[test]
+fn test_shadowed_before_filtering() {
+    let run = |query: &str| {
+        let output = fzf_keys()
+            .args([
+                "--niri-config",
+                "tests/niri-conflicts-config.kdl",
+                "--shadowed",
+                "--query",
+                query,
+            ])
+            .output()
+            .expect("Failed to run fzf-keys");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // The later Mod+T bind is filtered out but still shadows the first
+    assert_eq!(run("alacritty"), "Mod+T - spawn [\"alacritty\"] [niri]\n");
+    assert_eq!(run("foot"), "");
+}
+
+#[test]
+fn test_ndjson_format() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-conflicts-config.kdl",
+            "--format",
+            "ndjson",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3);
+    for line in lines {
+        let keybind: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(keybind["program"], "niri");
+    }
+}
+
+#[test]
+fn test_json_format_compact_and_pretty() {
+    let run = |extra: &[&str]| {
+        let output = fzf_keys()
+            .args([
+                "--niri-config",
+                "tests/niri-conflicts-config.kdl",
+                "--format",
+                "json",
+            ])
+            .args(extra)
+            .output()
+            .expect("Failed to run fzf-keys");
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let compact = run(&[]);
+    let pretty = run(&["--pretty"]);
+
+    assert!(!compact.trim_end().contains('\n'));
+    assert!(pretty.trim_end().contains('\n'));
+
+    let compact: serde_json::Value = serde_json::from_str(&compact).unwrap();
+    let pretty: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+    assert_eq!(compact, pretty);
+    assert_eq!(compact.as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn test_config_file_defaults() {
+    let config = write_config(
+        "fzf-keys-config-defaults.toml",
+        r#"
+sort = "action"
+
+[sources]
+niri-config = "tests/niri-conflicts-config.kdl"
+
+[filters]
+exclude-programs = ["kitty"]
+"#,
+    );
+
+    let output = fzf_keys()
+        .arg("--config")
+        .arg(&config)
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mod+Q - close-window [niri]\n\
+         Mod+T - spawn [\"alacritty\"] [niri]\n\
+         Mod+T - spawn [\"foot\"] [niri]\n"
+    );
+}
+
+#[test]
+fn test_cli_overrides_config_file() {
+    let config = write_config(
+        "fzf-keys-config-overrides.toml",
+        r#"
+format = "columns"
+sort = "action"
+
+[sources]
+niri-config = "tests/niri-empty-config.kdl"
+kitty = true
+
+[filters]
+with-cooldown-only = true
+respect-overlay-hidden = true
+"#,
+    );
+
+    let output = fzf_keys()
+        .arg("--config")
+        .arg(&config)
+        .args([
+            "--niri-config",
+            "tests/niri-conflicts-config.kdl",
+            "--format",
+            "plain",
+            "--sort",
+            "key",
+            "--kitty=false",
+            "--with-cooldown-only=false",
+            "--respect-overlay-hidden=false",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("kitty"));
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mod+Q - close-window [niri]\n\
+         Mod+T - spawn [\"alacritty\"] [niri]\n\
+         Mod+T - spawn [\"foot\"] [niri]\n"
+    );
+}
+
+#[test]
+fn test_group_by_modifiers() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-default-config.kdl",
+            "--group-by-modifiers",
+            "--query",
+            "column",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let headings: Vec<&str> = stdout
+        .lines()
+        .filter(|line| !line.starts_with("  "))
+        .collect();
+    assert_eq!(headings.first(), Some(&"Mod:"));
+    assert!(headings.contains(&"Mod+Ctrl:"));
+}
+
+#[test]
+fn test_completions_subcommand() {
+    for shell in ["bash", "zsh", "fish"] {
+        let output = fzf_keys()
+            .args(["completions", shell])
+            .output()
+            .expect("Failed to run fzf-keys");
+        assert!(output.status.success());
+
+        // fish spells long flags as `-l niri-config`
+        let script = String::from_utf8(output.stdout).unwrap();
+        assert!(
+            script.contains("niri-config"),
+            "{} script: {}",
+            shell,
+            script
+        );
+        if shell == "bash" {
+            assert!(script.contains("--niri-config"));
+        }
+    }
+}
+
+#[test]
+fn test_group_separator() {
+    let tool = r#"{"modifiers":[],"key":"x","action":"cut","description":null,"program":"tool","repeat":null,"cooldown_ms":null,"allow_when_locked":null,"allow_inhibiting":null}"#;
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-diff-old.kdl",
+            "--source-cmd",
+            &format!("echo '{}'", tool),
+            "--group-by-program",
+            "--group-separator",
+            "%%",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mod+T - spawn [\"alacritty\"] [niri]\n\
+         Mod+Q - close-window [niri]\n\
+         Mod+H - focus-column-left [niri]\n\
+         %%\n\
+         x - cut [tool]\n"
+    );
+}
+
+#[test]
+fn test_sort_within_group() {
+    let tool = r#"{"modifiers":[],"key":"a","action":"cut","description":null,"program":"tool","repeat":null,"cooldown_ms":null,"allow_when_locked":null,"allow_inhibiting":null}"#;
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-diff-old.kdl",
+            "--source-cmd",
+            &format!("echo '{}'", tool),
+            "--group-by-program",
+            "--sort-within-group",
+            "key",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert!(output.status.success());
+    // `a` sorts before niri's keys, but the tool group still comes last
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mod+H - focus-column-left [niri]\n\
+         Mod+Q - close-window [niri]\n\
+         Mod+T - spawn [\"alacritty\"] [niri]\n\
+         a - cut [tool]\n"
+    );
+}
+
+#[test]
+fn test_explain_subcommand() {
+    // Modifiers in another order and a lowercase key still find `Mod+Shift+E`
+    let output = fzf_keys()
+        .args([
+            "explain",
+            "shift+Mod+e",
+            "--niri-config",
+            "tests/niri-default-config.kdl",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Mod+Shift+E - quit [niri]\n"
+    );
+}
+
+#[test]
+fn test_explain_unbound_chord() {
+    let output = fzf_keys()
+        .args([
+            "explain",
+            "Mod+Shift+Q",
+            "--niri-config",
+            "tests/niri-diff-old.kdl",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Mod+Shift+Q is unbound\n"
+    );
+}
+
+#[test]
+fn test_show_mtime() {
+    let output = fzf_keys()
+        .args([
+            "--niri-config",
+            "tests/niri-diff-old.kdl",
+            "--show-mtime",
+            "--format",
+            "ndjson",
+        ])
+        .output()
+        .expect("Failed to run fzf-keys");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.is_empty());
+    assert!(
+        stdout
+            .lines()
+            .all(|line| line.contains(r#""config_mtime":"#))
+    );
+}
+
+#[test]
+fn test_explain_mod_is_super() {
+    let explain = |extra: &[&str]| {
+        fzf_keys()
+            .args([
+                "explain",
+                "Super+Shift+E",
+                "--niri-config",
+                "tests/niri-default-config.kdl",
+            ])
+            .args(extra)
+            .output()
+            .expect("Failed to run fzf-keys")
+    };
+
+    assert_eq!(explain(&[]).status.code(), Some(2));
+
+    let output = explain(&["--mod-is-super"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Super+Shift+E - quit [niri]\n"
+    );
+}
+
+#[test]
+fn test_stats_subcommand() {
+    let output = fzf_keys()
+        .args(["stats", "--niri-config", "tests/niri-diff-old.kdl"])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "modifiers:\n  Mod: 3\n  none: 0\n\
+         most used combinations:\n  Mod: 3\n\
+         unused keys: A B C D E F G I J K L M N O P R S U V W X Y Z 0 1 2 3 4 5 6 7 8 9\n"
+    );
+}
+
+#[test]
+fn test_diff_subcommand() {
+    let output = fzf_keys()
+        .args(["diff", "tests/niri-diff-old.kdl", "tests/niri-diff-new.kdl"])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "+ Mod+L - focus-column-right [niri]\n\
+         - Mod+Q - close-window [niri]\n\
+         ~ Mod+T: spawn \"alacritty\" -> spawn \"foot\" [niri]\n"
+    );
+}
+
+#[test]
+fn test_count_subcommand() {
+    let output = fzf_keys()
+        .args(["--niri-config", "tests/niri-conflicts-config.kdl", "count"])
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "niri: 3\ntotal: 3\n"
+    );
+}
+
+#[test]
+fn test_list_subcommand_matches_default() {
+    let run = |args: &[&str]| {
+        fzf_keys()
+            .args(args)
+            .output()
+            .expect("Failed to run fzf-keys")
+            .stdout
+    };
+
+    let config = "tests/niri-conflicts-config.kdl";
+    assert_eq!(
+        run(&["--niri-config", config, "list", "--sort", "key"]),
+        run(&["--niri-config", config, "--sort", "key"])
+    );
+}
+
+#[test]
+fn test_schema_subcommand() {
+    let output = fzf_keys()
+        .arg("schema")
+        .output()
+        .expect("Failed to run fzf-keys");
+    assert!(output.status.success());
+
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let properties = &schema["properties"];
+    assert_eq!(properties["modifiers"]["type"], "array");
+    assert_eq!(properties["program"]["type"], "string");
+    assert!(schema["$defs"]["Modifier"].is_object());
+}
+
+#[test]
+fn test_no_program_tag() {
+    let run = |extra: &[&str]| {
+        let output = fzf_keys()
+            .args(["--niri-config", "tests/niri-conflicts-config.kdl"])
+            .args(extra)
+            .output()
+            .expect("Failed to run fzf-keys");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let tagged = run(&[]);
+    let untagged = run(&["--no-program-tag"]);
+
+    assert!(tagged.lines().all(|line| line.ends_with(" [niri]")));
+    assert!(untagged.lines().all(|line| !line.contains("[niri]")));
+    assert_eq!(tagged.lines().count(), untagged.lines().count());
+}
+
+#[test]
+fn test_max_action_width_after_merging() {
+    let run = |extra: &[&str]| {
+        let output = fzf_keys()
+            .args([
+                "--niri-config",
+                "tests/niri-default-config.kdl",
+                "--merge-aliases",
+            ])
+            .args(extra)
+            .output()
+            .expect("Failed to run fzf-keys");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let whole = run(&[]);
+    let cut = run(&["--max-action-width", "12"]);
+
+    // focus-column-left and focus-column-right share a prefix but stay apart
+    assert_eq!(whole.lines().count(), cut.lines().count());
+    assert!(cut.contains("Mod+Left / Mod+H"));
+    assert!(!cut.contains("Mod+H / Mod+Right"));
+    assert!(
+        cut.lines()
+            .any(|line| line.contains(" - focus-colum… [niri]"))
+    );
+}
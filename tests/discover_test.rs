@@ -0,0 +1,38 @@
+use fzf_keys::keybind::Program;
+use fzf_keys::{DiscoverOptions, discover_all};
+use std::path::PathBuf;
+
+#[test]
+fn test_discover_all_niri_without_kitty() {
+    let opts = DiscoverOptions {
+        niri: true,
+        niri_configs: vec![PathBuf::from("tests/niri-default-config.kdl")],
+        kitty: false,
+        ..Default::default()
+    };
+
+    let (keybinds, errors) = discover_all(&opts);
+
+    assert!(!keybinds.is_empty(), "Should find niri keybinds");
+    assert!(keybinds.iter().all(|kb| kb.program == Program::Niri));
+    assert!(errors.is_empty(), "Unexpected errors: {:?}", errors);
+}
+
+#[test]
+fn test_discover_all_reports_failing_source() {
+    let opts = DiscoverOptions {
+        niri: true,
+        niri_configs: vec![PathBuf::from("tests/niri-default-config.kdl")],
+        dunst_config: Some(PathBuf::from("tests/does-not-exist-dunstrc")),
+        ..Default::default()
+    };
+
+    let (keybinds, errors) = discover_all(&opts);
+
+    assert!(
+        !keybinds.is_empty(),
+        "A failing source shouldn't stop the others"
+    );
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, "dunst");
+}
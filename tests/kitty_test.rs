@@ -0,0 +1,87 @@
+#![cfg(feature = "kitty-tests")]
+
+use fzf_keys::source::Source;
+use fzf_keys::sources::kitty::KittySource;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_kitty_discovery_from_two_threads() {
+    let (tx, rx) = mpsc::channel();
+
+    for _ in 0..2 {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let result = KittySource::new().discover().map_err(|e| e.to_string());
+            tx.send(result).unwrap();
+        });
+    }
+
+    for _ in 0..2 {
+        let result = rx
+            .recv_timeout(Duration::from_secs(60))
+            .expect("Kitty discovery hung when called from multiple threads");
+        let keybinds = result.expect("Failed to discover kitty keybinds");
+        assert!(!keybinds.is_empty(), "Should find kitty keybinds");
+    }
+}
+
+#[test]
+fn test_kitty_diff_categories() {
+    let keybinds = KittySource::new()
+        .with_diff(true)
+        .discover()
+        .expect("Failed to discover kitty keybinds");
+
+    assert!(!keybinds.is_empty(), "Should find kitty keybinds");
+    for keybind in &keybinds {
+        let description = keybind.description.as_deref().unwrap_or_default();
+        assert!(
+            ["(added)", "(changed)", "(default)"]
+                .iter()
+                .any(|category| description.ends_with(category)),
+            "Missing category on {}",
+            keybind
+        );
+        assert!(
+            description.starts_with(&keybind.action),
+            "Missing action on {}",
+            keybind
+        );
+    }
+}
+
+#[test]
+fn test_kitty_diff_categories_in_mode() {
+    let dir = std::env::temp_dir().join(format!("fzf-keys-kitty-mode-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("kitty.conf"),
+        "map --new-mode resize ctrl+shift+r\n\
+         map --mode resize ctrl+j neighboring_window down\n",
+    )
+    .unwrap();
+    // SAFETY: the other tests here read kitty's config the same way, so they
+    // still hold for this one
+    unsafe { std::env::set_var("KITTY_CONFIG_DIRECTORY", &dir) };
+
+    let keybinds = KittySource::new()
+        .with_diff(true)
+        .discover()
+        .expect("Failed to discover kitty keybinds");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let keybind = keybinds
+        .iter()
+        .find(|keybind| {
+            keybind
+                .description
+                .as_deref()
+                .is_some_and(|description| description.contains("(in resize mode)"))
+        })
+        .expect("Should find the resize mode map");
+    let description = keybind.description.as_deref().unwrap();
+    assert!(description.starts_with(&keybind.action), "{}", description);
+    assert!(description.ends_with(" (added)"), "{}", description);
+}
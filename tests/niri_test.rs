@@ -1,5 +1,7 @@
-use fzf_keys::keybind::Modifier;
+use fzf_keys::error::SourceError;
+use fzf_keys::keybind::{self, Modifier, Program};
 use fzf_keys::source::Source;
+use fzf_keys::sources::kdl_binds::{BindProperty, KdlKeybindSource};
 use fzf_keys::sources::niri::NiriSource;
 use std::path::PathBuf;
 
@@ -19,6 +21,54 @@ fn test_parse_niri_default_config() {
     }
 }
 
+#[test]
+fn test_niri_config_mtime() {
+    let config_path = PathBuf::from("tests/niri-default-config.kdl");
+    let modified = std::fs::metadata(&config_path)
+        .and_then(|metadata| metadata.modified())
+        .unwrap()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let keybinds = NiriSource::new(config_path.clone())
+        .with_mtime(true)
+        .discover()
+        .unwrap();
+    assert!(!keybinds.is_empty());
+    assert!(
+        keybinds
+            .iter()
+            .all(|keybind| keybind.config_mtime == Some(modified))
+    );
+    assert!(keybinds[0].to_string().contains("(modified="));
+
+    // Off by default
+    let keybinds = NiriSource::new(config_path).discover().unwrap();
+    assert!(
+        keybinds
+            .iter()
+            .all(|keybind| keybind.config_mtime.is_none())
+    );
+}
+
+#[test]
+fn test_kdl_source_matches_niri_source() {
+    let config_path = PathBuf::from("tests/niri-default-config.kdl");
+    let generic = KdlKeybindSource::new(config_path.clone(), Program::Niri, "binds")
+        .with_property("hotkey-overlay-title", BindProperty::Title)
+        .with_property("repeat", BindProperty::Repeat)
+        .with_property("cooldown-ms", BindProperty::CooldownMs)
+        .with_property("allow-when-locked", BindProperty::AllowWhenLocked)
+        .with_property("allow-inhibiting", BindProperty::AllowInhibiting);
+
+    let expected = NiriSource::new(config_path).discover().unwrap();
+
+    assert!(!expected.is_empty());
+    assert_eq!(generic.name(), "niri");
+    assert_eq!(generic.discover().unwrap(), expected);
+}
+
 #[test]
 fn test_niri_specific_keybinds() {
     let config_path = PathBuf::from("tests/niri-default-config.kdl");
@@ -28,7 +78,7 @@ fn test_niri_specific_keybinds() {
 
     let hotkey_overlay = keybinds
         .iter()
-        .find(|k| k.key == "Slash" && k.modifiers.contains(&Modifier::Mod));
+        .find(|k| k.key.first_key() == "Slash" && k.key.modifiers().contains(&Modifier::Mod));
 
     assert!(
         hotkey_overlay.is_some(),
@@ -37,7 +87,7 @@ fn test_niri_specific_keybinds() {
 
     let hotkey = hotkey_overlay.unwrap();
     assert_eq!(hotkey.action, "show-hotkey-overlay");
-    assert_eq!(hotkey.program, "niri");
+    assert_eq!(hotkey.program, Program::Niri);
 }
 
 #[test]
@@ -49,7 +99,7 @@ fn test_niri_keybind_with_description() {
 
     let terminal_bind = keybinds
         .iter()
-        .find(|k| k.key == "T" && k.modifiers.contains(&Modifier::Mod));
+        .find(|k| k.key.first_key() == "T" && k.key.modifiers().contains(&Modifier::Mod));
 
     assert!(terminal_bind.is_some(), "Should find Mod+T keybind");
 
@@ -65,7 +115,9 @@ fn test_niri_special_keys() {
 
     let keybinds = source.discover().expect("Failed to parse config");
 
-    let volume_up = keybinds.iter().find(|k| k.key == "XF86AudioRaiseVolume");
+    let volume_up = keybinds
+        .iter()
+        .find(|k| k.key.first_key() == "XF86AudioRaiseVolume");
 
     assert!(
         volume_up.is_some(),
@@ -73,8 +125,8 @@ fn test_niri_special_keys() {
     );
 
     let keybind = volume_up.unwrap();
-    assert_eq!(keybind.modifiers, vec![]);
-    assert_eq!(keybind.program, "niri");
+    assert!(keybind.key.modifiers().is_empty());
+    assert_eq!(keybind.program, Program::Niri);
 }
 
 #[test]
@@ -85,9 +137,9 @@ fn test_niri_multiple_modifiers() {
     let keybinds = source.discover().expect("Failed to parse config");
 
     let multi_mod = keybinds.iter().find(|k| {
-        k.modifiers.contains(&Modifier::Mod)
-            && k.modifiers.contains(&Modifier::Shift)
-            && k.modifiers.contains(&Modifier::Ctrl)
+        k.key.modifiers().contains(&Modifier::Mod)
+            && k.key.modifiers().contains(&Modifier::Shift)
+            && k.key.modifiers().contains(&Modifier::Ctrl)
     });
 
     assert!(multi_mod.is_some(), "Should find keybind with 3+ modifiers");
@@ -102,7 +154,7 @@ fn test_niri_repeat_property() {
 
     let overview_bind = keybinds
         .iter()
-        .find(|k| k.key == "O" && k.modifiers.contains(&Modifier::Mod));
+        .find(|k| k.key.first_key() == "O" && k.key.modifiers().contains(&Modifier::Mod));
 
     assert!(overview_bind.is_some(), "Should find Mod+O keybind");
     assert_eq!(
@@ -120,9 +172,9 @@ fn test_niri_cooldown_property() {
     let keybinds = source.discover().expect("Failed to parse config");
 
     let scroll_bind = keybinds.iter().find(|k| {
-        k.key == "WheelScrollDown"
-            && k.modifiers.contains(&Modifier::Mod)
-            && !k.modifiers.contains(&Modifier::Ctrl)
+        k.key.first_key() == "WheelScrollDown"
+            && k.key.modifiers().contains(&Modifier::Mod)
+            && !k.key.modifiers().contains(&Modifier::Ctrl)
     });
 
     assert!(
@@ -143,7 +195,9 @@ fn test_niri_allow_when_locked_property() {
 
     let keybinds = source.discover().expect("Failed to parse config");
 
-    let audio_bind = keybinds.iter().find(|k| k.key == "XF86AudioRaiseVolume");
+    let audio_bind = keybinds
+        .iter()
+        .find(|k| k.key.first_key() == "XF86AudioRaiseVolume");
 
     assert!(
         audio_bind.is_some(),
@@ -165,7 +219,7 @@ fn test_niri_allow_inhibiting_property() {
 
     let escape_bind = keybinds
         .iter()
-        .find(|k| k.key == "Escape" && k.modifiers.contains(&Modifier::Mod));
+        .find(|k| k.key.first_key() == "Escape" && k.key.modifiers().contains(&Modifier::Mod));
 
     assert!(escape_bind.is_some(), "Should find Mod+Escape keybind");
     assert_eq!(
@@ -174,3 +228,65 @@ fn test_niri_allow_inhibiting_property() {
         "Mod+Escape should have allow-inhibiting=false"
     );
 }
+
+#[test]
+fn test_niri_missing_config_not_found() {
+    let config_path = PathBuf::from("tests/does-not-exist.kdl");
+    let source = NiriSource::new(config_path.clone());
+
+    match source.discover() {
+        Err(SourceError::NotFound(path)) => assert_eq!(path, config_path),
+        other => panic!("Expected SourceError::NotFound, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_niri_switch_events() {
+    let config_path = PathBuf::from("tests/niri-switch-events-config.kdl");
+    let source = NiriSource::new(config_path);
+
+    let keybinds = source.discover().expect("Failed to discover keybinds");
+
+    assert_eq!(
+        keybinds.len(),
+        5,
+        "Expected one bind and four switch events"
+    );
+
+    let lid_close = keybinds
+        .iter()
+        .find(|kb| kb.key.first_key() == "lid-close")
+        .expect("Should have a lid-close switch event");
+    assert_eq!(lid_close.program, Program::Niri);
+    assert!(lid_close.key.modifiers().is_empty());
+    assert_eq!(lid_close.action, "spawn \"swaylock\"");
+
+    for key in ["lid-open", "tablet-mode-on", "tablet-mode-off"] {
+        assert!(
+            keybinds
+                .iter()
+                .any(|kb| kb.key.first_key() == key && kb.program == Program::Niri),
+            "Should have a {} switch event",
+            key
+        );
+    }
+}
+
+#[test]
+fn test_niri_multiple_binds_blocks() {
+    let source = NiriSource::new(PathBuf::from("tests/niri-two-binds-config.kdl"));
+
+    let keybinds = source.discover().expect("Failed to parse config");
+    let keys: Vec<String> = keybinds.iter().map(|kb| kb.keys_string()).collect();
+    assert_eq!(keys, vec!["Mod+T", "Mod+Q", "Mod+H", "Mod+T"]);
+
+    // The second block's Mod+T is the one niri keeps
+    let shadowed = keybind::find_shadowed(&keybinds);
+    assert_eq!(shadowed.len(), 1);
+    assert_eq!(shadowed[0].action, "spawn \"alacritty\"");
+
+    let diagnostics = source.check().expect("Failed to check config");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].line, 18);
+    assert_eq!(diagnostics[0].message, "overrides the bind on line 4");
+}